@@ -0,0 +1,82 @@
+//! Derive macros for deadpool-oracle
+//!
+//! Use these through the `derive` feature of `deadpool-oracle` rather than
+//! depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// Derive `deadpool_oracle::FromRow` for a struct with named fields
+///
+/// Each field is read from the column with the same name (case-insensitive),
+/// without the `r#` of raw identifiers, and converted with `FromValue`. Use `#[oracle(rename = "COLUMN")]` to read
+/// a field from a differently named column.
+#[proc_macro_derive(FromRow, attributes(oracle))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "FromRow can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "FromRow can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut initializers = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let mut column = ident.unraw().to_string();
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("oracle"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    column = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported oracle attribute"))
+                }
+            })?;
+        }
+
+        initializers.push(quote! {
+            #ident: ::deadpool_oracle::column(row, #column)?
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::deadpool_oracle::FromRow for #name #ty_generics #where_clause {
+            fn from_row(
+                row: &::deadpool_oracle::oracle_rs::Row,
+            ) -> ::std::result::Result<Self, ::deadpool_oracle::oracle_rs::Error> {
+                ::std::result::Result::Ok(Self {
+                    #(#initializers,)*
+                })
+            }
+        }
+    })
+}
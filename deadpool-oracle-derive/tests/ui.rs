@@ -0,0 +1,9 @@
+//! What `#[derive(FromRow)]` accepts and rejects, as seen by the compiler
+
+#[test]
+fn test_ui() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/rename.rs");
+    cases.compile_fail("tests/ui/tuple_struct.rs");
+    cases.compile_fail("tests/ui/unknown_attribute.rs");
+}
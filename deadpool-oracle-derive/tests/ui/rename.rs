@@ -0,0 +1,19 @@
+use deadpool_oracle::oracle_rs::{Row, Value};
+use deadpool_oracle::FromRow;
+
+#[derive(FromRow)]
+struct Document {
+    #[oracle(rename = "DOC_ID")]
+    id: i64,
+    r#type: String,
+}
+
+fn main() {
+    let row = Row::with_names(
+        vec![Value::Integer(7), Value::String("invoice".to_string())],
+        vec!["DOC_ID".to_string(), "TYPE".to_string()],
+    );
+    let document = Document::from_row(&row).unwrap();
+    assert_eq!(document.id, 7);
+    assert_eq!(document.r#type, "invoice");
+}
@@ -0,0 +1,6 @@
+use deadpool_oracle::FromRow;
+
+#[derive(FromRow)]
+struct Pair(i64, String);
+
+fn main() {}
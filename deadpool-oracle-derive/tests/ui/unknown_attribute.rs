@@ -0,0 +1,9 @@
+use deadpool_oracle::FromRow;
+
+#[derive(FromRow)]
+struct Employee {
+    #[oracle(column = "LAST_NAME")]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,124 @@
+//! Extractors for actix-web handlers
+//!
+//! [`DbConn`] and [`DbTransaction`] take the [`Pool`] from the app data,
+//! registered either as `web::Data<Pool>`, see [`Pool::app_data()`], or
+//! as a plain `Pool`.
+
+use crate::{DbConn, DbTransaction, Pool, PoolRejection};
+use actix_web::dev::Payload;
+use actix_web::http::StatusCode;
+use actix_web::{error, web, FromRequest, HttpRequest, ResponseError};
+use std::future::Future;
+use std::pin::Pin;
+
+type ExtractFuture<T> = Pin<Box<dyn Future<Output = Result<T, actix_web::Error>>>>;
+
+impl Pool {
+    /// This pool as app data of an actix-web `App`
+    ///
+    /// ```rust,no_run
+    /// use actix_web::{web, App, HttpServer, Responder};
+    /// use deadpool_oracle::{DbConn, Pool};
+    ///
+    /// async fn user_count(conn: DbConn) -> impl Responder {
+    ///     match conn.query("SELECT COUNT(*) FROM users", &[]).await {
+    ///         Ok(result) => format!("{:?}", result.rows[0].get_i64(0)),
+    ///         Err(e) => e.to_string(),
+    ///     }
+    /// }
+    ///
+    /// # async fn example(pool: Pool) -> std::io::Result<()> {
+    /// HttpServer::new(move || {
+    ///     App::new()
+    ///         .app_data(pool.app_data())
+    ///         .route("/users/count", web::get().to(user_count))
+    /// })
+    /// .bind(("127.0.0.1", 8080))?
+    /// .run()
+    /// .await
+    /// # }
+    /// ```
+    pub fn app_data(&self) -> web::Data<Pool> {
+        web::Data::new(self.clone())
+    }
+
+    /// Pool registered as app data of the app serving `req`
+    ///
+    /// For handlers and middleware that need the pool itself rather than a
+    /// connection.
+    pub fn from_app_data(req: &HttpRequest) -> Option<&Pool> {
+        let data = req.app_data::<web::Data<Pool>>().map(|data| data.get_ref());
+        data.or_else(|| req.app_data::<Pool>())
+    }
+}
+
+/// Pool of the app serving `req`, or an internal server error if there is none
+fn pool_of(req: &HttpRequest) -> Result<Pool, actix_web::Error> {
+    Pool::from_app_data(req)
+        .cloned()
+        .ok_or_else(|| error::ErrorInternalServerError("no deadpool_oracle::Pool in app data"))
+}
+
+impl FromRequest for DbConn {
+    type Error = actix_web::Error;
+    type Future = ExtractFuture<Self>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let pool = pool_of(req);
+        Box::pin(async move { Ok(DbConn::checkout(&pool?).await?) })
+    }
+}
+
+impl FromRequest for DbTransaction {
+    type Error = actix_web::Error;
+    type Future = ExtractFuture<Self>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let pool = pool_of(req);
+        Box::pin(async move { Ok(DbTransaction::begin(&pool?).await?) })
+    }
+}
+
+impl ResponseError for PoolRejection {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::tests::unreachable_pool;
+    use actix_web::test::TestRequest;
+
+    #[tokio::test]
+    async fn test_unavailable_pool_rejected_with_503() {
+        let pool = unreachable_pool();
+        let req = TestRequest::default()
+            .app_data(pool.app_data())
+            .to_http_request();
+        assert!(Pool::from_app_data(&req).is_some());
+
+        let Err(error) = DbConn::extract(&req).await else {
+            panic!("checkout should fail");
+        };
+        assert_eq!(
+            error.as_response_error().status_code(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert!(DbTransaction::extract(&req).await.is_err());
+
+        // A plain Pool works as app data too
+        let req = TestRequest::default().app_data(pool).to_http_request();
+        assert!(Pool::from_app_data(&req).is_some());
+
+        let req = TestRequest::default().to_http_request();
+        let Err(error) = DbConn::extract(&req).await else {
+            panic!("there is no pool");
+        };
+        assert_eq!(
+            error.as_response_error().status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
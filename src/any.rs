@@ -0,0 +1,205 @@
+//! Backend-agnostic pool and connection traits
+//!
+//! Applications that run on Oracle as well as on another database can
+//! write their data access against [`AnyPool`] and [`AnyConnection`] and
+//! pick the backend at startup, or use an in-memory one in tests. Both
+//! traits are object safe, so the pool can be held as
+//! `Arc<dyn AnyPool>`. Values cross the interface as [`AnyValue`], which
+//! covers the types every SQL database has.
+
+use crate::{Object, Pool};
+use deadpool::Status;
+use oracle_rs::{QueryResult, Value};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Error of a backend
+pub type AnyError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Future returned by the methods of [`AnyPool`] and [`AnyConnection`]
+pub type AnyFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, AnyError>> + Send + 'a>>;
+
+/// A connection pool of any backend
+pub trait AnyPool: Send + Sync {
+    /// Check out a connection
+    fn get(&self) -> AnyFuture<'_, Box<dyn AnyConnection>>;
+
+    /// Current size and availability of the pool
+    fn status(&self) -> Status;
+
+    /// Close the pool; checkouts fail from now on
+    fn close(&self);
+}
+
+/// A checked-out connection of any backend
+///
+/// Returned to its pool when dropped. Whether uncommitted work is then
+/// committed or rolled back depends on the backend and its configuration,
+/// so portable code commits or rolls back explicitly.
+pub trait AnyConnection: Send {
+    /// Execute a statement, returning the number of rows affected
+    fn execute<'a>(&'a self, sql: &'a str, params: &'a [AnyValue]) -> AnyFuture<'a, u64>;
+
+    /// Run a query and fetch all of its rows
+    fn query<'a>(&'a self, sql: &'a str, params: &'a [AnyValue]) -> AnyFuture<'a, AnyRows>;
+
+    /// Commit the current transaction
+    fn commit(&self) -> AnyFuture<'_, ()>;
+
+    /// Roll back the current transaction
+    fn rollback(&self) -> AnyFuture<'_, ()>;
+}
+
+/// A bind or column value in a backend-agnostic form
+///
+/// Oracle NUMBERs come as `Int` if they fit an `i64` and as their decimal
+/// text otherwise, to keep full precision. Dates, timestamps and the other
+/// Oracle-specific types come as text.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnyValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<AnyValue> for Value {
+    fn from(value: AnyValue) -> Self {
+        match value {
+            AnyValue::Null => Value::Null,
+            AnyValue::Bool(b) => Value::Boolean(b),
+            AnyValue::Int(n) => Value::Integer(n),
+            AnyValue::Float(f) => Value::Float(f),
+            AnyValue::Text(s) => Value::String(s),
+            AnyValue::Bytes(b) => Value::Bytes(b),
+        }
+    }
+}
+
+impl From<Value> for AnyValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => AnyValue::Null,
+            Value::Boolean(b) => AnyValue::Bool(b),
+            Value::Integer(n) => AnyValue::Int(n),
+            Value::Float(f) => AnyValue::Float(f),
+            Value::String(s) => AnyValue::Text(s),
+            Value::Bytes(b) => AnyValue::Bytes(b),
+            Value::Number(n) => match n.to_i64() {
+                Ok(i) if n.is_integer => AnyValue::Int(i),
+                _ => AnyValue::Text(n.as_str().to_string()),
+            },
+            other => AnyValue::Text(other.to_string()),
+        }
+    }
+}
+
+/// Rows returned by [`AnyConnection::query()`]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnyRows {
+    /// Column names, in select list order
+    pub columns: Vec<String>,
+    /// Values of each row, in column order
+    pub rows: Vec<Vec<AnyValue>>,
+}
+
+impl From<QueryResult> for AnyRows {
+    fn from(result: QueryResult) -> Self {
+        Self {
+            columns: result.columns.into_iter().map(|c| c.name).collect(),
+            rows: result
+                .rows
+                .into_iter()
+                .map(|row| row.into_values().into_iter().map(AnyValue::from).collect())
+                .collect(),
+        }
+    }
+}
+
+fn binds(params: &[AnyValue]) -> Vec<Value> {
+    params.iter().cloned().map(Value::from).collect()
+}
+
+impl AnyPool for Pool {
+    fn get(&self) -> AnyFuture<'_, Box<dyn AnyConnection>> {
+        Box::pin(async move {
+            let conn = Pool::get(self).await?;
+            Ok(Box::new(conn) as Box<dyn AnyConnection>)
+        })
+    }
+
+    fn status(&self) -> Status {
+        Pool::status(self)
+    }
+
+    fn close(&self) {
+        Pool::close(self)
+    }
+}
+
+impl AnyConnection for Object {
+    fn execute<'a>(&'a self, sql: &'a str, params: &'a [AnyValue]) -> AnyFuture<'a, u64> {
+        Box::pin(async move {
+            let result = Object::execute(self, sql, &binds(params)).await?;
+            Ok(result.rows_affected)
+        })
+    }
+
+    fn query<'a>(&'a self, sql: &'a str, params: &'a [AnyValue]) -> AnyFuture<'a, AnyRows> {
+        Box::pin(async move {
+            let result = self.query_all(sql, &binds(params)).await?;
+            Ok(result.into())
+        })
+    }
+
+    fn commit(&self) -> AnyFuture<'_, ()> {
+        Box::pin(async move { Ok(Object::commit(self).await?) })
+    }
+
+    fn rollback(&self) -> AnyFuture<'_, ()> {
+        Box::pin(async move { Ok(Object::rollback(self).await?) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PoolBuilder;
+    use oracle_rs::Config;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_any_value_conversion() {
+        let values = [
+            AnyValue::Null,
+            AnyValue::Bool(true),
+            AnyValue::Int(42),
+            AnyValue::Float(1.5),
+            AnyValue::Text("text".to_string()),
+            AnyValue::Bytes(vec![1, 2]),
+        ];
+        for value in values {
+            assert_eq!(AnyValue::from(Value::from(value.clone())), value);
+        }
+
+        let number = |s: &str| Value::Number(oracle_rs::types::OracleNumber::new(s));
+        assert_eq!(AnyValue::from(number("12")), AnyValue::Int(12));
+        assert_eq!(
+            AnyValue::from(number("3.14159265358979323846")),
+            AnyValue::Text("3.14159265358979323846".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pool_as_any_pool() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "user", "password");
+        let pool = PoolBuilder::new(config).max_size(3).build().unwrap();
+        let pool: Arc<dyn AnyPool> = Arc::new(pool);
+        assert_eq!(pool.status().max_size, 3);
+        pool.close();
+    }
+}
@@ -0,0 +1,168 @@
+//! Module and action of pooled sessions
+//!
+//! `DBMS_APPLICATION_INFO` labels a session with the module and action it
+//! is working on, which show up in `V$SESSION.MODULE` and `ACTION`, ASH and
+//! AWR reports, so DBAs can attribute database load to application
+//! endpoints. [`Object::set_context()`](crate::Object::set_context) sets
+//! them; with the `tracing` feature the pool can also take them from the
+//! span a connection is checked out in, see
+//! [`PoolBuilder::context_from_span()`](crate::PoolBuilder::context_from_span).
+//! The labels stay with the session when it goes back to the pool, so the
+//! ones last set are remembered to skip the round trip when they don't
+//! change.
+//!
+//! The client identifier, set with `DBMS_SESSION.SET_IDENTIFIER`, names the
+//! end user or request a session works for, in `V$SESSION`, ASH and the
+//! audit trail. Unlike module and action it belongs to a single checkout:
+//! the manager clears it when the connection is recycled.
+
+use oracle_rs::{Connection, Error, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const SET_MODULE_SQL: &str = "BEGIN DBMS_APPLICATION_INFO.SET_MODULE(:1, :2); END;";
+const SET_IDENTIFIER_SQL: &str = "BEGIN DBMS_SESSION.SET_IDENTIFIER(:1); END;";
+const CLEAR_IDENTIFIER_SQL: &str = "BEGIN DBMS_SESSION.CLEAR_IDENTIFIER; END;";
+
+/// Module and action set on a session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AppInfo {
+    pub(crate) module: String,
+    pub(crate) action: String,
+}
+
+impl AppInfo {
+    pub(crate) fn new(module: &str, action: &str) -> Self {
+        Self {
+            module: module.to_string(),
+            action: action.to_string(),
+        }
+    }
+
+    /// Module and action of the span the caller is in, if it is enabled
+    ///
+    /// The module is the span's target, usually the Rust module it was
+    /// created in, and the action is its name.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn from_current_span() -> Option<Self> {
+        let span = tracing::Span::current();
+        let metadata = span.metadata()?;
+        Some(Self::new(metadata.target(), metadata.name()))
+    }
+}
+
+/// Module and action of the sessions of a pool, and their client
+/// identifiers, by connection id
+#[derive(Debug, Default)]
+pub(crate) struct AppInfos {
+    sessions: Mutex<HashMap<u32, AppInfo>>,
+    identifiers: Mutex<HashMap<u32, String>>,
+}
+
+impl AppInfos {
+    pub(crate) fn get(&self, connection_id: u32) -> Option<AppInfo> {
+        self.sessions.lock().unwrap().get(&connection_id).cloned()
+    }
+
+    pub(crate) fn set(&self, connection_id: u32, info: Option<AppInfo>) {
+        let mut sessions = self.sessions.lock().unwrap();
+        match info {
+            Some(info) => sessions.insert(connection_id, info),
+            None => sessions.remove(&connection_id),
+        };
+    }
+
+    /// Whether a session has a client identifier to clear
+    pub(crate) fn is_identified(&self, connection_id: u32) -> bool {
+        self.identifiers
+            .lock()
+            .unwrap()
+            .contains_key(&connection_id)
+    }
+
+    /// Client identifier of a session, if one was set
+    pub(crate) fn identifier(&self, connection_id: u32) -> Option<String> {
+        self.identifiers
+            .lock()
+            .unwrap()
+            .get(&connection_id)
+            .cloned()
+    }
+
+    pub(crate) fn set_identifier(&self, connection_id: u32, identifier: Option<&str>) {
+        let mut identifiers = self.identifiers.lock().unwrap();
+        match identifier {
+            Some(identifier) => identifiers.insert(connection_id, identifier.to_string()),
+            None => identifiers.remove(&connection_id),
+        };
+    }
+
+    /// Forget a connection that left the pool
+    pub(crate) fn remove(&self, connection_id: u32) {
+        self.set(connection_id, None);
+        self.set_identifier(connection_id, None);
+    }
+}
+
+/// Set the module and action of a session, or clear them with `None`
+pub(crate) async fn set_module(conn: &Connection, info: Option<&AppInfo>) -> Result<(), Error> {
+    let params = match info {
+        Some(info) => [
+            Value::String(info.module.clone()),
+            Value::String(info.action.clone()),
+        ],
+        None => [Value::Null, Value::Null],
+    };
+    conn.execute(SET_MODULE_SQL, &params).await?;
+    Ok(())
+}
+
+/// Set the client identifier of a session
+pub(crate) async fn set_identifier(conn: &Connection, identifier: &str) -> Result<(), Error> {
+    let params = [Value::String(identifier.to_string())];
+    conn.execute(SET_IDENTIFIER_SQL, &params).await?;
+    Ok(())
+}
+
+/// Clear the client identifier of a session
+pub(crate) async fn clear_identifier(conn: &Connection) -> Result<(), Error> {
+    conn.execute(CLEAR_IDENTIFIER_SQL, &[]).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_infos() {
+        let infos = AppInfos::default();
+        assert_eq!(infos.get(1), None);
+
+        infos.set(1, Some(AppInfo::new("orders", "create")));
+        infos.set(2, Some(AppInfo::new("orders", "list")));
+        assert_eq!(infos.get(1), Some(AppInfo::new("orders", "create")));
+
+        infos.set(1, None);
+        assert_eq!(infos.get(1), None);
+        infos.remove(2);
+        assert_eq!(infos.get(2), None);
+
+        infos.set_identifier(1, Some("alice"));
+        assert!(infos.is_identified(1));
+        assert_eq!(infos.identifier(1).as_deref(), Some("alice"));
+        assert!(!infos.is_identified(2));
+        infos.remove(1);
+        assert!(!infos.is_identified(1));
+        assert_eq!(infos.identifier(1), None);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_no_span() {
+        // Spans are disabled without a subscriber
+        let span = tracing::info_span!("create_order");
+        let _entered = span.enter();
+        assert_eq!(AppInfo::from_current_span(), None);
+    }
+}
@@ -0,0 +1,158 @@
+//! SQL audit trail
+//!
+//! An [`AuditSink`] registered with
+//! [`PoolBuilder::audit_sink()`](crate::PoolBuilder::audit_sink) receives
+//! every statement run through `Object` and `Transaction`, with the
+//! connection it ran on and the client identifier set for the checkout,
+//! so security teams can forward them to a SIEM without touching each call
+//! site. Bind values are redacted to their type before the sink sees them.
+
+use oracle_rs::{BindDirection, BindParam, Error, Value};
+use std::time::SystemTime;
+
+/// A statement run on a pooled connection, for the audit trail
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct AuditEvent<'a> {
+    /// When the statement completed
+    pub timestamp: SystemTime,
+    /// Id of the connection the statement ran on
+    pub connection_id: u32,
+    /// Client identifier of the checkout, see `Object::set_client_identifier()`
+    pub client_identifier: Option<&'a str>,
+    /// SQL text as sent, with placeholders instead of bind values
+    pub sql: &'a str,
+    /// Type of each bind value in order, across all rows for array DML,
+    /// such as `VARCHAR2` or `NULL`, and `OUT` for out binds
+    ///
+    /// Empty for `Transaction::execute_batch()`, whose values the driver
+    /// doesn't expose; `bind_count` still has their number.
+    pub binds: &'a [&'static str],
+    /// Number of bind values
+    pub bind_count: usize,
+    /// Error the statement failed with
+    pub error: Option<&'a Error>,
+}
+
+/// Receives every statement run on the connections of a pool
+///
+/// Called on the task that ran the statement, right after it completed, so
+/// implementations should hand events off to a channel or buffer rather
+/// than send them over the network themselves. Statements the pool runs on
+/// its own, like health checks and session setup, are not included.
+/// Implemented for closures taking an [`AuditEvent`].
+pub trait AuditSink: Send + Sync + 'static {
+    /// Called once per statement
+    fn record(&self, event: &AuditEvent<'_>);
+}
+
+impl<F> AuditSink for F
+where
+    F: Fn(&AuditEvent<'_>) + Send + Sync + 'static,
+{
+    fn record(&self, event: &AuditEvent<'_>) {
+        self(event)
+    }
+}
+
+/// Bind values of a statement run through `Object::observe()`
+#[derive(Clone, Copy)]
+pub(crate) enum Binds<'a> {
+    Values(&'a [Value]),
+    /// Rows of array DML, each followed by `returning` out binds
+    Rows {
+        rows: &'a [&'a [Value]],
+        returning: usize,
+    },
+    Params(&'a [BindParam]),
+    /// Values not known, only their number
+    Count(usize),
+}
+
+impl Binds<'_> {
+    pub(crate) fn count(&self) -> usize {
+        match self {
+            Binds::Values(values) => values.len(),
+            Binds::Rows { rows, returning } => rows.iter().map(|row| row.len() + returning).sum(),
+            Binds::Params(params) => params.len(),
+            Binds::Count(count) => *count,
+        }
+    }
+
+    /// The type of each value, without the value
+    pub(crate) fn redacted(&self) -> Vec<&'static str> {
+        match self {
+            Binds::Values(values) => values.iter().map(value_type).collect(),
+            Binds::Rows { rows, returning } => rows
+                .iter()
+                .flat_map(|row| {
+                    let values = row.iter().map(value_type);
+                    values.chain(std::iter::repeat("OUT").take(*returning))
+                })
+                .collect(),
+            Binds::Params(params) => params.iter().map(param_type).collect(),
+            Binds::Count(_) => Vec::new(),
+        }
+    }
+}
+
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "NULL",
+        Value::String(_) => "VARCHAR2",
+        Value::Bytes(_) => "RAW",
+        Value::Integer(_) | Value::Float(_) | Value::Number(_) => "NUMBER",
+        Value::Date(_) => "DATE",
+        Value::Timestamp(_) => "TIMESTAMP",
+        Value::RowId(_) => "ROWID",
+        Value::Boolean(_) => "BOOLEAN",
+        Value::Lob(_) => "LOB",
+        Value::Json(_) => "JSON",
+        Value::Vector(_) => "VECTOR",
+        Value::Cursor(_) => "CURSOR",
+        Value::Collection(_) => "COLLECTION",
+    }
+}
+
+fn param_type(param: &BindParam) -> &'static str {
+    match (&param.direction, &param.value) {
+        (BindDirection::Output, _) | (_, None) => "OUT",
+        (_, Some(value)) => value_type(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_rs::OracleType;
+
+    #[test]
+    fn test_redacted_binds() {
+        let values = [
+            Value::String("4111 1111 1111 1111".to_string()),
+            Value::Integer(42),
+            Value::Null,
+        ];
+        let binds = Binds::Values(&values);
+        assert_eq!(binds.count(), 3);
+        assert_eq!(binds.redacted(), ["VARCHAR2", "NUMBER", "NULL"]);
+
+        let row: &[Value] = &[Value::Boolean(true)];
+        let binds = Binds::Rows {
+            rows: &[row, row],
+            returning: 1,
+        };
+        assert_eq!(binds.count(), 4);
+        assert_eq!(binds.redacted(), ["BOOLEAN", "OUT", "BOOLEAN", "OUT"]);
+
+        let params = [
+            BindParam::input(Value::Bytes(vec![1, 2])),
+            BindParam::output(OracleType::Number, 22),
+        ];
+        assert_eq!(Binds::Params(&params).redacted(), ["RAW", "OUT"]);
+
+        let binds = Binds::Count(6);
+        assert_eq!(binds.count(), 6);
+        assert!(binds.redacted().is_empty());
+    }
+}
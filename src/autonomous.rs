@@ -0,0 +1,93 @@
+//! Autonomous transactions
+//!
+//! Statements are collected into an anonymous PL/SQL block declared with
+//! `PRAGMA AUTONOMOUS_TRANSACTION`, so they commit independently of the
+//! transaction open on the connection. Each statement runs through
+//! `EXECUTE IMMEDIATE ... USING`, which keeps its binds positional.
+
+use oracle_rs::{BindParam, Connection, Error, Value};
+
+/// Statements to run in an autonomous transaction
+///
+/// Filled in by the closure passed to
+/// [`Object::autonomous()`](crate::Object::autonomous) or
+/// [`Transaction::autonomous()`](crate::Transaction::autonomous).
+#[derive(Debug, Default)]
+pub struct AutonomousBlock {
+    statements: Vec<(String, Vec<Value>)>,
+}
+
+impl AutonomousBlock {
+    /// Add a SQL statement with positional binds (`:1`, `:2`, ...)
+    ///
+    /// Statements run in the order they were added. PL/SQL blocks are
+    /// accepted as well.
+    pub fn execute(&mut self, sql: impl Into<String>, params: &[Value]) -> &mut Self {
+        self.statements.push((sql.into(), params.to_vec()));
+        self
+    }
+
+    /// Build the PL/SQL block and its binds
+    fn to_plsql(&self) -> (String, Vec<BindParam>) {
+        let mut sql = String::from("DECLARE PRAGMA AUTONOMOUS_TRANSACTION; BEGIN ");
+        let mut binds = Vec::new();
+
+        for (statement, params) in &self.statements {
+            binds.push(BindParam::input(Value::String(statement.clone())));
+            sql.push_str(&format!("EXECUTE IMMEDIATE :{}", binds.len()));
+
+            for (i, param) in params.iter().enumerate() {
+                binds.push(BindParam::input(param.clone()));
+                let separator = if i == 0 { " USING " } else { ", " };
+                sql.push_str(&format!("{}:{}", separator, binds.len()));
+            }
+            sql.push_str("; ");
+        }
+
+        // An autonomous block must end its transaction before returning
+        sql.push_str("COMMIT; EXCEPTION WHEN OTHERS THEN ROLLBACK; RAISE; END;");
+        (sql, binds)
+    }
+}
+
+/// Run the statements collected by `f` in an autonomous transaction
+pub(crate) async fn run<F>(conn: &Connection, f: F) -> Result<(), Error>
+where
+    F: FnOnce(&mut AutonomousBlock),
+{
+    let mut block = AutonomousBlock::default();
+    f(&mut block);
+    if block.statements.is_empty() {
+        return Ok(());
+    }
+
+    let (sql, binds) = block.to_plsql();
+    conn.execute_plsql(&sql, &binds).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autonomous_block_sql() {
+        let mut block = AutonomousBlock::default();
+        block
+            .execute(
+                "INSERT INTO audit_log (msg, user_id) VALUES (:1, :2)",
+                &[Value::String("login".to_string()), Value::Integer(42)],
+            )
+            .execute("DELETE FROM audit_log WHERE created < SYSDATE - 90", &[]);
+
+        let (sql, binds) = block.to_plsql();
+        assert_eq!(
+            sql,
+            "DECLARE PRAGMA AUTONOMOUS_TRANSACTION; BEGIN \
+             EXECUTE IMMEDIATE :1 USING :2, :3; \
+             EXECUTE IMMEDIATE :4; \
+             COMMIT; EXCEPTION WHEN OTHERS THEN ROLLBACK; RAISE; END;"
+        );
+        assert_eq!(binds.len(), 4);
+    }
+}
@@ -0,0 +1,121 @@
+//! Credentials from AWS Secrets Manager
+//!
+//! [`AwsSecretsManagerProvider`] reads the user name and password of a pool
+//! from a JSON secret, in the format Secrets Manager uses for database
+//! credentials, with a client the application configured already.
+
+use crate::credentials::{CredentialProvider, Credentials, CredentialsFuture};
+use aws_sdk_secretsmanager::error::DisplayErrorContext;
+use aws_sdk_secretsmanager::Client;
+use oracle_rs::Error;
+use std::fmt;
+
+/// Credential provider reading a secret from AWS Secrets Manager
+///
+/// The secret is a JSON object with `username` and `password` fields, as
+/// stored by the Secrets Manager rotation functions for databases. Secrets
+/// rotated on a schedule are picked up with
+/// `PoolBuilder::credential_refresh()`, or on the first login the old
+/// password fails.
+///
+/// ```rust,no_run
+/// # fn example(
+/// #     config: oracle_rs::Config,
+/// #     sdk_config: aws_sdk_secretsmanager::config::Config,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// use deadpool_oracle::{AwsSecretsManagerProvider, PoolBuilder};
+///
+/// // sdk_config usually comes from aws_config::load_from_env()
+/// let client = aws_sdk_secretsmanager::Client::from_conf(sdk_config);
+/// let pool = PoolBuilder::new(config)
+///     .credential_provider(AwsSecretsManagerProvider::new(client, "prod/orders/oracle"))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AwsSecretsManagerProvider {
+    client: Client,
+    secret_id: String,
+    username_key: String,
+    password_key: String,
+}
+
+impl AwsSecretsManagerProvider {
+    /// Read the secret with the given name or ARN
+    pub fn new(client: Client, secret_id: &str) -> Self {
+        Self {
+            client,
+            secret_id: secret_id.to_string(),
+            username_key: "username".to_string(),
+            password_key: "password".to_string(),
+        }
+    }
+
+    /// Set the fields of the secret holding the user name and password
+    ///
+    /// Default is `username` and `password`.
+    pub fn keys(mut self, username: &str, password: &str) -> Self {
+        self.username_key = username.to_string();
+        self.password_key = password.to_string();
+        self
+    }
+
+    async fn read(&self) -> Result<Credentials, Error> {
+        let request = self.client.get_secret_value().secret_id(&self.secret_id);
+        let output = match request.send().await {
+            Ok(output) => output,
+            Err(e) => return Err(self.error(DisplayErrorContext(e))),
+        };
+        let Some(secret) = output.secret_string() else {
+            return Err(self.error("it holds no string"));
+        };
+        parse_secret(secret, &self.username_key, &self.password_key)
+            .map_err(|reason| self.error(reason))
+    }
+
+    fn error(&self, reason: impl fmt::Display) -> Error {
+        Error::AuthenticationFailed(format!("AWS secret {}: {}", self.secret_id, reason))
+    }
+}
+
+impl CredentialProvider for AwsSecretsManagerProvider {
+    fn credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(self.read())
+    }
+}
+
+/// Take the user name and password from the JSON of a secret
+///
+/// The error never includes the secret, as it may be close enough to valid
+/// JSON to hold the password.
+fn parse_secret(secret: &str, username: &str, password: &str) -> Result<Credentials, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(secret).map_err(|_| "it is not a JSON object".to_string())?;
+    let field = |key: &str| {
+        value
+            .get(key)
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| format!("it has no string field {}", key))
+    };
+    Ok(Credentials::new(field(username)?, field(password)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secret() {
+        let secret = r#"{"engine": "oracle", "username": "app", "password": "s3cret"}"#;
+        let credentials = parse_secret(secret, "username", "password").unwrap();
+        assert_eq!(credentials, Credentials::new("app", "s3cret"));
+
+        let secret = r#"{"user": "app", "pass": "s3cret"}"#;
+        assert!(parse_secret(secret, "username", "password").is_err());
+        assert!(parse_secret(secret, "user", "pass").is_ok());
+
+        let err = parse_secret("s3cret", "username", "password").unwrap_err();
+        assert!(!err.contains("s3cret"));
+    }
+}
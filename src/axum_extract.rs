@@ -0,0 +1,65 @@
+//! Extractors for axum handlers
+//!
+//! [`DbConn`] and [`DbTransaction`] take the [`Pool`] from the router
+//! state, which is either the `Pool` itself or a state type implementing
+//! `FromRef` for it.
+
+use crate::{DbConn, DbTransaction, Pool, PoolRejection};
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+impl<S> FromRequestParts<S> for DbConn
+where
+    Pool: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = PoolRejection;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        DbConn::checkout(&Pool::from_ref(state)).await
+    }
+}
+
+impl<S> FromRequestParts<S> for DbTransaction
+where
+    Pool: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = PoolRejection;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        DbTransaction::begin(&Pool::from_ref(state)).await
+    }
+}
+
+impl IntoResponse for PoolRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::SERVICE_UNAVAILABLE, self.to_string()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::tests::unreachable_pool;
+    use axum::http::Request;
+    use deadpool::managed;
+
+    #[tokio::test]
+    async fn test_unavailable_pool_rejected_with_503() {
+        let pool = unreachable_pool();
+        let (mut parts, ()) = Request::new(()).into_parts();
+
+        let Err(rejection) = DbConn::from_request_parts(&mut parts, &pool).await else {
+            panic!("checkout should fail");
+        };
+        assert!(matches!(rejection.0, managed::PoolError::Backend(_)));
+        let response = rejection.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let result = DbTransaction::from_request_parts(&mut parts, &pool).await;
+        assert!(result.is_err());
+    }
+}
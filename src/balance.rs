@@ -0,0 +1,131 @@
+//! Weighted load balancing across endpoints
+//!
+//! Without weights, new connections stick to the endpoint that last
+//! accepted one and only move on when it fails. With weights, every new
+//! connection picks an endpoint by smooth weighted round-robin, so RAC
+//! nodes get their share of the pool in proportion to their weights. An
+//! endpoint that refused a connection is left out for a cool-down period;
+//! the others are still tried in turn if the chosen one fails.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long an endpoint that refused a connection is left out
+const ENDPOINT_COOL_DOWN: Duration = Duration::from_secs(10);
+
+/// Weights and health of the endpoints of a pool
+#[derive(Debug)]
+pub(crate) struct EndpointBalancer {
+    weights: Vec<u32>,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Running scores of the smooth weighted round-robin
+    current: Vec<i64>,
+    down_until: Vec<Option<Instant>>,
+}
+
+impl EndpointBalancer {
+    pub(crate) fn new(weights: Vec<u32>) -> Self {
+        Self {
+            weights,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Weight of an endpoint, 1 if none was given
+    fn weight(&self, index: usize) -> i64 {
+        i64::from(self.weights.get(index).copied().unwrap_or(1))
+    }
+
+    /// Order in which to try `count` endpoints for the next connection
+    ///
+    /// The first is picked by weight among the healthy endpoints with a
+    /// weight above 0. The rest follow as fallbacks: healthy endpoints by
+    /// descending weight, then those in their cool-down.
+    pub(crate) fn order(&self, count: usize, now: Instant) -> Vec<usize> {
+        let mut state = self.state.lock().unwrap();
+        state.current.resize(count, 0);
+        state.down_until.resize(count, None);
+        let healthy = |state: &State, index: usize| {
+            state.down_until[index].map_or(true, |until| now >= until)
+        };
+
+        let candidates: Vec<usize> = (0..count)
+            .filter(|&index| self.weight(index) > 0 && healthy(&state, index))
+            .collect();
+        let total: i64 = candidates.iter().map(|&index| self.weight(index)).sum();
+        let mut chosen = None;
+        for &index in &candidates {
+            state.current[index] += self.weight(index);
+            let score = state.current[index];
+            if chosen.map_or(true, |best: usize| score > state.current[best]) {
+                chosen = Some(index);
+            }
+        }
+        if let Some(chosen) = chosen {
+            state.current[chosen] -= total;
+        }
+
+        let mut order: Vec<usize> = (0..count).filter(|&index| Some(index) != chosen).collect();
+        order.sort_by_key(|&index| (!healthy(&state, index), -self.weight(index)));
+        order.splice(0..0, chosen);
+        order
+    }
+
+    /// Leave an endpoint out for the cool-down after it refused a
+    /// connection at `now`
+    pub(crate) fn record_failure(&self, index: usize, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if state.down_until.len() <= index {
+            state.down_until.resize(index + 1, None);
+        }
+        state.down_until[index] = Some(now + ENDPOINT_COOL_DOWN);
+    }
+
+    /// Put an endpoint back in rotation after it accepted a connection
+    pub(crate) fn record_success(&self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(until) = state.down_until.get_mut(index) {
+            *until = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted_order() {
+        let balancer = EndpointBalancer::new(vec![3, 1]);
+        let mut picks = [0; 3];
+        for _ in 0..10 {
+            let order = balancer.order(3, Instant::now());
+            assert_eq!(order.len(), 3);
+            picks[order[0]] += 1;
+        }
+        // The third endpoint has the default weight of 1
+        assert_eq!(picks, [6, 2, 2]);
+    }
+
+    #[test]
+    fn test_failed_endpoints_are_left_out() {
+        let balancer = EndpointBalancer::new(vec![1, 1, 0]);
+        let now = Instant::now();
+        balancer.record_failure(0, now);
+        for _ in 0..4 {
+            // Weight 0 is only a fallback, the failed endpoint comes last
+            assert_eq!(balancer.order(3, now), [1, 2, 0]);
+        }
+        let later = now + ENDPOINT_COOL_DOWN;
+        assert_eq!(balancer.order(3, later)[2], 2);
+
+        balancer.record_success(0);
+        let firsts: Vec<usize> = (0..4).map(|_| balancer.order(3, now)[0]).collect();
+        assert!(firsts.contains(&0) && firsts.contains(&1));
+        assert!(!firsts.contains(&2));
+    }
+}
@@ -0,0 +1,79 @@
+//! Array DML
+//!
+//! Rows are sent with the driver's array binding in chunks of
+//! [`CHUNK_ROWS`], so a bulk insert costs one round trip per chunk instead of
+//! one per row. Batch errors mode is always on: a failing row is reported in
+//! [`BatchResult::errors`] and the remaining rows still run.
+
+use oracle_rs::{BatchBuilder, BatchResult, Connection, Error, Value};
+
+/// Rows sent per round trip
+const CHUNK_ROWS: usize = 1000;
+
+/// Execute `sql` once per row of binds
+pub(crate) async fn execute_many(
+    conn: &Connection,
+    sql: &str,
+    rows: &[&[Value]],
+) -> Result<BatchResult, Error> {
+    let mut result = BatchResult::with_row_counts(Vec::with_capacity(rows.len()));
+
+    for (chunk_index, chunk) in rows.chunks(CHUNK_ROWS).enumerate() {
+        let batch = BatchBuilder::new(sql)
+            .add_rows(chunk.iter().map(|row| row.to_vec()).collect())
+            .with_batch_errors()
+            .with_row_counts()
+            .build();
+        let chunk_result = conn.execute_batch(&batch).await?;
+        merge(&mut result, chunk_result, chunk_index * CHUNK_ROWS);
+    }
+
+    Ok(result)
+}
+
+/// Add the result of a chunk starting at row `offset`
+fn merge(result: &mut BatchResult, chunk: BatchResult, offset: usize) {
+    if let (Some(counts), Some(chunk_counts)) = (&mut result.row_counts, chunk.row_counts) {
+        counts.extend(chunk_counts);
+    }
+    result.total_rows_affected += chunk.total_rows_affected;
+    result.success_count += chunk.success_count;
+    result.failure_count += chunk.failure_count;
+    result
+        .errors
+        .extend(chunk.errors.into_iter().map(|mut error| {
+            error.row_index += offset;
+            error
+        }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_rs::BatchError;
+
+    #[test]
+    fn test_merge_offsets_errors() {
+        let mut result = BatchResult::with_row_counts(Vec::new());
+
+        let mut first = BatchResult::with_row_counts(vec![1, 1]);
+        first.total_rows_affected = 2;
+        first.success_count = 2;
+        merge(&mut result, first, 0);
+
+        let mut second = BatchResult::with_row_counts(vec![1, 0]);
+        second.total_rows_affected = 1;
+        second.success_count = 1;
+        second.failure_count = 1;
+        second
+            .errors
+            .push(BatchError::new(1, 1, "unique constraint violated"));
+        merge(&mut result, second, CHUNK_ROWS);
+
+        assert_eq!(result.row_counts, Some(vec![1, 1, 1, 0]));
+        assert_eq!(result.total_rows_affected, 3);
+        assert_eq!(result.success_count, 3);
+        assert_eq!(result.failure_count, 1);
+        assert_eq!(result.errors[0].row_index, CHUNK_ROWS + 1);
+    }
+}
@@ -0,0 +1,131 @@
+//! Adapter for bb8 pools
+//!
+//! [`Bb8Manager`] implements `bb8::ManageConnection` on top of an
+//! [`OracleConnectionManager`], so applications standardized on bb8 get the
+//! same connection setup (failover, session initialization, DRCP, ...) and
+//! checkout validation (rollback, health check, container reset, ...) as
+//! pools built by this crate. Features of [`Pool`](crate::Pool) and
+//! [`Object`](crate::Object), such as statistics per checkout, transaction
+//! guards or connection tags, are not available through bb8.
+
+use crate::OracleConnectionManager;
+use deadpool::managed::{Manager, Metrics, RecycleError};
+use oracle_rs::{Connection, Error};
+use std::ops::{Deref, DerefMut};
+use std::time::Instant;
+
+/// bb8 connection manager backed by an [`OracleConnectionManager`]
+///
+/// ```rust,no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use deadpool_oracle::{Bb8Manager, OracleConnectionManager};
+/// use oracle_rs::Config;
+///
+/// let config = Config::new("localhost", 1521, "FREEPDB1", "user", "password");
+/// let manager = OracleConnectionManager::new(config).with_session_init_sql(vec![
+///     "ALTER SESSION SET TIME_ZONE = 'UTC'".to_string(),
+/// ]);
+/// let pool = bb8::Pool::builder()
+///     .max_size(10)
+///     .build(Bb8Manager::new(manager))
+///     .await?;
+///
+/// let conn = pool.get().await?;
+/// let result = conn.query("SELECT * FROM users", &[]).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Bb8Manager {
+    manager: OracleConnectionManager,
+}
+
+impl Bb8Manager {
+    /// Create and validate connections with `manager`
+    pub fn new(manager: OracleConnectionManager) -> Self {
+        Self { manager }
+    }
+
+    /// The manager creating and validating the connections, e.g. to subscribe to its events
+    pub fn manager(&self) -> &OracleConnectionManager {
+        &self.manager
+    }
+}
+
+/// Connection of a bb8 pool managed by [`Bb8Manager`]
+///
+/// Dereferences to the driver's `Connection`.
+pub struct Bb8Connection {
+    conn: Connection,
+    metrics: Metrics,
+}
+
+impl Bb8Connection {
+    /// When the connection was created and how often it was validated
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+}
+
+impl Deref for Bb8Connection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+impl DerefMut for Bb8Connection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        &mut self.conn
+    }
+}
+
+impl bb8::ManageConnection for Bb8Manager {
+    type Connection = Bb8Connection;
+    type Error = Error;
+
+    async fn connect(&self) -> Result<Bb8Connection, Error> {
+        let conn = self.manager.create().await?;
+        Ok(Bb8Connection {
+            conn,
+            metrics: Metrics::default(),
+        })
+    }
+
+    async fn is_valid(&self, conn: &mut Bb8Connection) -> Result<(), Error> {
+        if let Err(e) = self.manager.recycle(&mut conn.conn, &conn.metrics).await {
+            // bb8 drops the connection without telling the manager
+            self.manager.detach(&mut conn.conn);
+            return Err(match e {
+                RecycleError::Backend(e) => e,
+                RecycleError::Message(message) => Error::SqlError(message.into_owned()),
+            });
+        }
+        conn.metrics.recycle_count += 1;
+        conn.metrics.recycled = Some(Instant::now());
+        Ok(())
+    }
+
+    fn has_broken(&self, conn: &mut Bb8Connection) -> bool {
+        let broken = conn.is_closed();
+        if broken {
+            self.manager.detach(&mut conn.conn);
+        }
+        broken
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bb8::ManageConnection;
+    use oracle_rs::Config;
+
+    #[tokio::test]
+    async fn test_connect_failure_reported() {
+        // Nothing listens on port 1
+        let config = Config::new("127.0.0.1", 1, "FREEPDB1", "user", "password");
+        let manager = Bb8Manager::new(OracleConnectionManager::new(config));
+        assert!(manager.connect().await.is_err());
+    }
+}
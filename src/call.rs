@@ -0,0 +1,169 @@
+//! Stored procedure calls
+//!
+//! A call is sent as `BEGIN name(:1, :2, ...); END;`. The driver returns OUT
+//! values in the order of the OUT binds only, so they are mapped back to the
+//! position of their parameter.
+
+use crate::audit::Binds;
+use crate::from_row::FromValue;
+use crate::page::is_identifier;
+use crate::Object;
+use oracle_rs::{BindParam, Error, OracleType, RefCursor, Value};
+
+/// Parameters of a stored procedure call, in declaration order
+///
+/// ```rust
+/// use deadpool_oracle::CallParams;
+/// use oracle_rs::OracleType;
+///
+/// let params = CallParams::new()
+///     .input(42)
+///     .output(OracleType::Varchar, 200)
+///     .input_output(0, 22);
+/// ```
+#[derive(Debug, Default)]
+pub struct CallParams {
+    binds: Vec<BindParam>,
+}
+
+impl CallParams {
+    /// Create an empty parameter list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an IN parameter
+    pub fn input(mut self, value: impl Into<Value>) -> Self {
+        self.binds.push(BindParam::input(value.into()));
+        self
+    }
+
+    /// Add an OUT parameter of `oracle_type`, up to `buffer_size` bytes
+    pub fn output(mut self, oracle_type: OracleType, buffer_size: u32) -> Self {
+        self.binds.push(BindParam::output(oracle_type, buffer_size));
+        self
+    }
+
+    /// Add a `SYS_REFCURSOR` OUT parameter
+    ///
+    /// Read it back with [`CallResult::cursor()`] and iterate it with
+    /// [`Object::cursor_rows()`](crate::Object::cursor_rows).
+    pub fn output_cursor(mut self) -> Self {
+        self.binds.push(BindParam::output_cursor());
+        self
+    }
+
+    /// Add an IN OUT parameter, up to `buffer_size` bytes on the way out
+    pub fn input_output(mut self, value: impl Into<Value>, buffer_size: u32) -> Self {
+        self.binds
+            .push(BindParam::input_output(value.into(), buffer_size));
+        self
+    }
+
+    /// Build the anonymous block calling `name`
+    fn to_plsql(&self, name: &str) -> Result<String, Error> {
+        if !is_identifier(name) {
+            return Err(Error::SqlError(format!("invalid procedure name: {}", name)));
+        }
+        let placeholders: Vec<String> = (1..=self.binds.len()).map(|i| format!(":{}", i)).collect();
+        Ok(format!("BEGIN {}({}); END;", name, placeholders.join(", ")))
+    }
+}
+
+/// OUT values of a stored procedure call
+#[derive(Debug, Clone)]
+pub struct CallResult {
+    values: Vec<Option<Value>>,
+}
+
+impl CallResult {
+    /// Pair the OUT values with the OUT parameters they belong to
+    fn new(params: &CallParams, out_values: Vec<Value>) -> Self {
+        let mut out_values = out_values.into_iter();
+        let values = params
+            .binds
+            .iter()
+            .map(|bind| {
+                if bind.direction.is_output() {
+                    Some(out_values.next().unwrap_or(Value::Null))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        Self { values }
+    }
+
+    /// Raw value of the OUT or IN OUT parameter at `index` (zero-based)
+    ///
+    /// `None` for IN parameters and indexes past the end.
+    pub fn value(&self, index: usize) -> Option<&Value> {
+        self.values.get(index)?.as_ref()
+    }
+
+    /// Value of the OUT or IN OUT parameter at `index` (zero-based),
+    /// converted to `T`
+    pub fn get<T: FromValue>(&self, index: usize) -> Result<T, Error> {
+        let value = self.value(index).ok_or_else(|| {
+            Error::DataConversionError(format!("no OUT parameter at index {}", index))
+        })?;
+        T::from_value(value)
+    }
+
+    /// REF CURSOR returned by the OUT parameter at `index` (zero-based)
+    pub fn cursor(&self, index: usize) -> Result<RefCursor, Error> {
+        self.value(index)
+            .and_then(Value::as_cursor)
+            .cloned()
+            .ok_or_else(|| Error::InvalidCursor(format!("no cursor at index {}", index)))
+    }
+}
+
+/// Call the stored procedure `name`
+pub(crate) async fn call(
+    conn: &Object,
+    name: &str,
+    params: &CallParams,
+) -> Result<CallResult, Error> {
+    let sql = params.to_plsql(name)?;
+    let binds = Binds::Params(&params.binds);
+    let result = conn
+        .observe(
+            &sql,
+            binds,
+            |conn| conn.execute_plsql(&sql, &params.binds),
+            |_| None,
+        )
+        .await?;
+    Ok(CallResult::new(params, result.out_values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_plsql_and_out_values() {
+        let params = CallParams::new()
+            .input(7)
+            .output(OracleType::Varchar, 100)
+            .input("x")
+            .input_output(1, 22);
+
+        assert_eq!(
+            params.to_plsql("hr.emp_pkg.rename").unwrap(),
+            "BEGIN hr.emp_pkg.rename(:1, :2, :3, :4); END;"
+        );
+        assert!(params.to_plsql("p; DROP TABLE emp").is_err());
+
+        let result = CallResult::new(
+            &params,
+            vec![Value::String("SMITH".to_string()), Value::Integer(2)],
+        );
+        assert!(result.value(0).is_none());
+        assert_eq!(result.get::<String>(1).unwrap(), "SMITH");
+        assert!(result.get::<String>(2).is_err());
+        assert_eq!(result.get::<i64>(3).unwrap(), 2);
+        assert!(result.cursor(1).is_err());
+    }
+}
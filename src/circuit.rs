@@ -0,0 +1,160 @@
+//! Circuit breaker for database outages
+//!
+//! Every create and recycle reports its outcome. After the configured number
+//! of consecutive failures the circuit opens: `get()` fails at once instead
+//! of queueing callers behind connection attempts that are bound to time
+//! out. A single background task then tries to connect after every
+//! cool-down period and closes the circuit on the first success. Pools in
+//! deterministic mode let the first checkout after the cool-down try
+//! instead.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Failure counter and open state shared by a pool and its probe
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    threshold: u32,
+    cool_down: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    failures: u32,
+    /// When the next probe is due, if the circuit is open
+    open_until: Option<Instant>,
+    probing: bool,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(threshold: u32, cool_down: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cool_down,
+            state: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn cool_down(&self) -> Duration {
+        self.cool_down
+    }
+
+    pub(crate) fn is_open(&self) -> bool {
+        self.state.lock().unwrap().open_until.is_some()
+    }
+
+    /// Record a successful create or recycle, returning whether it closed the circuit
+    pub(crate) fn record_success(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.failures = 0;
+        state.probing = false;
+        state.open_until.take().is_some()
+    }
+
+    /// Record a failed create or recycle at `now`, returning the number of
+    /// consecutive failures if it opened the circuit
+    pub(crate) fn record_failure(&self, now: Instant) -> Option<u32> {
+        let mut state = self.state.lock().unwrap();
+        state.failures = state.failures.saturating_add(1);
+        if state.open_until.is_some() || state.failures < self.threshold {
+            return None;
+        }
+        state.open_until = Some(now + self.cool_down);
+        Some(state.failures)
+    }
+
+    /// Claim the probe of an open circuit, returning when it is due
+    ///
+    /// Returns `None` if the circuit is closed or another task is probing.
+    pub(crate) fn start_probe(&self) -> Option<Instant> {
+        let mut state = self.state.lock().unwrap();
+        let due = state.open_until.filter(|_| !state.probing)?;
+        state.probing = true;
+        Some(due)
+    }
+
+    /// Keep the circuit open for another cool-down after a probe failed at
+    /// `now`, returning when the next probe is due
+    pub(crate) fn probe_failed(&self, now: Instant) -> Instant {
+        let mut state = self.state.lock().unwrap();
+        let due = now + self.cool_down;
+        state.open_until = Some(due);
+        due
+    }
+
+    /// Claim the probe of an open circuit whose cool-down is over at `now`,
+    /// for a checkout to make
+    pub(crate) fn start_inline_probe(&self, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let due = state.open_until.is_some_and(|until| now >= until);
+        if !due || state.probing {
+            return false;
+        }
+        state.probing = true;
+        true
+    }
+
+    /// Keep the circuit open for another cool-down after a checkout probe
+    /// failed at `now`, letting the next checkout after it probe again
+    pub(crate) fn inline_probe_failed(&self, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        state.open_until = Some(now + self.cool_down);
+        state.probing = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker() {
+        let circuit = CircuitBreaker::new(3, Duration::from_secs(5));
+        let now = Instant::now();
+        assert_eq!(circuit.record_failure(now), None);
+        assert_eq!(circuit.record_failure(now), None);
+        assert!(!circuit.record_success());
+
+        // Only consecutive failures count
+        assert_eq!(circuit.record_failure(now), None);
+        assert_eq!(circuit.record_failure(now), None);
+        assert_eq!(circuit.start_probe(), None);
+        assert_eq!(circuit.record_failure(now), Some(3));
+        assert!(circuit.is_open());
+        assert_eq!(circuit.record_failure(now), None);
+
+        // One probe at a time
+        assert!(circuit.start_probe().is_some());
+        assert_eq!(circuit.start_probe(), None);
+        assert_eq!(circuit.probe_failed(now), now + Duration::from_secs(5));
+        assert!(circuit.is_open());
+
+        assert!(circuit.record_success());
+        assert!(!circuit.is_open());
+        assert_eq!(circuit.start_probe(), None);
+    }
+
+    #[test]
+    fn test_inline_probe() {
+        let cool_down = Duration::from_secs(5);
+        let circuit = CircuitBreaker::new(1, cool_down);
+        let now = Instant::now();
+        assert!(!circuit.start_inline_probe(now));
+        assert_eq!(circuit.record_failure(now), Some(1));
+        assert!(!circuit.start_inline_probe(now));
+
+        // One checkout probes at a time, and the next one after a failure
+        // waits for another cool-down
+        let later = now + cool_down;
+        assert!(circuit.start_inline_probe(later));
+        assert!(!circuit.start_inline_probe(later));
+        circuit.inline_probe_failed(later);
+        assert!(circuit.is_open());
+        assert!(!circuit.start_inline_probe(later));
+        assert!(circuit.start_inline_probe(later + cool_down));
+
+        assert!(circuit.record_success());
+        assert!(!circuit.start_inline_probe(later + cool_down));
+    }
+}
@@ -0,0 +1,87 @@
+//! Time source of a pool
+//!
+//! The pool reads the time and sleeps through a [`Clock`]: for backoffs,
+//! quarantine, the circuit breaker cool-down, idle expiry, its background
+//! tasks, call and health check timeouts, leak detection, transaction
+//! hold limits and the checkout and connect times it reports. The default
+//! [`TokioClock`] follows `tokio::time::pause()`, so tests can advance time
+//! instead of waiting for it.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+/// Future returned by [`Clock::sleep()`]
+pub type ClockSleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Source of the current time and of timers for a pool
+///
+/// Set with `PoolBuilder::clock()`. Embedded users with their own time
+/// source implement it; tests are usually served by the default
+/// [`TokioClock`] and `tokio::time::pause()`.
+pub trait Clock: Send + Sync + 'static {
+    /// The current time
+    fn now(&self) -> Instant;
+
+    /// A future completing once `duration` has passed on this clock
+    fn sleep(&self, duration: Duration) -> ClockSleep;
+}
+
+/// The Tokio clock, which can be paused and advanced in tests
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    fn sleep(&self, duration: Duration) -> ClockSleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Await `future` for at most `duration` on `clock`, `None` if it took
+/// longer
+pub(crate) async fn timeout<F: Future>(
+    clock: &dyn Clock,
+    duration: Duration,
+    future: F,
+) -> Option<F::Output> {
+    let mut future = std::pin::pin!(future);
+    let mut deadline = clock.sleep(duration);
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        deadline.as_mut().poll(cx).map(|()| None)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tokio_clock_follows_pause() {
+        let clock = TokioClock;
+        let start = clock.now();
+        tokio::time::advance(Duration::from_secs(3600)).await;
+        assert_eq!(clock.now() - start, Duration::from_secs(3600));
+
+        clock.sleep(Duration::from_secs(60)).await;
+        assert_eq!(clock.now() - start, Duration::from_secs(3660));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timeout() {
+        let clock = TokioClock;
+        let quick = timeout(&clock, Duration::from_secs(1), async { 7 });
+        assert_eq!(quick.await, Some(7));
+
+        let slow = tokio::time::sleep(Duration::from_secs(2));
+        assert_eq!(timeout(&clock, Duration::from_secs(1), slow).await, None);
+    }
+}
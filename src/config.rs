@@ -0,0 +1,224 @@
+//! Pool settings from configuration files
+//!
+//! [`PoolConfig`] describes a pool declaratively, so it can be read with
+//! serde from whatever format the application uses. It covers the
+//! connection target and the most common builder options; anything else
+//! can be set on the [`PoolBuilder`] it returns. [`PoolsConfig`] names
+//! several of them, for services with more than one Oracle target.
+//! Unknown keys are rejected rather than ignored, so a misspelled setting
+//! fails to load instead of leaving the default in place.
+
+use crate::{BuildError, Pool, PoolBuilder};
+use oracle_rs::Config;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Settings of several named pools
+///
+/// Read from a `pools` table keyed by pool name and turned into pools with
+/// [`Pools::from_config()`](crate::Pools::from_config). In TOML:
+///
+/// ```toml
+/// [pools.orders]
+/// host = "db.example.com"
+/// service_name = "ORDERS"
+/// username = "app"
+/// password = "secret"
+/// max_size = 20
+///
+/// [pools.reporting]
+/// host = "replica.example.com"
+/// service_name = "DWH"
+/// username = "report"
+/// password = "secret"
+/// wait_timeout_ms = 10000
+/// ```
+///
+/// or in YAML:
+///
+/// ```yaml
+/// pools:
+///   orders:
+///     host: db.example.com
+///     service_name: ORDERS
+///     username: app
+///     password: secret
+///     max_size: 20
+/// ```
+#[derive(Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct PoolsConfig {
+    /// Settings of each pool, by name
+    #[serde(default)]
+    pub pools: BTreeMap<String, PoolConfig>,
+}
+
+/// Connection target and settings of a pool
+///
+/// ```rust
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use deadpool_oracle::PoolConfig;
+///
+/// let config: PoolConfig = serde_json::from_str(
+///     r#"{
+///         "host": "db.example.com",
+///         "service_name": "ORDERS",
+///         "username": "app",
+///         "password": "secret",
+///         "max_size": 20,
+///         "wait_timeout_ms": 5000
+///     }"#,
+/// )?;
+/// let pool = config.build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct PoolConfig {
+    /// Database host
+    pub host: String,
+    /// Listener port, 1521 if not given
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Service name of the database
+    pub service_name: String,
+    /// User to connect as
+    pub username: String,
+    /// Password of the user
+    pub password: String,
+    /// Maximum number of connections
+    #[serde(default)]
+    pub max_size: Option<usize>,
+    /// Timeout waiting for a connection, in milliseconds
+    #[serde(default)]
+    pub wait_timeout_ms: Option<u64>,
+    /// Timeout creating a connection, in milliseconds
+    #[serde(default)]
+    pub create_timeout_ms: Option<u64>,
+    /// Timeout of the health check on recycle, in milliseconds
+    #[serde(default)]
+    pub recycle_timeout_ms: Option<u64>,
+    /// Statements run on every new connection
+    #[serde(default)]
+    pub session_init_sql: Vec<String>,
+    /// Parsed statements cached per connection
+    #[serde(default)]
+    pub statement_cache_size: Option<usize>,
+}
+
+fn default_port() -> u16 {
+    1521
+}
+
+impl PoolConfig {
+    /// Create a pool builder with these settings
+    ///
+    /// Settings not given keep the builder's defaults.
+    pub fn builder(&self) -> PoolBuilder {
+        let config = Config::new(
+            &self.host,
+            self.port,
+            &self.service_name,
+            &self.username,
+            &self.password,
+        );
+        let mut builder = PoolBuilder::new(config).session_init_sql(self.session_init_sql.clone());
+        if let Some(size) = self.max_size {
+            builder = builder.max_size(size);
+        }
+        if let Some(ms) = self.wait_timeout_ms {
+            builder = builder.wait_timeout(Some(Duration::from_millis(ms)));
+        }
+        if let Some(ms) = self.create_timeout_ms {
+            builder = builder.create_timeout(Some(Duration::from_millis(ms)));
+        }
+        if let Some(ms) = self.recycle_timeout_ms {
+            builder = builder.recycle_timeout(Some(Duration::from_millis(ms)));
+        }
+        if let Some(size) = self.statement_cache_size {
+            builder = builder.statement_cache_size(size);
+        }
+        builder
+    }
+
+    /// Build a pool with these settings
+    pub fn build(&self) -> Result<Pool, BuildError> {
+        self.builder().build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_config_defaults() {
+        let config: PoolConfig = serde_json::from_str(
+            r#"{"host": "db", "service_name": "FREEPDB1", "username": "u", "password": "p"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.port, 1521);
+        assert_eq!(config.max_size, None);
+        assert!(config.session_init_sql.is_empty());
+
+        let pool = config.build().unwrap();
+        assert_eq!(pool.name(), "db:1521/FREEPDB1");
+    }
+
+    #[test]
+    fn test_pools_config_from_toml() {
+        let config: PoolsConfig = toml::from_str(
+            r#"
+            [pools.orders]
+            host = "db"
+            service_name = "ORDERS"
+            username = "app"
+            password = "p"
+            max_size = 8
+            session_init_sql = ["ALTER SESSION SET TIME_ZONE = 'UTC'"]
+
+            [pools.reporting]
+            host = "replica"
+            port = 1522
+            service_name = "DWH"
+            username = "report"
+            password = "p"
+            "#,
+        )
+        .unwrap();
+        let names: Vec<&str> = config.pools.keys().map(String::as_str).collect();
+        assert_eq!(names, ["orders", "reporting"]);
+        assert_eq!(config.pools["orders"].max_size, Some(8));
+        assert_eq!(config.pools["orders"].session_init_sql.len(), 1);
+        assert_eq!(config.pools["reporting"].port, 1522);
+
+        let empty: PoolsConfig = toml::from_str("").unwrap();
+        assert!(empty.pools.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_keys_rejected() {
+        let err = toml::from_str::<PoolsConfig>(
+            r#"
+            [pools.orders]
+            host = "db"
+            service_name = "ORDERS"
+            username = "app"
+            password = "p"
+            max_sise = 8
+            "#,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("max_sise"), "{}", err);
+
+        let err = toml::from_str::<PoolsConfig>("[pool.orders]\nhost = \"db\"")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("pool"), "{}", err);
+    }
+}
@@ -0,0 +1,128 @@
+//! Switching pooled sessions between pluggable databases
+//!
+//! In a container database (CDB), a common user can move its session to
+//! another pluggable database (PDB) with `ALTER SESSION SET CONTAINER`,
+//! so one pool can serve all PDBs of a consolidated deployment.
+//! [`Pool::get_for_container()`](crate::Pool::get_for_container) switches
+//! the connection it hands out; the manager remembers the container the
+//! session started in and switches back when the connection is recycled.
+
+use crate::page::is_identifier;
+use oracle_rs::{Connection, Error};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Name of the container a session is in
+const CON_NAME_SQL: &str = "SELECT SYS_CONTEXT('USERENV', 'CON_NAME') FROM DUAL";
+
+/// Containers of the connections that were switched, by connection id
+#[derive(Debug, Default)]
+pub(crate) struct SessionContainers {
+    switched: Mutex<HashMap<u32, Switched>>,
+}
+
+#[derive(Debug, Clone)]
+struct Switched {
+    /// Container the session was opened in
+    home: String,
+    /// Container the session is in now
+    current: String,
+}
+
+impl SessionContainers {
+    /// Container a connection was switched to, if it was
+    pub(crate) fn current(&self, connection_id: u32) -> Option<String> {
+        let switched = self.switched.lock().unwrap();
+        switched.get(&connection_id).map(|s| s.current.clone())
+    }
+
+    /// Container a switched connection was opened in
+    pub(crate) fn home(&self, connection_id: u32) -> Option<String> {
+        let switched = self.switched.lock().unwrap();
+        switched.get(&connection_id).map(|s| s.home.clone())
+    }
+
+    pub(crate) fn set(&self, connection_id: u32, home: String, current: String) {
+        let mut switched = self.switched.lock().unwrap();
+        if home == current {
+            switched.remove(&connection_id);
+        } else {
+            switched.insert(connection_id, Switched { home, current });
+        }
+    }
+
+    /// Forget a connection that is back in its home container or left the pool
+    pub(crate) fn remove(&self, connection_id: u32) {
+        self.switched.lock().unwrap().remove(&connection_id);
+    }
+}
+
+/// Normalize a container name, rejecting anything but a plain identifier
+///
+/// The name ends up in DDL, where it cannot be bound.
+pub(crate) fn container_name(name: &str) -> Result<String, Error> {
+    if !is_identifier(name) || name.contains('.') {
+        return Err(Error::SqlError(format!("invalid container name: {}", name)));
+    }
+    Ok(name.to_ascii_uppercase())
+}
+
+/// Statement switching a session to `container`, a name from `container_name()`
+pub(crate) fn alter_session_sql(container: &str) -> String {
+    format!("ALTER SESSION SET CONTAINER = {}", container)
+}
+
+/// Ask the server which container the session is in
+pub(crate) async fn current_container(conn: &Connection) -> Result<String, Error> {
+    let result = conn.query(CON_NAME_SQL, &[]).await?;
+    let name = result.rows.first().and_then(|row| row.get_string(0));
+    name.map(str::to_string).ok_or(Error::NoDataFound)
+}
+
+/// Switch a session back to `home` and verify it arrived there
+pub(crate) async fn reset(conn: &Connection, home: &str) -> Result<(), Error> {
+    conn.execute(&alter_session_sql(home), &[]).await?;
+    let current = current_container(conn).await?;
+    if current != home {
+        return Err(Error::SqlError(format!(
+            "session is in container {} instead of {}",
+            current, home
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_name() {
+        assert_eq!(container_name("pdb2").unwrap(), "PDB2");
+        assert_eq!(container_name("CDB$ROOT").unwrap(), "CDB$ROOT");
+        assert!(container_name("").is_err());
+        assert!(container_name("2PDB").is_err());
+        assert!(container_name("PDB2; DROP USER app").is_err());
+        assert!(container_name("APP.PDB2").is_err());
+        assert_eq!(
+            alter_session_sql("PDB2"),
+            "ALTER SESSION SET CONTAINER = PDB2"
+        );
+    }
+
+    #[test]
+    fn test_session_containers() {
+        let containers = SessionContainers::default();
+        containers.set(1, "PDB1".to_string(), "PDB2".to_string());
+        assert_eq!(containers.current(1).as_deref(), Some("PDB2"));
+        assert_eq!(containers.home(1).as_deref(), Some("PDB1"));
+
+        // Back home is the same as never switched
+        containers.set(1, "PDB1".to_string(), "PDB1".to_string());
+        assert_eq!(containers.current(1), None);
+
+        containers.set(2, "PDB1".to_string(), "PDB3".to_string());
+        containers.remove(2);
+        assert_eq!(containers.home(2), None);
+    }
+}
@@ -0,0 +1,274 @@
+//! Credentials from a secret store
+//!
+//! A [`CredentialProvider`] set with
+//! [`PoolBuilder::credential_provider()`](crate::PoolBuilder::credential_provider)
+//! supplies the user name and password of new connections, so they never
+//! appear in configuration files. The manager caches what it returns and
+//! asks again when a login is refused, as the secret may have been rotated
+//! since, and, with
+//! [`PoolBuilder::credential_refresh()`](crate::PoolBuilder::credential_refresh),
+//! in the background at a fixed interval. Credentials that expire, like the
+//! database tokens of cloud IAM services, say so with
+//! [`Credentials::with_lifetime()`]: they are not used for a login after
+//! they expired, the background task replaces them ahead of time, and the
+//! connections that logged in with them are retired once they are replaced.
+//! Providers for HashiCorp Vault and AWS Secrets Manager come with the
+//! `vault` and `aws-secrets-manager` features.
+
+use oracle_rs::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// User name and password to log in with
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    username: String,
+    password: String,
+    lifetime: Option<Duration>,
+}
+
+impl Credentials {
+    /// Create credentials of `username`
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+            lifetime: None,
+        }
+    }
+
+    /// Set how long the credentials are valid from when they are returned
+    ///
+    /// For tokens and leased secrets that expire. Default is no expiry.
+    pub fn with_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = Some(lifetime);
+        self
+    }
+
+    /// The user to log in as
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// The password of the user
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    /// How long the credentials are valid, if they expire
+    pub fn lifetime(&self) -> Option<Duration> {
+        self.lifetime
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .field("lifetime", &self.lifetime)
+            .finish()
+    }
+}
+
+/// Future returned by [`CredentialProvider::credentials()`]
+pub type CredentialsFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Credentials, Error>> + Send + 'a>>;
+
+/// Source of the credentials of new connections
+///
+/// Set with `PoolBuilder::credential_provider()`. A failure is reported as
+/// the error of the connection attempt, so it should say what could not be
+/// read; `Error::AuthenticationFailed` is a good fit.
+///
+/// ```rust
+/// use deadpool_oracle::{CredentialProvider, Credentials, CredentialsFuture};
+///
+/// struct EnvProvider;
+///
+/// impl CredentialProvider for EnvProvider {
+///     fn credentials(&self) -> CredentialsFuture<'_> {
+///         Box::pin(async {
+///             let password = std::env::var("DB_PASSWORD").map_err(|e| {
+///                 oracle_rs::Error::AuthenticationFailed(format!("DB_PASSWORD: {}", e))
+///             })?;
+///             Ok(Credentials::new("app", password))
+///         })
+///     }
+/// }
+/// ```
+pub trait CredentialProvider: Send + Sync + 'static {
+    /// Read the current credentials
+    fn credentials(&self) -> CredentialsFuture<'_>;
+}
+
+/// Always the same credentials, such as those of
+/// `PoolBuilder::readonly_credentials()`
+impl CredentialProvider for Credentials {
+    fn credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(async move { Ok(self.clone()) })
+    }
+}
+
+/// Credentials and when the provider returned them
+struct Cached {
+    credentials: Credentials,
+    fetched_at: Instant,
+}
+
+impl Cached {
+    fn expires_at(&self) -> Option<Instant> {
+        self.credentials
+            .lifetime
+            .map(|lifetime| self.fetched_at + lifetime)
+    }
+}
+
+/// Provider of a pool and the credentials it last returned
+pub(crate) struct CredentialCache {
+    provider: Arc<dyn CredentialProvider>,
+    /// Held while the provider is asked, so concurrent logins ask once
+    cached: tokio::sync::Mutex<Option<Cached>>,
+}
+
+impl CredentialCache {
+    pub(crate) fn new(provider: Arc<dyn CredentialProvider>) -> Self {
+        Self {
+            provider,
+            cached: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// The cached credentials, asking the provider if there are none or
+    /// they expired by `now`
+    pub(crate) async fn get(&self, now: Instant) -> Result<Credentials, Error> {
+        let mut cached = self.cached.lock().await;
+        let valid = cached
+            .as_ref()
+            .filter(|cached| cached.expires_at().map_or(true, |at| now < at));
+        if let Some(cached) = valid {
+            return Ok(cached.credentials.clone());
+        }
+        let credentials = self.provider.credentials().await?;
+        *cached = Some(Cached {
+            credentials: credentials.clone(),
+            fetched_at: now,
+        });
+        Ok(credentials)
+    }
+
+    /// Ask the provider again, replacing the cached credentials
+    ///
+    /// Also returns whether they replaced different credentials that
+    /// expire, whose connections should be retired. On failure the cached
+    /// credentials are kept, so a secret store that is briefly unavailable
+    /// doesn't stop the pool from connecting.
+    pub(crate) async fn refresh(&self, now: Instant) -> Result<(Credentials, bool), Error> {
+        let credentials = self.provider.credentials().await?;
+        let fresh = Cached {
+            credentials: credentials.clone(),
+            fetched_at: now,
+        };
+        let replaced = self.cached.lock().await.replace(fresh);
+        let expiring = replaced.is_some_and(|replaced| {
+            replaced.credentials.lifetime.is_some() && replaced.credentials != credentials
+        });
+        Ok((credentials, expiring))
+    }
+
+    /// User name of the cached credentials, without asking the provider
+    pub(crate) async fn username(&self) -> Option<String> {
+        let cached = self.cached.lock().await;
+        let credentials = &cached.as_ref()?.credentials;
+        Some(credentials.username.clone())
+    }
+
+    /// When the cached credentials should be replaced, if they expire
+    ///
+    /// That is `margin` before they expire, or halfway through their
+    /// lifetime if it is shorter than twice the margin.
+    pub(crate) async fn refresh_due(&self, margin: Duration) -> Option<Instant> {
+        let cached = self.cached.lock().await;
+        let cached = cached.as_ref()?;
+        let lifetime = cached.credentials.lifetime?;
+        Some(cached.fetched_at + lifetime - margin.min(lifetime / 2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingProvider(AtomicUsize);
+
+    impl CredentialProvider for CountingProvider {
+        fn credentials(&self) -> CredentialsFuture<'_> {
+            let calls = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            Box::pin(async move { Ok(Credentials::new("app", format!("secret-{}", calls))) })
+        }
+    }
+
+    struct TokenProvider(AtomicUsize);
+
+    impl CredentialProvider for TokenProvider {
+        fn credentials(&self) -> CredentialsFuture<'_> {
+            let calls = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            let token = Credentials::new("app", format!("token-{}", calls))
+                .with_lifetime(Duration::from_secs(600));
+            Box::pin(async move { Ok(token) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_credential_cache() {
+        let provider = Arc::new(CountingProvider(AtomicUsize::new(0)));
+        let cache = CredentialCache::new(provider.clone());
+        let now = Instant::now();
+
+        assert_eq!(cache.get(now).await.unwrap().password(), "secret-1");
+        assert_eq!(cache.get(now).await.unwrap().password(), "secret-1");
+        assert_eq!(provider.0.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.refresh_due(Duration::from_secs(60)).await, None);
+
+        let (fresh, expiring) = cache.refresh(now).await.unwrap();
+        assert_eq!(fresh.password(), "secret-2");
+        assert!(!expiring);
+        assert_eq!(cache.get(now).await.unwrap().password(), "secret-2");
+        assert_eq!(provider.0.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_credential_cache_expiry() {
+        let provider = Arc::new(TokenProvider(AtomicUsize::new(0)));
+        let cache = CredentialCache::new(provider.clone());
+        let start = Instant::now();
+
+        assert_eq!(cache.get(start).await.unwrap().password(), "token-1");
+        let due = cache.refresh_due(Duration::from_secs(60)).await;
+        assert_eq!(due, Some(start + Duration::from_secs(540)));
+        // Short lifetimes are replaced halfway through
+        let due = cache.refresh_due(Duration::from_secs(900)).await;
+        assert_eq!(due, Some(start + Duration::from_secs(300)));
+
+        let later = start + Duration::from_secs(599);
+        assert_eq!(cache.get(later).await.unwrap().password(), "token-1");
+        let expired = start + Duration::from_secs(600);
+        assert_eq!(cache.get(expired).await.unwrap().password(), "token-2");
+
+        let (fresh, expiring) = cache.refresh(expired).await.unwrap();
+        assert_eq!(fresh.password(), "token-3");
+        assert!(expiring);
+    }
+
+    #[test]
+    fn test_credentials_debug_redacts_password() {
+        let credentials = Credentials::new("app", "hunter2").with_lifetime(Duration::from_secs(60));
+        let debug = format!("{:?}", credentials);
+        assert!(debug.contains("app"));
+        assert!(!debug.contains("hunter2"));
+    }
+}
@@ -0,0 +1,144 @@
+//! Cursor fetching
+//!
+//! The driver hands a REF CURSOR back as an OUT value holding the server-side
+//! cursor id and its columns. [`CursorRows`] fetches its rows a batch at a
+//! time on the connection that opened it, so large result sets are never held
+//! in memory at once.
+//!
+//! A query returns only its first batch of rows; [`fetch_all`] fetches the
+//! rest. Both fetch the pool's fetch array size per round trip unless
+//! overridden.
+
+use crate::from_row::FromRow;
+use crate::Object;
+use oracle_rs::{Connection, Error, QueryResult, RefCursor, Row, Value};
+use std::collections::VecDeque;
+
+/// Rows fetched per round trip, the same as the driver's prefetch
+pub(crate) const DEFAULT_FETCH_ARRAY_SIZE: u32 = 100;
+
+/// Run a query and fetch all of its rows, `fetch_size` per round trip
+pub(crate) async fn fetch_all(
+    conn: &Object,
+    sql: &str,
+    params: &[Value],
+    fetch_size: u32,
+) -> Result<QueryResult, Error> {
+    let mut result = conn.query(sql, params).await?;
+    while result.has_more_rows {
+        let fetch =
+            conn.connection()
+                .fetch_more(result.cursor_id, &result.columns, fetch_size.max(1));
+        let more = conn.limit_call(fetch).await?;
+        result.has_more_rows = more.has_more_rows && !more.rows.is_empty();
+        result.rows.extend(more.rows);
+    }
+    Ok(result)
+}
+
+/// Rows of a REF CURSOR, fetched on demand
+///
+/// Created with [`Object::cursor_rows()`](crate::Object::cursor_rows). The
+/// connection stays borrowed until the rows are consumed or dropped.
+pub struct CursorRows<'a> {
+    conn: &'a Connection,
+    cursor: RefCursor,
+    names: Vec<String>,
+    buffer: VecDeque<Row>,
+    fetch_size: u32,
+    started: bool,
+    done: bool,
+}
+
+impl<'a> CursorRows<'a> {
+    pub(crate) fn new(conn: &'a Connection, cursor: RefCursor, fetch_size: u32) -> Self {
+        let names = cursor.columns().iter().map(|c| c.name.clone()).collect();
+        Self {
+            conn,
+            cursor,
+            names,
+            buffer: VecDeque::new(),
+            fetch_size,
+            started: false,
+            done: false,
+        }
+    }
+
+    /// Set the rows fetched per round trip for this cursor
+    ///
+    /// Overrides the fetch array size of the pool.
+    pub fn fetch_size(mut self, rows: u32) -> Self {
+        self.fetch_size = rows.max(1);
+        self
+    }
+
+    /// Column names of the cursor
+    pub fn column_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Next row, or `None` once the cursor is exhausted
+    ///
+    /// Rows carry their column names, so they can be read with
+    /// `Row::get_by_name()` or mapped with [`FromRow`].
+    pub async fn next(&mut self) -> Result<Option<Row>, Error> {
+        if self.buffer.is_empty() && !self.done {
+            self.fetch().await?;
+        }
+        Ok(self.buffer.pop_front())
+    }
+
+    /// Next row mapped to `T`
+    pub async fn next_as<T: FromRow>(&mut self) -> Result<Option<T>, Error> {
+        match self.next().await? {
+            Some(row) => T::from_row(&row).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch all remaining rows mapped to `T`
+    pub async fn collect_as<T: FromRow>(mut self) -> Result<Vec<T>, Error> {
+        let mut items = Vec::new();
+        while let Some(item) = self.next_as().await? {
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    async fn fetch(&mut self) -> Result<(), Error> {
+        let result = if self.started {
+            self.conn
+                .fetch_more(
+                    self.cursor.cursor_id(),
+                    self.cursor.columns(),
+                    self.fetch_size,
+                )
+                .await?
+        } else {
+            self.started = true;
+            self.conn
+                .fetch_cursor_with_size(&self.cursor, self.fetch_size)
+                .await?
+        };
+
+        self.done = !result.has_more_rows || result.rows.is_empty();
+        self.buffer.extend(
+            result
+                .rows
+                .into_iter()
+                .map(|row| Row::with_names(row.into_values(), self.names.clone())),
+        );
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for CursorRows<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CursorRows")
+            .field("cursor_id", &self.cursor.cursor_id())
+            .field("columns", &self.names)
+            .field("buffered", &self.buffer.len())
+            .field("done", &self.done)
+            .finish()
+    }
+}
@@ -0,0 +1,106 @@
+//! Following DNS changes of the database host
+//!
+//! The driver resolves the host name on every new connection, but pooled
+//! connections stay with the address they were opened to. When the name is
+//! moved to another host, for example by flipping a CNAME to a standby, the
+//! pool would keep using the old one for as long as its connections live.
+//! The manager therefore remembers the addresses the host resolved to,
+//! resolves it again periodically and after failed connection attempts, and
+//! retires all connections once the addresses change.
+
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Last resolution of the database host
+#[derive(Debug)]
+pub(crate) struct DnsWatch {
+    interval: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Host and port resolved, with their sorted addresses
+    resolved: Option<(String, u16, Vec<SocketAddr>)>,
+    checked_at: Option<Instant>,
+}
+
+impl DnsWatch {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Claim a refresh at `now` if the interval has passed since the last
+    /// one, or at once with `force`
+    pub(crate) fn claim(&self, force: bool, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let due = force
+            || state.checked_at.map_or(true, |checked_at| {
+                now.saturating_duration_since(checked_at) >= self.interval
+            });
+        if due {
+            state.checked_at = Some(now);
+        }
+        due
+    }
+
+    /// Remember the addresses `host` resolved to, returning whether they
+    /// differ from the previous resolution of the same host
+    pub(crate) fn update(&self, host: &str, port: u16, mut addresses: Vec<SocketAddr>) -> bool {
+        if addresses.is_empty() {
+            return false;
+        }
+        addresses.sort();
+        addresses.dedup();
+
+        let mut state = self.state.lock().unwrap();
+        let changed = state
+            .resolved
+            .as_ref()
+            .is_some_and(|(h, p, previous)| h == host && *p == port && *previous != addresses);
+        state.resolved = Some((host.to_string(), port, addresses));
+        changed
+    }
+}
+
+/// Resolve `host` to its addresses, empty if the lookup fails
+pub(crate) async fn resolve(host: &str, port: u16) -> Vec<SocketAddr> {
+    match tokio::net::lookup_host((host, port)).await {
+        Ok(addresses) => addresses.collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dns_watch() {
+        let watch = DnsWatch::new(Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(watch.claim(false, now));
+        assert!(!watch.claim(false, now));
+        assert!(watch.claim(true, now));
+        assert!(watch.claim(false, now + Duration::from_secs(60)));
+
+        let primary: SocketAddr = "10.0.0.1:1521".parse().unwrap();
+        let standby: SocketAddr = "10.0.0.2:1521".parse().unwrap();
+        assert!(!watch.update("db", 1521, vec![primary, standby]));
+        assert!(!watch.update("db", 1521, vec![standby, primary]));
+        // Failed lookups keep the previous addresses
+        assert!(!watch.update("db", 1521, Vec::new()));
+        assert!(watch.update("db", 1521, vec![standby]));
+        // A different host is a new baseline
+        assert!(!watch.update("db-2", 1521, vec![primary]));
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        assert!(!resolve("localhost", 1521).await.is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+//! Database Resident Connection Pooling
+//!
+//! With DRCP, sessions are served by a pool of server processes on the
+//! database host instead of a dedicated process each, so many client pools
+//! can share a bounded number of servers. A connection asks for a pooled
+//! server with `(SERVER=POOLED)` in the connect data, optionally with a
+//! connection class, which lets sessions and their state be shared among
+//! connections of the same application, and a purity, which says whether
+//! such a session may be reused.
+//!
+//! The driver takes the connect data only from the service name or SID, so
+//! the DRCP parameters are appended to it when connecting.
+
+use oracle_rs::config::ServiceMethod;
+use oracle_rs::{Config, DrcpOptions, Error, SessionPurity};
+
+/// Connect data requesting a pooled server with `options`
+fn connect_data(options: &DrcpOptions) -> Result<String, Error> {
+    let mut data = String::from(")(SERVER=POOLED");
+    if let Some(class) = &options.connection_class {
+        let valid = !class.is_empty()
+            && class.len() <= 128
+            && class
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '#' | '.' | '-'));
+        if !valid {
+            return Err(Error::InvalidConnectionString(format!(
+                "invalid DRCP connection class: {}",
+                class
+            )));
+        }
+        data.push_str(&format!(")(POOL_CONNECTION_CLASS={}", class));
+    }
+    match options.purity {
+        SessionPurity::Default => {}
+        SessionPurity::New => data.push_str(")(POOL_PURITY=NEW"),
+        SessionPurity::Self_ => data.push_str(")(POOL_PURITY=SELF"),
+    }
+    Ok(data)
+}
+
+/// Make `config` request a pooled server, unless `options` are disabled
+pub(crate) fn apply(config: &mut Config, options: &DrcpOptions) -> Result<(), Error> {
+    if !options.is_enabled() {
+        return Ok(());
+    }
+    let data = connect_data(options)?;
+    match &mut config.service {
+        ServiceMethod::ServiceName(name) | ServiceMethod::Sid(name) => name.push_str(&data),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drcp_connect_data() {
+        let mut config = Config::new("db", 1521, "ORDERS", "app", "secret");
+        let options = DrcpOptions::new()
+            .with_connection_class("ORDERS_APP")
+            .with_purity(SessionPurity::Self_);
+        apply(&mut config, &options).unwrap();
+        assert_eq!(
+            config.build_connect_string(),
+            "(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST=db)(PORT=1521))\
+             (CONNECT_DATA=(SERVICE_NAME=ORDERS)(SERVER=POOLED)\
+             (POOL_CONNECTION_CLASS=ORDERS_APP)(POOL_PURITY=SELF)))"
+        );
+
+        let mut config = Config::with_sid("db", 1521, "ORCL", "app", "secret");
+        apply(&mut config, &DrcpOptions::new()).unwrap();
+        assert!(config
+            .build_connect_string()
+            .contains("(CONNECT_DATA=(SID=ORCL)(SERVER=POOLED))"));
+
+        let mut config = Config::new("db", 1521, "ORDERS", "app", "secret");
+        apply(&mut config, &DrcpOptions::new().disabled()).unwrap();
+        assert_eq!(config.service.service_name(), Some("ORDERS"));
+
+        let options = DrcpOptions::new().with_connection_class("APP)(SERVER=DEDICATED");
+        assert!(apply(&mut config, &options).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! One error type for application code
+//!
+//! `Pool::get()` fails with a [`PoolError`], wrapping the driver's error
+//! when a connection could not be created, while statements run on a
+//! checkout fail with the driver's error itself. [`DbError`] takes either
+//! through `?` and answers the questions error handling usually asks, such
+//! as the ORA code or whether a unique constraint was violated, without
+//! matching on both layers.
+
+use crate::{is_serialization_failure, is_session_killed, is_transient_connect_error};
+use crate::{PoolError, CALL_TIMEOUT_CODE};
+use deadpool::managed;
+use oracle_rs::Error;
+use std::fmt;
+
+/// ORA-00001: unique constraint violated
+const UNIQUE_VIOLATION_CODE: u32 = 1;
+
+/// ORA-00060: deadlock detected while waiting for resource
+const DEADLOCK_CODE: u32 = 60;
+
+/// ORA-01400: cannot insert NULL
+const NOT_NULL_VIOLATION_CODE: u32 = 1400;
+
+/// ORA-01403: no data found
+const NO_DATA_FOUND_CODE: u32 = 1403;
+
+/// ORA codes of violated foreign keys
+///
+/// - ORA-02291: parent key not found
+/// - ORA-02292: child record found
+const FOREIGN_KEY_VIOLATION_CODES: &[u32] = &[2291, 2292];
+
+/// ORA-02290: check constraint violated
+const CHECK_VIOLATION_CODE: u32 = 2290;
+
+/// Error of a checkout or a statement
+///
+/// Converts from [`PoolError`] and `oracle_rs::Error`, so a function can
+/// use `?` on both. A `PoolError::Backend` becomes `DbError::Oracle`, as
+/// it is the database or the driver that failed.
+///
+/// ```rust,no_run
+/// # use oracle_rs::Value;
+/// use deadpool_oracle::{DbError, Pool};
+///
+/// async fn register(pool: &Pool, email: &str) -> Result<bool, DbError> {
+///     let conn = pool.get().await?;
+///     let sql = "INSERT INTO users (email) VALUES (:1)";
+///     let email = Value::String(email.to_string());
+///     match conn.execute(sql, &[email]).await.map_err(DbError::from) {
+///         Err(e) if e.is_unique_violation() => return Ok(false),
+///         result => result?,
+///     };
+///     conn.commit().await?;
+///     Ok(true)
+/// }
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DbError {
+    /// No connection could be checked out, without the database or the
+    /// driver failing: the wait timed out, the pool is closed, ...
+    Pool(PoolError),
+    /// Reported by the database or the driver
+    Oracle(Error),
+}
+
+impl DbError {
+    /// The ORA error code, e.g. 1 for ORA-00001
+    pub fn code(&self) -> Option<u32> {
+        self.as_oracle().and_then(ora_code)
+    }
+
+    /// The error of the database or the driver, if it is one
+    pub fn as_oracle(&self) -> Option<&Error> {
+        match self {
+            DbError::Oracle(e) => Some(e),
+            DbError::Pool(_) => None,
+        }
+    }
+
+    /// Whether a unique constraint was violated (ORA-00001)
+    pub fn is_unique_violation(&self) -> bool {
+        self.code() == Some(UNIQUE_VIOLATION_CODE)
+    }
+
+    /// Whether a foreign key was violated (ORA-02291, ORA-02292)
+    pub fn is_foreign_key_violation(&self) -> bool {
+        self.code()
+            .is_some_and(|code| FOREIGN_KEY_VIOLATION_CODES.contains(&code))
+    }
+
+    /// Whether a check constraint was violated (ORA-02290)
+    pub fn is_check_violation(&self) -> bool {
+        self.code() == Some(CHECK_VIOLATION_CODE)
+    }
+
+    /// Whether NULL was inserted into a NOT NULL column (ORA-01400)
+    pub fn is_not_null_violation(&self) -> bool {
+        self.code() == Some(NOT_NULL_VIOLATION_CODE)
+    }
+
+    /// Whether the statement was chosen as the victim of a deadlock
+    /// (ORA-00060)
+    pub fn is_deadlock(&self) -> bool {
+        self.code() == Some(DEADLOCK_CODE)
+    }
+
+    /// Whether a serializable transaction could not serialize access
+    /// (ORA-08177)
+    pub fn is_serialization_failure(&self) -> bool {
+        self.as_oracle().is_some_and(is_serialization_failure)
+    }
+
+    /// Whether a query returned no rows where one was expected (ORA-01403)
+    pub fn is_no_data_found(&self) -> bool {
+        self.as_oracle().is_some_and(Error::is_no_data_found)
+            || self.code() == Some(NO_DATA_FOUND_CODE)
+    }
+
+    /// Whether the checkout, connecting or a call timed out (ORA-03156)
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            DbError::Pool(e) => matches!(e, managed::PoolError::Timeout(_)),
+            DbError::Oracle(Error::ConnectionTimeout(_)) => true,
+            DbError::Oracle(_) => self.code() == Some(CALL_TIMEOUT_CODE),
+        }
+    }
+
+    /// Whether running the transaction again may succeed
+    ///
+    /// True for timeouts, lost connections, listener errors that go away
+    /// once the database is up, killed sessions, deadlocks and
+    /// serialization failures. A connection lost during a commit may have
+    /// committed, so a transaction that isn't idempotent needs a way to
+    /// tell before it is run again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DbError::Pool(e) => matches!(e, managed::PoolError::Timeout(_)),
+            DbError::Oracle(e) => {
+                is_transient_connect_error(e)
+                    || is_session_killed(e)
+                    || is_serialization_failure(e)
+                    || self.is_deadlock()
+                    || self.is_timeout()
+            }
+        }
+    }
+}
+
+impl From<PoolError> for DbError {
+    fn from(error: PoolError) -> Self {
+        match error {
+            managed::PoolError::Backend(e) => DbError::Oracle(e),
+            error => DbError::Pool(error),
+        }
+    }
+}
+
+impl From<Error> for DbError {
+    fn from(error: Error) -> Self {
+        DbError::Oracle(error)
+    }
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::Pool(e) => e.fmt(f),
+            DbError::Oracle(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DbError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DbError::Pool(e) => e.source(),
+            DbError::Oracle(e) => e.source(),
+        }
+    }
+}
+
+/// ORA error code of a driver error
+pub(crate) fn ora_code(err: &Error) -> Option<u32> {
+    match err {
+        Error::OracleError { code, .. } | Error::ServerError { code, .. } => Some(*code),
+        Error::ConnectionRefused { error_code, .. } => *error_code,
+        Error::InvalidServiceName { .. } => Some(12514),
+        Error::InvalidSid { .. } => Some(12505),
+        Error::InvalidCredentials => Some(1017),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_db_error() {
+        let unique = DbError::from(Error::oracle(1, "unique constraint violated"));
+        assert_eq!(unique.code(), Some(1));
+        assert!(unique.is_unique_violation());
+        assert!(!unique.is_retryable());
+        assert_eq!(unique.to_string(), "ORA-00001: unique constraint violated");
+
+        let deadlock = DbError::from(Error::oracle(60, "deadlock detected"));
+        assert!(deadlock.is_deadlock());
+        assert!(deadlock.is_retryable());
+
+        let parent = DbError::from(Error::oracle(2291, "parent key not found"));
+        assert!(parent.is_foreign_key_violation());
+        assert!(!parent.is_unique_violation());
+        assert!(DbError::from(Error::oracle(2290, "check")).is_check_violation());
+        assert!(DbError::from(Error::oracle(1400, "null")).is_not_null_violation());
+        assert!(DbError::from(Error::oracle(8177, "serialize")).is_serialization_failure());
+        assert!(DbError::from(Error::NoDataFound).is_no_data_found());
+        assert!(DbError::from(Error::oracle(3156, "timed out")).is_timeout());
+
+        // Backend errors of the pool are unwrapped
+        let refused = DbError::from(PoolError::Backend(Error::InvalidCredentials));
+        assert!(matches!(
+            refused,
+            DbError::Oracle(Error::InvalidCredentials)
+        ));
+        assert_eq!(refused.code(), Some(1017));
+        assert!(!refused.is_retryable());
+
+        let timeout = DbError::from(PoolError::Timeout(managed::TimeoutType::Wait));
+        assert!(timeout.is_timeout());
+        assert!(timeout.is_retryable());
+        assert_eq!(timeout.code(), None);
+        assert!(timeout.as_oracle().is_none());
+
+        let closed = DbError::from(PoolError::Closed);
+        assert!(!closed.is_retryable());
+        assert!(!closed.is_timeout());
+    }
+}
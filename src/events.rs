@@ -0,0 +1,148 @@
+//! Pool lifecycle events
+//!
+//! Events are delivered over a broadcast channel, so any number of
+//! subscribers can observe them. Nothing is buffered when nobody is
+//! subscribed.
+
+use crate::EvictionReason;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Number of events buffered per subscriber before it starts lagging
+const EVENT_CAPACITY: usize = 256;
+
+/// Lifecycle event emitted by a pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PoolEvent {
+    /// A new connection was established
+    ConnectionCreated {
+        /// Id of the new connection
+        connection_id: u32,
+    },
+    /// A connection was removed from the pool during recycle
+    ConnectionEvicted {
+        /// Id of the evicted connection
+        connection_id: u32,
+        /// Why the connection was evicted
+        reason: EvictionReason,
+    },
+    /// `get()` gave up waiting for a connection
+    AcquireTimeout {
+        /// How long the caller waited
+        wait: Duration,
+    },
+    /// `get()` took longer than the configured slow-acquire threshold
+    SlowAcquire {
+        /// How long the caller waited
+        wait: Duration,
+        /// Number of connections in the pool
+        size: usize,
+        /// Number of idle connections in the pool
+        available: usize,
+        /// Number of callers waiting for a connection
+        waiting: usize,
+    },
+    /// A connection has been checked out for longer than the leak detection threshold
+    ConnectionLeaked {
+        /// Id of the connection still checked out
+        connection_id: u32,
+        /// How long the connection had been held when reported
+        held: Duration,
+        /// Backtrace of the checkout, if capturing is enabled
+        backtrace: Option<String>,
+    },
+    /// A health check (rollback or ping) failed during recycle
+    RecycleFailed {
+        /// Id of the connection being recycled
+        connection_id: u32,
+        /// Error reported by the driver
+        error: String,
+    },
+    /// A transaction was open longer than the maximum transaction duration
+    LongTransaction {
+        /// Id of the connection holding the transaction
+        connection_id: u32,
+        /// How long the transaction had been open when reported
+        held: Duration,
+    },
+    /// Committing a connection with commit-on-drop enabled failed
+    CommitOnDropFailed {
+        /// Id of the connection
+        connection_id: u32,
+        /// Error reported by the driver
+        error: String,
+    },
+    /// New connections go to a different endpoint than before
+    EndpointFailover {
+        /// The endpoint now connected to, as `host:port/service`
+        endpoint: String,
+    },
+    /// The database host resolves to new addresses, so all connections
+    /// were retired
+    HostAddressChanged {
+        /// Host name from the `Config`
+        host: String,
+    },
+    /// Consecutive create or recycle failures opened the circuit breaker
+    CircuitOpened {
+        /// Number of consecutive failures
+        failures: u32,
+        /// Time until the first probe
+        cool_down: Duration,
+    },
+    /// A successful connection closed the circuit breaker
+    CircuitClosed,
+}
+
+/// Broadcast channel distributing events to subscribers
+#[derive(Clone)]
+pub(crate) struct EventBus {
+    sender: broadcast::Sender<PoolEvent>,
+}
+
+impl EventBus {
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<PoolEvent> {
+        self.sender.subscribe()
+    }
+
+    pub(crate) fn emit(&self, event: PoolEvent) {
+        // Sending only fails when there are no subscribers
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { sender }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_bus_delivers_to_subscribers() {
+        let bus = EventBus::default();
+
+        // Emitting without subscribers is a no-op
+        bus.emit(PoolEvent::ConnectionCreated { connection_id: 1 });
+
+        let mut receiver = bus.subscribe();
+        bus.emit(PoolEvent::ConnectionEvicted {
+            connection_id: 2,
+            reason: EvictionReason::SessionKilled,
+        });
+
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            PoolEvent::ConnectionEvicted {
+                connection_id: 2,
+                reason: EvictionReason::SessionKilled,
+            }
+        );
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! Connections checked out for web request handlers
+//!
+//! [`DbConn`] and [`DbTransaction`] are handler arguments of the web
+//! framework integrations (`axum`, `actix`, `rocket` and `warp` features):
+//! the connection is checked out of the application's [`Pool`] before the
+//! handler runs. A checkout that fails rejects the request with 503 Service
+//! Unavailable.
+//! The `tower` middleware fails requests with the same [`PoolRejection`].
+
+use crate::transaction::PendingRollback;
+use crate::{Object, Pool, PoolError, Transaction};
+use deadpool::managed;
+use oracle_rs::Error;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// A pooled connection checked out for a request handler
+pub struct DbConn(pub Object);
+
+impl DbConn {
+    // Only the framework extractors check out by themselves
+    #[cfg_attr(
+        not(any(
+            feature = "actix",
+            feature = "axum",
+            feature = "rocket",
+            feature = "warp"
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) async fn checkout(pool: &Pool) -> Result<Self, PoolRejection> {
+        Ok(Self(pool.get().await?))
+    }
+}
+
+impl Deref for DbConn {
+    type Target = Object;
+
+    fn deref(&self) -> &Object {
+        &self.0
+    }
+}
+
+impl DerefMut for DbConn {
+    fn deref_mut(&mut self) -> &mut Object {
+        &mut self.0
+    }
+}
+
+/// A pooled connection with a transaction open, checked out for a request handler
+///
+/// Statements run through it belong to one transaction, which is only
+/// committed by [`commit()`](Self::commit). If the handler returns without
+/// committing, for example on an error, the transaction is rolled back
+/// when the connection is returned, even with commit-on-drop enabled.
+/// Savepoints and commit hooks need a [`Transaction`] guard from
+/// [`DbConn`] and [`Object::begin()`] instead.
+pub struct DbTransaction {
+    conn: Object,
+    finished: bool,
+}
+
+impl DbTransaction {
+    #[cfg_attr(
+        not(any(
+            feature = "actix",
+            feature = "axum",
+            feature = "rocket",
+            feature = "warp"
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) async fn begin(pool: &Pool) -> Result<Self, PoolRejection> {
+        let mut conn = pool.get().await?;
+        conn.start_transaction()
+            .await
+            .map_err(managed::PoolError::Backend)?;
+        Ok(Self {
+            conn,
+            finished: false,
+        })
+    }
+
+    /// Commit the transaction
+    pub async fn commit(mut self) -> Result<(), Error> {
+        Transaction::new(&mut self.conn).commit().await?;
+        self.finished = true;
+        Ok(())
+    }
+
+    /// Roll back the transaction
+    pub async fn rollback(mut self) -> Result<(), Error> {
+        Transaction::new(&mut self.conn).rollback().await?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Deref for DbTransaction {
+    type Target = Object;
+
+    fn deref(&self) -> &Object {
+        &self.conn
+    }
+}
+
+impl Drop for DbTransaction {
+    fn drop(&mut self) {
+        if !self.finished {
+            *self.conn.rollback_pending.get_mut().unwrap() = Some(PendingRollback::Transaction);
+        }
+    }
+}
+
+/// Rejection of [`DbConn`] and [`DbTransaction`] when no connection could be checked out
+///
+/// Responds with 503 Service Unavailable, as the pool is exhausted or the
+/// database unreachable. The error is available to handlers that take the
+/// extractor wrapped in a `Result`.
+#[derive(Debug)]
+pub struct PoolRejection(pub PoolError);
+
+impl From<PoolError> for PoolRejection {
+    fn from(error: PoolError) -> Self {
+        Self(error)
+    }
+}
+
+impl fmt::Display for PoolRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "database unavailable: {}", self.0)
+    }
+}
+
+impl std::error::Error for PoolRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{Pool, PoolBuilder};
+    use oracle_rs::Config;
+    use std::time::Duration;
+
+    /// A pool whose checkouts fail, as nothing listens on port 1
+    pub(crate) fn unreachable_pool() -> Pool {
+        let config = Config::new("127.0.0.1", 1, "FREEPDB1", "user", "password");
+        PoolBuilder::new(config)
+            .max_size(1)
+            .create_timeout(Some(Duration::from_secs(1)))
+            .build()
+            .unwrap()
+    }
+}
@@ -0,0 +1,307 @@
+//! Typed mapping of query results
+//!
+//! [`FromValue`] converts a single column, [`FromRow`] a whole row. Rows can
+//! be mapped to tuples by position, or to structs by column name with
+//! `#[derive(FromRow)]` when the `derive` feature is enabled.
+
+use crate::Object;
+use oracle_rs::types::{OracleDate, OracleTimestamp};
+use oracle_rs::{Error, Row, Value};
+
+/// Conversion from a column value
+pub trait FromValue: Sized {
+    /// Convert the value, failing with `Error::DataConversionError` if it
+    /// does not fit
+    fn from_value(value: &Value) -> Result<Self, Error>;
+}
+
+/// Conversion from a result row
+///
+/// Implemented for tuples (columns by position) and, with the `derive`
+/// feature, for structs (columns by field name, case-insensitive):
+///
+/// ```rust,ignore
+/// use deadpool_oracle::FromRow;
+///
+/// #[derive(FromRow)]
+/// struct Employee {
+///     id: i64,
+///     #[oracle(rename = "LAST_NAME")]
+///     name: String,
+///     manager_id: Option<i64>,
+/// }
+///
+/// let employees: Vec<Employee> = conn
+///     .query_as("SELECT id, last_name, manager_id FROM employees", &[])
+///     .await?;
+/// ```
+pub trait FromRow: Sized {
+    /// Map the row
+    fn from_row(row: &Row) -> Result<Self, Error>;
+}
+
+fn conversion_error(value: &Value, target: &str) -> Error {
+    Error::DataConversionError(format!("cannot convert {:?} to {}", value, target))
+}
+
+/// Get a column by name and convert it
+///
+/// Used by `#[derive(FromRow)]`.
+pub fn column<T: FromValue>(row: &Row, name: &str) -> Result<T, Error> {
+    let value = row
+        .get_by_name(name)
+        .ok_or_else(|| Error::DataConversionError(format!("no column named {}", name)))?;
+    T::from_value(value).map_err(|e| Error::DataConversionError(format!("column {}: {}", name, e)))
+}
+
+/// Get a column by position and convert it
+fn column_at<T: FromValue>(row: &Row, index: usize) -> Result<T, Error> {
+    let value = row
+        .get(index)
+        .ok_or_else(|| Error::DataConversionError(format!("no column at index {}", index)))?;
+    T::from_value(value)
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        Ok(value.clone())
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::from_value(value).map(Some)
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            Value::Number(n) => Ok(n.as_str().to_string()),
+            Value::Integer(i) => Ok(i.to_string()),
+            Value::Float(f) => Ok(f.to_string()),
+            _ => Err(conversion_error(value, "String")),
+        }
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        match value {
+            // Numbers fetched as text, e.g. COUNT(*) on some servers
+            Value::String(s) => s.trim().parse().ok(),
+            _ => value.as_i64(),
+        }
+        .ok_or_else(|| conversion_error(value, "i64"))
+    }
+}
+
+macro_rules! from_value_via_i64 {
+    ($($ty:ty),*) => {
+        $(
+            impl FromValue for $ty {
+                fn from_value(value: &Value) -> Result<Self, Error> {
+                    i64::from_value(value)?
+                        .try_into()
+                        .map_err(|_| conversion_error(value, stringify!($ty)))
+                }
+            }
+        )*
+    };
+}
+
+from_value_via_i64!(i8, i16, i32, u8, u16, u32, u64, usize);
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::String(s) => s.trim().parse().ok(),
+            _ => value.as_f64(),
+        }
+        .ok_or_else(|| conversion_error(value, "f64"))
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        f64::from_value(value).map(|f| f as f32)
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::Number(n) => n.to_i64().ok().map(|i| i != 0),
+            _ => value.as_bool(),
+        }
+        .ok_or_else(|| conversion_error(value, "bool"))
+    }
+}
+
+impl FromValue for Vec<u8> {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::Bytes(b) => Ok(b.clone()),
+            _ => Err(conversion_error(value, "Vec<u8>")),
+        }
+    }
+}
+
+impl FromValue for OracleDate {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        value
+            .as_date()
+            .cloned()
+            .ok_or_else(|| conversion_error(value, "OracleDate"))
+    }
+}
+
+impl FromValue for OracleTimestamp {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        value
+            .as_timestamp()
+            .cloned()
+            .ok_or_else(|| conversion_error(value, "OracleTimestamp"))
+    }
+}
+
+impl FromRow for Row {
+    fn from_row(row: &Row) -> Result<Self, Error> {
+        Ok(row.clone())
+    }
+}
+
+macro_rules! from_row_tuple {
+    ($($ty:ident $index:tt),+) => {
+        impl<$($ty: FromValue),+> FromRow for ($($ty,)+) {
+            fn from_row(row: &Row) -> Result<Self, Error> {
+                Ok(($(column_at::<$ty>(row, $index)?,)+))
+            }
+        }
+    };
+}
+
+from_row_tuple!(A 0);
+from_row_tuple!(A 0, B 1);
+from_row_tuple!(A 0, B 1, C 2);
+from_row_tuple!(A 0, B 1, C 2, D 3);
+from_row_tuple!(A 0, B 1, C 2, D 3, E 4);
+from_row_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// Run a query and map every row
+pub(crate) async fn query_as<T: FromRow>(
+    conn: &Object,
+    sql: &str,
+    params: &[Value],
+) -> Result<Vec<T>, Error> {
+    let result = conn.query_all(sql, params).await?;
+    let names: Vec<String> = result.columns.iter().map(|c| c.name.clone()).collect();
+
+    result
+        .rows
+        .into_iter()
+        .map(|row| {
+            // Attach the names so rows can be mapped by column name
+            let row = Row::with_names(row.into_values(), names.clone());
+            T::from_row(&row)
+        })
+        .collect()
+}
+
+/// ORA-01422: exact fetch returns more than requested number of rows
+const TOO_MANY_ROWS_CODE: u32 = 1422;
+
+/// Take the only element, failing if there is more than one
+fn at_most_one<T>(rows: Vec<T>) -> Result<Option<T>, Error> {
+    if rows.len() > 1 {
+        return Err(Error::oracle(
+            TOO_MANY_ROWS_CODE,
+            format!("exact fetch returns {} rows instead of one", rows.len()),
+        ));
+    }
+    Ok(rows.into_iter().next())
+}
+
+/// Run a query expected to return at most one row
+pub(crate) async fn query_opt(
+    conn: &Object,
+    sql: &str,
+    params: &[Value],
+) -> Result<Option<Row>, Error> {
+    at_most_one(query_as(conn, sql, params).await?)
+}
+
+/// Run a query expected to return exactly one row
+pub(crate) async fn query_one(conn: &Object, sql: &str, params: &[Value]) -> Result<Row, Error> {
+    query_opt(conn, sql, params)
+        .await?
+        .ok_or(Error::NoDataFound)
+}
+
+/// Run a query expected to return exactly one row and convert its first column
+pub(crate) async fn query_scalar<T: FromValue>(
+    conn: &Object,
+    sql: &str,
+    params: &[Value],
+) -> Result<T, Error> {
+    let row = query_one(conn, sql, params).await?;
+    column_at(&row, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row() -> Row {
+        Row::with_names(
+            vec![
+                Value::Integer(7),
+                Value::String("SMITH".to_string()),
+                Value::Null,
+            ],
+            vec![
+                "ID".to_string(),
+                "NAME".to_string(),
+                "MANAGER_ID".to_string(),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_at_most_one() {
+        assert_eq!(at_most_one(Vec::<i64>::new()).unwrap(), None);
+        assert_eq!(at_most_one(vec![1]).unwrap(), Some(1));
+
+        let err = at_most_one(vec![1, 2]).unwrap_err();
+        assert!(matches!(err, Error::OracleError { code: 1422, .. }));
+    }
+
+    #[test]
+    fn test_from_value_conversions() {
+        assert_eq!(
+            i64::from_value(&Value::String("42".to_string())).unwrap(),
+            42
+        );
+        assert_eq!(i32::from_value(&Value::Integer(-1)).unwrap(), -1);
+        assert!(u8::from_value(&Value::Integer(300)).is_err());
+        assert_eq!(String::from_value(&Value::Integer(5)).unwrap(), "5");
+        assert!(bool::from_value(&Value::Integer(1)).unwrap());
+        assert_eq!(Option::<i64>::from_value(&Value::Null).unwrap(), None);
+        assert!(i64::from_value(&Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_from_row_tuple_and_columns() {
+        let row = row();
+        let (id, name, manager): (i64, String, Option<i64>) = FromRow::from_row(&row).unwrap();
+        assert_eq!((id, name.as_str(), manager), (7, "SMITH", None));
+
+        assert_eq!(column::<String>(&row, "name").unwrap(), "SMITH");
+        assert!(column::<i64>(&row, "salary").is_err());
+    }
+}
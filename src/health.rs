@@ -0,0 +1,51 @@
+//! Health checks for readiness probes
+
+use crate::Status;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Outcome of [`Pool::health_check()`](crate::Pool::health_check)
+#[derive(Debug, Clone)]
+pub struct HealthReport {
+    /// Whether a connection could be acquired and pinged within the timeout
+    pub healthy: bool,
+    /// Time spent acquiring and pinging a connection
+    pub latency: Duration,
+    /// Pool status after the check
+    pub status: Status,
+    /// Why this check failed, if it did
+    pub error: Option<String>,
+    /// Error of the most recent failed check, which may be this one
+    pub last_error: Option<String>,
+}
+
+/// Remembers the error of the most recent failed health check
+#[derive(Default)]
+pub(crate) struct HealthState {
+    last_error: Mutex<Option<String>>,
+}
+
+impl HealthState {
+    /// Record the outcome of a check and return the most recent error
+    pub(crate) fn record(&self, error: Option<&str>) -> Option<String> {
+        let mut last_error = self.last_error.lock().unwrap();
+        if let Some(error) = error {
+            *last_error = Some(error.to_string());
+        }
+        last_error.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_state_keeps_last_error() {
+        let state = HealthState::default();
+        assert_eq!(state.record(None), None);
+        let error = Some("ORA-12541".to_string());
+        assert_eq!(state.record(error.as_deref()), error);
+        assert_eq!(state.record(None), error);
+    }
+}
@@ -0,0 +1,142 @@
+//! Verifying who pooled sessions are logged in as
+//!
+//! A session keeps the user it logged in as and the roles enabled at login
+//! for as long as it lives: roles granted or revoked since, and credentials
+//! rotated to a different user, only apply to new sessions. With
+//! [`PoolBuilder::expected_identity()`](crate::PoolBuilder::expected_identity)
+//! the manager compares the user and enabled roles of each connection it
+//! hands out against a [`SessionIdentity`] and evicts the sessions that
+//! differ.
+
+use oracle_rs::{Connection, Error};
+use std::collections::BTreeSet;
+
+const SESSION_USER_SQL: &str = "SELECT SYS_CONTEXT('USERENV', 'SESSION_USER') FROM DUAL";
+const SESSION_ROLES_SQL: &str = "SELECT role FROM session_roles";
+
+/// User and roles pooled sessions are expected to have
+///
+/// Names are compared case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct SessionIdentity {
+    user: Option<String>,
+    roles: Option<Vec<String>>,
+}
+
+impl SessionIdentity {
+    /// Expect the user the pool currently logs in as, with any roles
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expect `user` instead of the user the pool logs in as
+    ///
+    /// By default the expected user follows the credentials, so once they
+    /// change to a different user, sessions that connected as the previous
+    /// one are evicted. A fixed user also catches sessions connecting as a
+    /// different user than the application should run as, after the
+    /// credentials changed to the wrong account.
+    pub fn user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Expect exactly `roles` to be enabled, no more and no fewer
+    pub fn roles<I, S>(mut self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.roles = Some(roles.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+/// Check that `conn` is logged in as `expected`, `login_user` being the
+/// user the pool logs in as now
+pub(crate) async fn verify(
+    conn: &Connection,
+    expected: &SessionIdentity,
+    login_user: &str,
+) -> Result<(), Error> {
+    let result = conn.query(SESSION_USER_SQL, &[]).await?;
+    let user = result.rows.first().and_then(|row| row.get_string(0));
+    let user = user.ok_or(Error::NoDataFound)?;
+    let expected_user = expected.user.as_deref().unwrap_or(login_user);
+    if let Some(message) = user_mismatch(expected_user, user) {
+        return Err(Error::SqlError(message));
+    }
+
+    let Some(roles) = &expected.roles else {
+        return Ok(());
+    };
+    let result = conn.query(SESSION_ROLES_SQL, &[]).await?;
+    let enabled = result.rows.iter().filter_map(|row| row.get_string(0));
+    match role_mismatch(roles, enabled) {
+        Some(message) => Err(Error::SqlError(message)),
+        None => Ok(()),
+    }
+}
+
+fn user_mismatch(expected: &str, user: &str) -> Option<String> {
+    if user.eq_ignore_ascii_case(expected) {
+        return None;
+    }
+    Some(format!(
+        "session user {} is not the expected {}",
+        user, expected
+    ))
+}
+
+fn role_mismatch<'a>(
+    expected: &[String],
+    enabled: impl Iterator<Item = &'a str>,
+) -> Option<String> {
+    let expected: BTreeSet<_> = expected
+        .iter()
+        .map(|role| role.to_ascii_uppercase())
+        .collect();
+    let enabled: BTreeSet<_> = enabled.map(str::to_ascii_uppercase).collect();
+    if expected == enabled {
+        return None;
+    }
+    let missing: Vec<_> = expected.difference(&enabled).collect();
+    let unexpected: Vec<_> = enabled.difference(&expected).collect();
+    Some(format!(
+        "session roles differ from the expected ones, missing {:?}, unexpected {:?}",
+        missing, unexpected
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_mismatch() {
+        assert_eq!(user_mismatch("app", "APP"), None);
+        assert_eq!(
+            user_mismatch("APP_V2", "APP_V1").as_deref(),
+            Some("session user APP_V1 is not the expected APP_V2")
+        );
+    }
+
+    #[test]
+    fn test_role_mismatch() {
+        let expected = SessionIdentity::new().roles(["app_rw", "CONNECT"]);
+        let expected = expected.roles.unwrap();
+        assert_eq!(
+            role_mismatch(&expected, ["CONNECT", "APP_RW"].into_iter()),
+            None
+        );
+        assert_eq!(
+            role_mismatch(&expected, ["CONNECT", "DBA"].into_iter()).as_deref(),
+            Some(
+                "session roles differ from the expected ones, \
+                 missing [\"APP_RW\"], unexpected [\"DBA\"]"
+            )
+        );
+        assert!(role_mismatch(&[], ["CONNECT"].into_iter()).is_some());
+        assert_eq!(role_mismatch(&[], std::iter::empty()), None);
+    }
+}
@@ -0,0 +1,158 @@
+//! Tower middleware checking out a connection per request
+//!
+//! [`ConnectionLayer`] wraps any `tower::Service` taking `http::Request`s,
+//! which covers hyper and tonic services as well as axum routers. Before
+//! the inner service is called, a connection is checked out and put into
+//! the request extensions as a [`RequestConnection`]. Once the inner
+//! service has responded, the connection goes back to the pool; if it
+//! failed, uncommitted work is rolled back first.
+
+use crate::transaction::PendingRollback;
+use crate::{Object, Pool, PoolRejection};
+use http::Request;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Error of a failed checkout or of the inner service
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Layer adding a connection from `pool` to every request
+///
+/// Failed checkouts fail the request with a [`PoolRejection`], which can
+/// be turned into a response with the framework's error handling, such as
+/// axum's `HandleErrorLayer`.
+///
+/// ```rust,no_run
+/// use deadpool_oracle::{ConnectionLayer, Pool, RequestConnection};
+/// use http::{Request, Response};
+/// use tower::{service_fn, ServiceBuilder};
+///
+/// # fn example(pool: Pool) {
+/// let service = ServiceBuilder::new()
+///     .layer(ConnectionLayer::new(pool))
+///     .service(service_fn(|req: Request<String>| async move {
+///         let conn = req.extensions().get::<RequestConnection>().unwrap();
+///         let conn = conn.lock().await.unwrap();
+///         conn.execute("INSERT INTO requests (body) VALUES (:1)", &[req.body().clone().into()])
+///             .await?;
+///         conn.commit().await?;
+///         Ok::<_, oracle_rs::Error>(Response::new(String::new()))
+///     }));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ConnectionLayer {
+    pool: Pool,
+}
+
+impl ConnectionLayer {
+    /// Check out connections from `pool`
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+impl<S> Layer<S> for ConnectionLayer {
+    type Service = ConnectionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectionService {
+            inner,
+            pool: self.pool.clone(),
+        }
+    }
+}
+
+/// Service checking out a connection for each request, see [`ConnectionLayer`]
+#[derive(Clone)]
+pub struct ConnectionService<S> {
+    inner: S,
+    pool: Pool,
+}
+
+impl<S, B> Service<Request<B>> for ConnectionService<S>
+where
+    S: Service<Request<B>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Response: Send,
+    S::Error: Into<BoxError>,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        // Call the service that was polled ready, leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let pool = self.pool.clone();
+
+        Box::pin(async move {
+            let conn = pool.get().await.map_err(PoolRejection)?;
+            let handle = RequestConnection {
+                conn: Arc::new(Mutex::new(Some(conn))),
+            };
+            req.extensions_mut().insert(handle.clone());
+
+            let result = inner.call(req).await.map_err(Into::into);
+            // Return the connection even if the handler kept a handle to it
+            if let Some(mut conn) = handle.conn.lock().await.take() {
+                if result.is_err() {
+                    *conn.rollback_pending.get_mut().unwrap() = Some(PendingRollback::Transaction);
+                }
+            }
+            result
+        })
+    }
+}
+
+/// Connection of the current request, put into the request extensions by [`ConnectionLayer`]
+///
+/// Uncommitted work is rolled back if the inner service fails. Otherwise
+/// it is committed if the pool commits on drop, and rolled back when the
+/// connection is recycled if not.
+#[derive(Clone)]
+pub struct RequestConnection {
+    conn: Arc<Mutex<Option<Object>>>,
+}
+
+impl RequestConnection {
+    /// Lock the connection for use
+    ///
+    /// Returns `None` once the request has been responded to and the
+    /// connection returned to the pool.
+    pub async fn lock(&self) -> Option<MappedMutexGuard<'_, Object>> {
+        MutexGuard::try_map(self.conn.lock().await, Option::as_mut).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::tests::unreachable_pool;
+    use deadpool::managed;
+    use std::convert::Infallible;
+    use tower::{service_fn, ServiceExt};
+
+    #[tokio::test]
+    async fn test_failed_checkout_fails_request() {
+        let pool = unreachable_pool();
+        let service = ConnectionLayer::new(pool).layer(service_fn(|_req: Request<()>| async {
+            Ok::<_, Infallible>("called")
+        }));
+
+        let error = service.oneshot(Request::new(())).await.unwrap_err();
+        let rejection = error.downcast::<PoolRejection>().unwrap();
+        assert!(matches!(rejection.0, managed::PoolError::Backend(_)));
+    }
+}
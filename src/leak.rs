@@ -0,0 +1,249 @@
+//! Detection of connections that are held for too long
+//!
+//! Every checkout spawns a timer on the pool's clock that fires once the
+//! configured threshold has passed. Returning the connection cancels the timer, so only
+//! connections that are still checked out get reported, and each of them
+//! only once.
+//!
+//! A deterministic pool spawns no timers. Its checkouts are timed on the
+//! clock instead and reported when they are returned, if they were held past
+//! the threshold.
+
+use crate::events::EventBus;
+use crate::task;
+use crate::{Clock, PoolEvent};
+use std::backtrace::Backtrace;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Settings for the leak detector
+pub(crate) struct LeakDetector {
+    threshold: Duration,
+    capture_backtrace: bool,
+}
+
+impl LeakDetector {
+    pub(crate) fn new(threshold: Duration, capture_backtrace: bool) -> Self {
+        Self {
+            threshold,
+            capture_backtrace,
+        }
+    }
+
+    /// Start watching a checked out connection
+    ///
+    /// With `deterministic` set no timer is spawned; the checkout is checked
+    /// against the threshold when the watch is dropped.
+    pub(crate) fn watch(
+        &self,
+        connection_id: u32,
+        events: EventBus,
+        clock: &Arc<dyn Clock>,
+        deterministic: bool,
+    ) -> LeakWatch {
+        let leak = Leak {
+            connection_id,
+            threshold: self.threshold,
+            backtrace: self.capture_backtrace.then(Backtrace::force_capture),
+            events,
+        };
+
+        if deterministic {
+            return LeakWatch::OnReturn {
+                leak: Some(leak),
+                started: clock.now(),
+                clock: clock.clone(),
+            };
+        }
+
+        let sleep = clock.sleep(self.threshold);
+        let handle = task::spawn("oracle_pool.leak_watch", async move {
+            sleep.await;
+            leak.report();
+        });
+
+        LeakWatch::Timer(handle)
+    }
+}
+
+/// A checkout held past the threshold
+pub(crate) struct Leak {
+    connection_id: u32,
+    threshold: Duration,
+    backtrace: Option<Backtrace>,
+    events: EventBus,
+}
+
+impl Leak {
+    fn report(self) {
+        let Leak {
+            connection_id,
+            threshold,
+            backtrace,
+            events,
+        } = self;
+        let backtrace = backtrace.map(|b| b.to_string());
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            connection_id,
+            held = ?threshold,
+            backtrace = backtrace.as_deref().unwrap_or("<not captured>"),
+            "possible connection leak: connection held longer than the leak detection threshold"
+        );
+
+        events.emit(PoolEvent::ConnectionLeaked {
+            connection_id,
+            held: threshold,
+            backtrace,
+        });
+    }
+}
+
+/// Watch over a single checkout, cancelled or settled when dropped
+pub(crate) enum LeakWatch {
+    /// Timer task reporting the leak while the connection is still out
+    Timer(JoinHandle<()>),
+    /// Hold time checked on return, for deterministic pools
+    OnReturn {
+        leak: Option<Leak>,
+        started: Instant,
+        clock: Arc<dyn Clock>,
+    },
+}
+
+impl Drop for LeakWatch {
+    fn drop(&mut self) {
+        match self {
+            LeakWatch::Timer(handle) => handle.abort(),
+            LeakWatch::OnReturn {
+                leak,
+                started,
+                clock,
+            } => {
+                let held = clock.now().saturating_duration_since(*started);
+                if let Some(leak) = leak.take().filter(|leak| held >= leak.threshold) {
+                    leak.report();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokioClock;
+
+    fn clock() -> Arc<dyn Clock> {
+        Arc::new(TokioClock)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_leak_reported_once_after_threshold() {
+        let events = EventBus::default();
+        let mut receiver = events.subscribe();
+        let detector = LeakDetector::new(Duration::from_secs(10), false);
+
+        let _watch = detector.watch(7, events.clone(), &clock(), false);
+        tokio::time::sleep(Duration::from_secs(11)).await;
+
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            PoolEvent::ConnectionLeaked {
+                connection_id: 7,
+                held: Duration::from_secs(10),
+                backtrace: None,
+            }
+        );
+
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert!(receiver.try_recv().is_err());
+    }
+
+    /// A clock whose timers all fire at once
+    struct ElapsedClock;
+
+    impl Clock for ElapsedClock {
+        fn now(&self) -> std::time::Instant {
+            std::time::Instant::now()
+        }
+
+        fn sleep(&self, _duration: Duration) -> crate::ClockSleep {
+            Box::pin(std::future::ready(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_leak_timed_on_pool_clock() {
+        let events = EventBus::default();
+        let mut receiver = events.subscribe();
+        let detector = LeakDetector::new(Duration::from_secs(3600), false);
+
+        let clock: Arc<dyn Clock> = Arc::new(ElapsedClock);
+        let _watch = detector.watch(7, events.clone(), &clock, false);
+        let reported = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await;
+        assert!(matches!(
+            reported,
+            Ok(Ok(PoolEvent::ConnectionLeaked {
+                connection_id: 7,
+                ..
+            }))
+        ));
+    }
+
+    /// A clock that only moves when told to
+    struct ManualClock(std::sync::Mutex<Instant>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+
+        fn sleep(&self, _duration: Duration) -> crate::ClockSleep {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    #[test]
+    fn test_deterministic_leak_reported_on_return() {
+        let events = EventBus::default();
+        let mut receiver = events.subscribe();
+        let detector = LeakDetector::new(Duration::from_secs(10), false);
+        let manual = Arc::new(ManualClock(std::sync::Mutex::new(Instant::now())));
+        let clock: Arc<dyn Clock> = manual.clone();
+
+        // Runs outside a runtime, so nothing can have been spawned
+        drop(detector.watch(7, events.clone(), &clock, true));
+        assert!(receiver.try_recv().is_err());
+
+        let watch = detector.watch(7, events.clone(), &clock, true);
+        *manual.0.lock().unwrap() += Duration::from_secs(11);
+        assert!(receiver.try_recv().is_err());
+        drop(watch);
+
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            PoolEvent::ConnectionLeaked {
+                connection_id: 7,
+                held: Duration::from_secs(10),
+                backtrace: None,
+            }
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_returned_connection_not_reported() {
+        let events = EventBus::default();
+        let mut receiver = events.subscribe();
+        let detector = LeakDetector::new(Duration::from_secs(10), false);
+
+        let watch = detector.watch(7, events.clone(), &clock(), false);
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        drop(watch);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+
+        assert!(receiver.try_recv().is_err());
+    }
+}
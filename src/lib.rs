@@ -31,95 +31,4113 @@
 //! }
 //! ```
 
+#[cfg(feature = "actix")]
+mod actix_extract;
+mod any;
+mod app_info;
+mod audit;
+mod autonomous;
+#[cfg(feature = "aws-secrets-manager")]
+mod aws_secrets;
+#[cfg(feature = "axum")]
+mod axum_extract;
+mod balance;
+mod batch;
+#[cfg(feature = "bb8")]
+mod bb8_adapter;
+mod call;
+mod circuit;
+mod clock;
+#[cfg(feature = "serde")]
+mod config;
+mod container;
+mod credentials;
+mod cursor;
+mod dns;
+mod drcp;
+mod error;
+mod events;
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "rocket",
+    feature = "tower",
+    feature = "warp"
+))]
+mod extract;
+mod from_row;
+mod health;
+mod identity;
+#[cfg(feature = "tower")]
+mod layer;
+mod leak;
+mod lob;
+#[cfg(feature = "metrics")]
+mod metrics_facade;
+mod named;
+mod nls;
+mod observer;
+#[cfg(feature = "otel")]
+mod otel;
+mod page;
+mod quarantine;
+#[cfg(feature = "record")]
+mod recorder;
+mod redact;
+mod registry;
+mod result_cache;
+mod returning;
+#[cfg(feature = "rocket")]
+mod rocket_fairing;
+mod schema;
+mod session_context;
+mod session_params;
+mod shard;
+mod split;
+mod statement_cache;
+mod stats;
+mod tags;
+mod task;
+mod tenant;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod tls;
+mod transaction;
+#[cfg(feature = "vault")]
+mod vault;
+#[cfg(feature = "warp")]
+mod warp_filter;
+
+use app_info::{AppInfo, AppInfos};
+use audit::Binds;
+use balance::EndpointBalancer;
+use circuit::CircuitBreaker;
+use container::SessionContainers;
+use credentials::CredentialCache;
+use cursor::DEFAULT_FETCH_ARRAY_SIZE;
 use deadpool::managed::{self, Manager, Metrics, RecycleError, RecycleResult};
-use oracle_rs::{Config, Connection, Error};
-use std::time::Duration;
+use dns::DnsWatch;
+use events::EventBus;
+use health::HealthState;
+use leak::{LeakDetector, LeakWatch};
+use observer::Observed;
+use oracle_rs::config::ServiceMethod;
+use oracle_rs::{
+    BatchBinds, BatchResult, BindParam, Config, Connection, DrcpOptions, Error, LobLocator,
+    OracleType, PlsqlResult, QueryResult, RefCursor, Row, Value,
+};
+use quarantine::Quarantine;
+use redact::{ConfigDebug, REDACTED};
+use schema::SessionSchemas;
+use session_context::{SessionContext, SessionContextHook};
+use session_params::SessionSettings;
+use shard::Shards;
+use statement_cache::StatementCaches;
+use stats::{AcquireOutcome, PoolCounters, RecycleStats};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tags::{ConnectionTags, TagFixup};
+use tls::TlsOptions;
+use transaction::{HoldLimit, HoldWatch, Hook, PendingRollback};
+
+pub use any::{AnyConnection, AnyError, AnyFuture, AnyPool, AnyRows, AnyValue};
+pub use audit::{AuditEvent, AuditSink};
+pub use autonomous::AutonomousBlock;
+#[cfg(feature = "aws-secrets-manager")]
+pub use aws_secrets::AwsSecretsManagerProvider;
+#[cfg(feature = "bb8")]
+pub use bb8_adapter::{Bb8Connection, Bb8Manager};
+pub use call::{CallParams, CallResult};
+pub use clock::{Clock, ClockSleep, TokioClock};
+#[cfg(feature = "serde")]
+pub use config::{PoolConfig, PoolsConfig};
+pub use credentials::{CredentialProvider, Credentials, CredentialsFuture};
+pub use cursor::CursorRows;
+pub use deadpool::managed::Timeouts;
+pub use deadpool::Status;
+#[cfg(feature = "derive")]
+pub use deadpool_oracle_derive::FromRow;
+pub use error::DbError;
+pub use events::PoolEvent;
+#[cfg(any(
+    feature = "actix",
+    feature = "axum",
+    feature = "rocket",
+    feature = "tower",
+    feature = "warp"
+))]
+pub use extract::{DbConn, DbTransaction, PoolRejection};
+pub use from_row::{column, FromRow, FromValue};
+pub use health::HealthReport;
+pub use identity::SessionIdentity;
+#[cfg(feature = "tower")]
+pub use layer::{ConnectionLayer, ConnectionService, RequestConnection};
+pub use lob::{LobReader, LobWriter};
+pub use named::NamedBinds;
+pub use nls::NlsConfig;
+pub use observer::{QueryEvent, QueryObserver};
+pub use page::{Page, PageRequest};
+#[cfg(feature = "record")]
+pub use recorder::{RecordedOutcome, RecordedQuery, Recorder, RecordingPool};
+pub use registry::{PoolRegistry, Pools};
+pub use result_cache::{result_cache_hint, ResultCacheMode};
+#[cfg(feature = "rocket")]
+pub use rocket_fairing::PoolFairing;
+pub use session_context::SessionContextFuture;
+pub use shard::{HashShardResolver, ShardResolver};
+pub use split::SplitPool;
+pub use statement_cache::StatementCacheMetrics;
+pub use stats::{
+    AcquireLatency, ConfigSummary, ConnectionStats, EvictionReason, LatencyHistogram, PoolSnapshot,
+    PoolStats, RecycleMetrics,
+};
+pub use tags::TagFixupFuture;
+pub use tenant::TenantPools;
+pub use transaction::{IsolationLevel, Transaction};
+#[cfg(feature = "vault")]
+pub use vault::VaultProvider;
+
+// Re-export the driver so users and derived code agree on its version
+pub use oracle_rs;
+
+/// ORA codes reported for sessions that were killed or timed out server-side
+///
+/// - ORA-00028: your session has been killed
+/// - ORA-00031: session marked for kill
+/// - ORA-01012: not logged on
+/// - ORA-02396: exceeded maximum idle time
+const SESSION_KILLED_CODES: &[u32] = &[28, 31, 1012, 2396];
+
+/// Check whether an error means the server-side session is gone
+fn is_session_killed(err: &Error) -> bool {
+    match err {
+        Error::OracleError { code, .. } | Error::ServerError { code, .. } => {
+            SESSION_KILLED_CODES.contains(code)
+        }
+        _ => false,
+    }
+}
+
+/// Why a connection whose statement failed with `err` has to be evicted, if
+/// the error means it is dead
+fn dead_connection_reason(err: &Error) -> Option<EvictionReason> {
+    if is_session_killed(err) {
+        Some(EvictionReason::SessionKilled)
+    } else if err.is_connection_error() {
+        Some(EvictionReason::Closed)
+    } else {
+        None
+    }
+}
+
+/// Check whether a login was refused for its user name or password
+fn is_invalid_credentials(err: &Error) -> bool {
+    match err {
+        Error::InvalidCredentials => true,
+        Error::OracleError { code, .. } | Error::ServerError { code, .. } => *code == 1017,
+        _ => false,
+    }
+}
+
+/// ORA-08177: can't serialize access for this transaction
+const SERIALIZATION_FAILURE_CODE: u32 = 8177;
+
+/// Check whether an error is a serialization failure worth retrying
+fn is_serialization_failure(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::OracleError { code, .. } | Error::ServerError { code, .. }
+            if *code == SERIALIZATION_FAILURE_CODE
+    )
+}
+
+/// ORA-03156: OCI call timed out
+const CALL_TIMEOUT_CODE: u32 = 3156;
+
+/// ORA codes of connection attempts that may succeed when retried
+///
+/// - ORA-01033: initialization or shutdown in progress
+/// - ORA-01034: not available
+/// - ORA-01089: immediate shutdown in progress
+/// - ORA-12505, ORA-12514: listener does not know the SID or service yet
+/// - ORA-12516, ORA-12519, ORA-12520: listener has no handler available
+/// - ORA-12521: listener does not know the instance yet
+/// - ORA-12528: listener is blocking new connections
+/// - ORA-12537: TNS connection closed
+/// - ORA-12541: no listener
+const TRANSIENT_CONNECT_CODES: &[u32] = &[
+    1033, 1034, 1089, 12505, 12514, 12516, 12519, 12520, 12521, 12528, 12537, 12541,
+];
+
+/// Check whether a failed connection attempt is worth retrying
+fn is_transient_connect_error(err: &Error) -> bool {
+    match err {
+        Error::OracleError { code, .. } | Error::ServerError { code, .. } => {
+            TRANSIENT_CONNECT_CODES.contains(code)
+        }
+        Error::InvalidServiceName { .. } | Error::InvalidSid { .. } => true,
+        _ => err.is_connection_error(),
+    }
+}
+
+/// Least time between failed credential refreshes of the background task
+const CREDENTIAL_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+tokio::task_local! {
+    /// Time spent creating connections for the checkout running in this
+    /// task, as deadpool creates them in the task of the caller
+    static CREATE_TIME: Cell<Option<Duration>>;
+
+    /// Tag `get_tagged()` is looking for among the idle connections
+    static TAG_SCAN: String;
+}
 
 /// Manager for creating and recycling Oracle connections
 ///
 /// This implements the `deadpool::managed::Manager` trait to integrate
 /// with the deadpool connection pool.
 pub struct OracleConnectionManager {
-    config: Config,
+    /// Replaced by `Pool::drain_and_refresh()`
+    config: RwLock<Config>,
+    /// Connections created before this are closed instead of reused
+    ///
+    /// On the system clock, as deadpool stamps connections with it.
+    retired_before: Mutex<Option<Instant>>,
+    /// Connections not handed out for longer than this are closed
+    idle_timeout: Option<Duration>,
+    /// When each connection was last handed out, tracked with an idle timeout
+    handed_out_at: Mutex<HashMap<u32, Instant>>,
+    clock: Arc<dyn Clock>,
+    /// Probe an open circuit on checkout instead of in the background
+    deterministic: bool,
+    failover_endpoints: Vec<Config>,
+    /// Index of the endpoint that last accepted a connection, 0 being `config`
+    active_endpoint: AtomicUsize,
+    /// Spreads new connections over the endpoints, if weights are set
+    balancer: Option<EndpointBalancer>,
+    drcp: Option<DrcpOptions>,
+    /// Applied to the configuration of every endpoint if set
+    tls: Option<TlsOptions>,
+    /// Replaces the user name and password of `config` if set
+    credentials: Option<CredentialCache>,
+    /// Checked on every checkout if set
+    identity: Option<SessionIdentity>,
+    name: String,
+    session_init_sql: Vec<String>,
+    prepare_on_create: Vec<String>,
+    isolation_level: Option<IsolationLevel>,
+    result_cache_mode: Option<ResultCacheMode>,
+    /// Statement applying the NLS settings of every session
+    nls_sql: Option<String>,
+    /// Statement setting the time zone of every session
+    time_zone_sql: Option<String>,
+    session_params: Vec<(String, String)>,
+    commit_on_drop: bool,
+    call_timeout: Option<Duration>,
+    hold_limit: Option<HoldLimit>,
+    recycle_stats: RecycleStats,
+    counters: PoolCounters,
+    events: EventBus,
+    slow_acquire_threshold: Option<Duration>,
+    leak_detector: Option<LeakDetector>,
+    health: HealthState,
+    transaction_retries: u32,
+    transaction_retry_backoff: Duration,
+    reconnect_retries: u32,
+    create_retries: u32,
+    create_retry_backoff: Duration,
+    circuit_breaker: Option<CircuitBreaker>,
+    quarantine: Option<Quarantine>,
+    dns_watch: Option<DnsWatch>,
+    /// Permits for connection attempts, if their concurrency is limited
+    create_permits: Option<tokio::sync::Semaphore>,
+    statement_caches: Arc<StatementCaches>,
+    fetch_array_size: u32,
+    tags: Arc<ConnectionTags>,
+    tag_fixup: Option<Arc<TagFixup>>,
+    session_context: Option<SessionContext>,
+    containers: SessionContainers,
+    schemas: SessionSchemas,
+    session_settings: Arc<SessionSettings>,
+    app_info: Arc<AppInfos>,
+    /// Set the module and action of sessions from the caller's span
+    #[cfg(feature = "tracing")]
+    context_from_span: bool,
+    shards: Option<Shards>,
+    /// Sub-pool of `Pool::get_readonly()`
+    readonly: Option<Pool>,
+    query_observer: Option<Arc<dyn QueryObserver>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    #[cfg(feature = "testing")]
+    fault_injector: Option<testing::FaultInjector>,
+    #[cfg(feature = "otel")]
+    otel: otel::OtelMetrics,
 }
 
 impl OracleConnectionManager {
     /// Create a new connection manager with the given configuration
     pub fn new(config: Config) -> Self {
-        Self { config }
+        let name = default_pool_name(&config);
+        Self {
+            statement_caches: Arc::new(StatementCaches::new(config.stmtcachesize)),
+            config: RwLock::new(config),
+            retired_before: Mutex::new(None),
+            idle_timeout: None,
+            handed_out_at: Mutex::default(),
+            clock: Arc::new(TokioClock),
+            deterministic: false,
+            failover_endpoints: Vec::new(),
+            active_endpoint: AtomicUsize::new(0),
+            balancer: None,
+            drcp: None,
+            tls: None,
+            credentials: None,
+            identity: None,
+            session_init_sql: Vec::new(),
+            prepare_on_create: Vec::new(),
+            isolation_level: None,
+            result_cache_mode: None,
+            nls_sql: None,
+            time_zone_sql: None,
+            session_params: Vec::new(),
+            commit_on_drop: false,
+            call_timeout: None,
+            hold_limit: None,
+            recycle_stats: RecycleStats::default(),
+            counters: PoolCounters::default(),
+            events: EventBus::default(),
+            slow_acquire_threshold: None,
+            leak_detector: None,
+            health: HealthState::default(),
+            transaction_retries: 0,
+            transaction_retry_backoff: Duration::from_millis(50),
+            reconnect_retries: 1,
+            create_retries: 0,
+            create_retry_backoff: Duration::from_millis(100),
+            circuit_breaker: None,
+            quarantine: None,
+            dns_watch: None,
+            create_permits: None,
+            fetch_array_size: DEFAULT_FETCH_ARRAY_SIZE,
+            tags: Arc::default(),
+            tag_fixup: None,
+            session_context: None,
+            containers: SessionContainers::default(),
+            schemas: SessionSchemas::default(),
+            session_settings: Arc::default(),
+            app_info: Arc::default(),
+            #[cfg(feature = "tracing")]
+            context_from_span: false,
+            shards: None,
+            readonly: None,
+            query_observer: None,
+            audit_sink: None,
+            #[cfg(feature = "testing")]
+            fault_injector: None,
+            #[cfg(feature = "otel")]
+            otel: otel::OtelMetrics::new(&name),
+            name,
+        }
     }
-}
 
-impl Manager for OracleConnectionManager {
-    type Type = Connection;
-    type Error = Error;
+    /// Set the name identifying this pool in telemetry
+    ///
+    /// Defaults to `host:port/service`.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        #[cfg(feature = "otel")]
+        {
+            self.otel = otel::OtelMetrics::new(&self.name);
+        }
+        self
+    }
 
-    async fn create(&self) -> Result<Connection, Error> {
-        Connection::connect_with_config(self.config.clone()).await
+    /// Get the name identifying this pool in telemetry
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
-    async fn recycle(
-        &self,
-        conn: &mut Connection,
-        _metrics: &Metrics,
-    ) -> RecycleResult<Error> {
+    /// Set SQL statements to execute on every newly created connection
+    ///
+    /// Statements run in order, immediately after connecting. If any of them
+    /// fails, the connection is discarded and the error is returned from `create()`.
+    pub fn with_session_init_sql(mut self, statements: Vec<String>) -> Self {
+        self.session_init_sql = statements;
+        self
+    }
+
+    /// Set queries to parse into the statement cache of every new connection
+    ///
+    /// Each query is executed once with all binds set to NULL, after the
+    /// session init SQL. Only queries are accepted. A query that fails is
+    /// skipped with a warning when the `tracing` feature is enabled; the
+    /// connection is still handed out.
+    pub fn with_prepare_on_create(mut self, queries: Vec<String>) -> Self {
+        self.prepare_on_create = queries;
+        self
+    }
+
+    /// Set the isolation level of every session
+    ///
+    /// Applied when a connection is created, before the session init SQL,
+    /// and asserted again whenever the connection is recycled.
+    pub fn with_isolation_level(mut self, level: Option<IsolationLevel>) -> Self {
+        self.isolation_level = level;
+        self
+    }
+
+    /// Set the result cache mode of every session
+    ///
+    /// Applied when a connection is created, before the session init SQL.
+    pub fn with_result_cache_mode(mut self, mode: Option<ResultCacheMode>) -> Self {
+        self.result_cache_mode = mode;
+        self
+    }
+
+    /// Set session parameters with `ALTER SESSION` on every new connection
+    ///
+    /// Applied after the NLS settings and before the session init SQL.
+    /// Names must be plain identifiers; a connection can't be created
+    /// otherwise.
+    pub fn with_session_params(mut self, params: Vec<(String, String)>) -> Self {
+        self.session_params = params;
+        self
+    }
+
+    /// Set the NLS parameters of every session
+    ///
+    /// Applied when a connection is created, before the session init SQL,
+    /// and asserted again whenever the connection is recycled.
+    pub fn with_nls(mut self, nls: NlsConfig) -> Self {
+        self.nls_sql = nls.alter_session_sql();
+        self
+    }
+
+    /// Set the time zone of every session
+    ///
+    /// Applied when a connection is created, before the session init SQL,
+    /// and asserted again whenever the connection is recycled.
+    pub fn with_time_zone(mut self, time_zone: &str) -> Self {
+        self.time_zone_sql = Some(nls::time_zone_sql(time_zone));
+        self
+    }
+
+    /// Commit outstanding work of connections when they are dropped
+    ///
+    /// Sets the default of `Object::commit_on_drop()` for every checkout.
+    pub fn with_commit_on_drop(mut self, enabled: bool) -> Self {
+        self.commit_on_drop = enabled;
+        self
+    }
+
+    /// Limit how long a single statement may run
+    ///
+    /// Sets the default of `Object::call_timeout()` for every checkout.
+    pub fn with_call_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.call_timeout = timeout;
+        self
+    }
+
+    /// Report transactions that stay open longer than `limit`
+    ///
+    /// If `force_rollback` is set, such a transaction is rolled back as soon
+    /// as its connection is returned instead of when it is next recycled.
+    pub fn with_max_transaction_duration(mut self, limit: Duration, force_rollback: bool) -> Self {
+        self.hold_limit = Some(HoldLimit {
+            limit,
+            force_rollback,
+        });
+        self
+    }
+
+    /// Set how long `get()` may take before a slow acquisition is reported
+    pub fn with_slow_acquire_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.slow_acquire_threshold = threshold;
+        self
+    }
+
+    /// Report connections checked out for longer than `threshold`
+    ///
+    /// If `capture_backtrace` is set, a backtrace is captured on every
+    /// checkout and included in the report. This is expensive.
+    pub fn with_leak_detection(mut self, threshold: Duration, capture_backtrace: bool) -> Self {
+        self.leak_detector = Some(LeakDetector::new(threshold, capture_backtrace));
+        self
+    }
+
+    /// Retry `Pool::with_transaction()` up to `retries` times on ORA-08177
+    ///
+    /// The delay before the first retry is `backoff`; it doubles on every
+    /// further attempt.
+    pub fn with_transaction_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.transaction_retries = retries;
+        self.transaction_retry_backoff = backoff;
+        self
+    }
+
+    /// Set how often `Pool::with_connection()` moves to another connection
+    /// after the first statement hit a dead one, see `PoolBuilder::reconnect_retries()`
+    pub fn with_reconnect_retries(mut self, retries: u32) -> Self {
+        self.reconnect_retries = retries;
+        self
+    }
+
+    /// Retry failed connection attempts up to `retries` times in `create()`
+    ///
+    /// Only listener and network errors that may go away are retried, not
+    /// invalid credentials. The delay before the first retry is `backoff`;
+    /// it doubles on every further attempt.
+    pub fn with_create_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.create_retries = retries;
+        self.create_retry_backoff = backoff;
+        self
+    }
+
+    /// Fail over to `endpoints`, in order, when the primary endpoint is down
+    pub fn with_failover_endpoints(mut self, endpoints: Vec<Config>) -> Self {
+        self.failover_endpoints = endpoints;
+        self
+    }
+
+    /// Spread new connections over the primary and failover endpoints by
+    /// `weights`, in the same order, instead of sticking to one
+    pub fn with_endpoint_weights(mut self, weights: Vec<u32>) -> Self {
+        self.balancer = Some(EndpointBalancer::new(weights));
+        self
+    }
+
+    /// Fail `get()` fast for `cool_down` after `failure_threshold`
+    /// consecutive failed creates or recycles
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, cool_down: Duration) -> Self {
+        self.circuit_breaker = Some(CircuitBreaker::new(failure_threshold, cool_down));
+        self
+    }
+
+    /// Hold back new connections after failed recycles, for `base` doubling
+    /// up to `max` with every further failure
+    pub fn with_recycle_failure_backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.quarantine = Some(Quarantine::new(base, max));
+        self
+    }
+
+    /// Resolve the database host again every `interval` and after failed
+    /// connection attempts, retiring all connections when its addresses change
+    pub fn with_dns_refresh(mut self, interval: Duration) -> Self {
+        self.dns_watch = Some(DnsWatch::new(interval));
+        self
+    }
+
+    /// Close connections that were not handed out for longer than `timeout`
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Read the time and sleep with `clock`, see `PoolBuilder::clock()`
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Run no background tasks, see `PoolBuilder::deterministic()`
+    ///
+    /// An open circuit is probed by the first checkout after its cool-down
+    /// instead of by a background task.
+    pub fn with_deterministic(mut self, enabled: bool) -> Self {
+        self.deterministic = enabled;
+        self
+    }
+
+    /// Allow at most `limit` connection attempts at a time
+    pub fn with_max_concurrent_creates(mut self, limit: usize) -> Self {
+        self.create_permits = Some(tokio::sync::Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Set up session state for `Pool::get_tagged()` with `fixup` when no
+    /// connection has the requested tag
+    pub fn with_tag_fixup(mut self, fixup: Arc<TagFixup>) -> Self {
+        self.tag_fixup = Some(fixup);
+        self
+    }
+
+    /// Set the application context of every connection checked out with
+    /// `hook`, and clear it with `clear_sql` on recycle, see
+    /// `PoolBuilder::session_context()`
+    pub fn with_session_context(
+        mut self,
+        hook: Arc<SessionContextHook>,
+        clear_sql: impl Into<String>,
+    ) -> Self {
+        self.session_context = Some(SessionContext::new(hook, clear_sql.into()));
+        self
+    }
+
+    /// Connect to DRCP pooled servers with the given connection class and purity
+    pub fn with_drcp(mut self, options: DrcpOptions) -> Self {
+        self.drcp = Some(options);
+        self
+    }
+
+    /// Trust the certificates in the PEM file at `path` instead of the
+    /// system roots, see `PoolBuilder::tls_ca_cert()`
+    pub fn with_tls_ca_cert(mut self, path: impl Into<String>) -> Self {
+        self.tls.get_or_insert_with(TlsOptions::default).ca_cert = Some(path.into());
+        self
+    }
+
+    /// Verify the server certificate against `name` instead of the host,
+    /// see `PoolBuilder::tls_server_name()`
+    pub fn with_tls_server_name(mut self, name: impl Into<String>) -> Self {
+        self.tls.get_or_insert_with(TlsOptions::default).server_name = Some(name.into());
+        self
+    }
+
+    /// Authenticate to the server with the certificate chain and private
+    /// key in the PEM files `cert_path` and `key_path`, see
+    /// `PoolBuilder::tls_client_cert()`
+    pub fn with_tls_client_cert(
+        mut self,
+        cert_path: impl Into<String>,
+        key_path: impl Into<String>,
+    ) -> Self {
+        let client_cert = (cert_path.into(), key_path.into());
+        self.tls.get_or_insert_with(TlsOptions::default).client_cert = Some(client_cert);
+        self
+    }
+
+    /// Take the trusted and client certificates from the wallet at `path`,
+    /// see `PoolBuilder::tls_wallet()`
+    pub fn with_tls_wallet(mut self, path: impl Into<String>, password: Option<String>) -> Self {
+        self.tls.get_or_insert_with(TlsOptions::default).wallet = Some((path.into(), password));
+        self
+    }
+
+    /// Log in with the credentials of `provider`, see
+    /// `PoolBuilder::credential_provider()`
+    pub fn with_credential_provider(mut self, provider: Arc<dyn CredentialProvider>) -> Self {
+        self.credentials = Some(CredentialCache::new(provider));
+        self
+    }
+
+    /// Evict sessions not logged in as `identity`, see
+    /// `PoolBuilder::expected_identity()`
+    pub fn with_expected_identity(mut self, identity: SessionIdentity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Inject faults into connection attempts, health checks and checkouts
+    #[cfg(feature = "testing")]
+    pub fn with_fault_injector(mut self, injector: testing::FaultInjector) -> Self {
+        self.fault_injector = Some(injector);
+        self
+    }
+
+    /// Route `Pool::get_for_shard()` to `pools` using `resolver`
+    pub fn with_shards(mut self, pools: Vec<Pool>, resolver: Arc<dyn ShardResolver>) -> Self {
+        self.shards = Some(Shards { pools, resolver });
+        self
+    }
+
+    /// Route `Pool::get_readonly()` to `pool`
+    pub fn with_readonly(mut self, pool: Pool) -> Self {
+        self.readonly = Some(pool);
+        self
+    }
+
+    /// Set how many parsed statements each connection caches
+    ///
+    /// Overrides the statement cache size of the `Config`. 0 disables the cache.
+    pub fn with_statement_cache_size(mut self, size: usize) -> Self {
+        self.config.get_mut().unwrap().stmtcachesize = size;
+        self.statement_caches = Arc::new(StatementCaches::new(size));
+        self
+    }
+
+    /// Set how many rows are fetched per round trip after the first
+    ///
+    /// Applies to `Object::query_all()` and the helpers built on it, and to
+    /// `CursorRows`. The first round trip of a query always prefetches 100
+    /// rows.
+    pub fn with_fetch_array_size(mut self, rows: u32) -> Self {
+        self.fetch_array_size = rows.max(1);
+        self
+    }
+
+    /// Set the module and action of sessions from the span they are checked
+    /// out in, see `PoolBuilder::context_from_span()`
+    #[cfg(feature = "tracing")]
+    pub fn with_context_from_span(mut self, enabled: bool) -> Self {
+        self.context_from_span = enabled;
+        self
+    }
+
+    /// Report every statement run on the pool's connections to `observer`
+    pub fn with_query_observer(mut self, observer: Arc<dyn QueryObserver>) -> Self {
+        self.query_observer = Some(observer);
+        self
+    }
+
+    /// Record every statement run on the pool's connections to `sink`, see
+    /// `PoolBuilder::audit_sink()`
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Get a snapshot of recycle outcomes since the manager was created
+    pub fn recycle_metrics(&self) -> RecycleMetrics {
+        self.recycle_stats.snapshot()
+    }
+
+    /// Close every current connection once it is idle, see
+    /// `Pool::drain_and_refresh()`
+    fn retire_connections(&self) {
+        *self.retired_before.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Whether a connection was retired by `Pool::drain_and_refresh()`
+    fn is_retired(&self, metrics: &Metrics) -> bool {
+        self.retired_before
+            .lock()
+            .unwrap()
+            .is_some_and(|retired_before| metrics.created < retired_before)
+    }
+
+    /// Whether a connection was not handed out for longer than the idle timeout
+    fn is_idle_expired(&self, conn: &Connection, metrics: &Metrics) -> bool {
+        let Some(timeout) = self.idle_timeout else {
+            return false;
+        };
+        let idle = match self.handed_out_at.lock().unwrap().get(&conn.id()) {
+            Some(at) => self.clock.now().saturating_duration_since(*at),
+            None => metrics.last_used(),
+        };
+        idle > timeout
+    }
+
+    /// Start the idle time of a connection being handed out
+    fn record_hand_out(&self, conn: &Connection) {
+        if self.idle_timeout.is_some() {
+            let now = self.clock.now();
+            self.handed_out_at.lock().unwrap().insert(conn.id(), now);
+        }
+    }
+
+    /// Time elapsed on the pool's clock since `start`
+    fn elapsed(&self, start: Instant) -> Duration {
+        self.clock.now().saturating_duration_since(start)
+    }
+
+    /// Run the health check on a connection being handed out again
+    async fn check_connection(&self, conn: &Connection, metrics: &Metrics) -> RecycleResult<Error> {
+        self.refresh_dns(false).await;
+        if self.is_retired(metrics) {
+            self.record_eviction(conn, EvictionReason::Retired);
+            return Err(RecycleError::message("connection retired"));
+        }
+        if self.is_idle_expired(conn, metrics) {
+            self.record_eviction(conn, EvictionReason::Idle);
+            return Err(RecycleError::message("connection idle too long"));
+        }
+
         // Check if connection is still alive
         if conn.is_closed() {
+            self.record_eviction(conn, EvictionReason::Closed);
             return Err(RecycleError::message("connection closed"));
         }
 
-        // Rollback any pending transaction to ensure clean state
-        conn.rollback().await.ok();
+        // An injected failure drops the session, so the rollback below fails
+        #[cfg(feature = "testing")]
+        if let Some(faults) = &self.fault_injector {
+            if faults.fail_recycle() {
+                let _ = conn.close().await;
+            }
+        }
+
+        // Roll back whatever the previous borrower left open. A rollback the
+        // server answers proves the session is alive, so no separate ping is
+        // needed, and an isolation level, NLS setting or time zone changed
+        // by the previous borrower is undone in the same round trip.
+        //
+        // Parameters the previous borrower changed with
+        // `with_session_setting()` are set back first, so that the pool's
+        // own NLS settings re-asserted after them win.
+        let restore = match self.session_settings.take_restore(conn.id()) {
+            Ok(restore) => restore,
+            Err(e) => {
+                self.record_recycle_failure(conn, &e);
+                self.record_eviction(conn, EvictionReason::ResetFailed);
+                return Err(RecycleError::Backend(e));
+            }
+        };
+        let reasserted: Vec<&str> = restore
+            .as_deref()
+            .into_iter()
+            .chain(self.isolation_level.map(IsolationLevel::alter_session_sql))
+            .chain(self.nls_sql.as_deref())
+            .chain(self.time_zone_sql.as_deref())
+            .collect();
+        let result = if reasserted.is_empty() {
+            conn.rollback().await
+        } else {
+            let block = transaction::rollback_block_sql(&reasserted);
+            conn.execute_plsql(&block, &[]).await.map(drop)
+        };
+
+        if let Err(e) = result {
+            if e.is_connection_error() {
+                self.recycle_stats.record_ping_failure();
+            } else {
+                self.recycle_stats.record_rollback_failure();
+            }
+            self.record_recycle_failure(conn, &e);
+            // A killed session says nothing about the database being reachable
+            if is_session_killed(&e) {
+                return Err(self.evict_killed(conn));
+            }
+            // Nor does a statement the server refused, such as a bad
+            // re-asserted session setting
+            if !e.is_connection_error() {
+                self.record_eviction(conn, EvictionReason::ResetFailed);
+                return Err(RecycleError::Backend(e));
+            }
+            self.record_circuit_outcome(false);
+            self.quarantine_after_failure();
+            self.record_eviction(conn, EvictionReason::PingFailed);
+            return Err(RecycleError::Backend(e));
+        }
+
+        if let Some(home) = self.containers.home(conn.id()) {
+            if let Err(e) = self.reset_container(conn, &home).await {
+                self.record_recycle_failure(conn, &e);
+                self.record_eviction(conn, EvictionReason::ResetFailed);
+                return Err(RecycleError::Backend(e));
+            }
+        }
+
+        if self.schemas.current(conn.id()).is_some() {
+            if let Err(e) = self.reset_schema(conn).await {
+                self.record_recycle_failure(conn, &e);
+                self.record_eviction(conn, EvictionReason::ResetFailed);
+                return Err(RecycleError::Backend(e));
+            }
+        }
+
+        // The application context belongs to the previous borrower
+        if let Some(context) = &self.session_context {
+            if context.is_set(conn.id()) {
+                if let Err(e) = context.clear(conn).await {
+                    self.record_recycle_failure(conn, &e);
+                    self.record_eviction(conn, EvictionReason::ResetFailed);
+                    return Err(RecycleError::Backend(e));
+                }
+            }
+        }
+
+        // The client identifier belongs to the previous borrower
+        if self.app_info.is_identified(conn.id()) {
+            if let Err(e) = app_info::clear_identifier(conn).await {
+                self.record_recycle_failure(conn, &e);
+                self.record_eviction(conn, EvictionReason::ResetFailed);
+                return Err(RecycleError::Backend(e));
+            }
+            self.app_info.set_identifier(conn.id(), None);
+        }
+
+        // Grants and credentials may have changed since the session logged in
+        if let Some(expected) = &self.identity {
+            if let Err(e) = self.verify_identity(conn, expected).await {
+                self.record_recycle_failure(conn, &e);
+                self.record_eviction(conn, EvictionReason::IdentityMismatch);
+                return Err(RecycleError::Backend(e));
+            }
+        }
+
+        self.record_circuit_outcome(true);
+        if let Some(quarantine) = &self.quarantine {
+            quarantine.record_success();
+        }
+        Ok(())
+    }
+
+    /// Check that a session is logged in as `expected`, by default as the
+    /// user new connections log in as
+    async fn verify_identity(
+        &self,
+        conn: &Connection,
+        expected: &SessionIdentity,
+    ) -> Result<(), Error> {
+        let login_user = match &self.credentials {
+            Some(cache) => cache.username().await,
+            None => None,
+        };
+        let login_user = login_user.unwrap_or_else(|| self.config.read().unwrap().username.clone());
+        identity::verify(conn, expected, &login_user).await
+    }
+
+    /// Switch a connection to `container`, see `Pool::get_for_container()`
+    async fn switch_container(&self, conn: &Connection, container: &str) -> Result<(), Error> {
+        if self.containers.current(conn.id()).as_deref() == Some(container) {
+            return Ok(());
+        }
+        let home = match self.containers.home(conn.id()) {
+            Some(home) => home,
+            None => container::current_container(conn).await?,
+        };
+        if home == container && self.containers.current(conn.id()).is_none() {
+            return Ok(());
+        }
+
+        // Cached cursors were parsed in the previous container
+        conn.clear_statement_cache().await;
+        self.statement_caches.remove(conn.id());
+        let sql = container::alter_session_sql(container);
+        conn.execute(&sql, &[]).await?;
+        self.containers.set(conn.id(), home, container.to_string());
+        Ok(())
+    }
+
+    /// Switch a connection back to the container it was opened in
+    async fn reset_container(&self, conn: &Connection, home: &str) -> Result<(), Error> {
+        conn.clear_statement_cache().await;
+        self.statement_caches.remove(conn.id());
+        container::reset(conn, home).await?;
+        self.containers.remove(conn.id());
+        Ok(())
+    }
+
+    /// Switch a connection to `schema`, see `Pool::get_with_schema()`
+    async fn switch_schema(&self, conn: &Connection, schema: &str) -> Result<(), Error> {
+        if self.schemas.current(conn.id()).as_deref() == Some(schema) {
+            return Ok(());
+        }
+
+        // Cached cursors resolved their names in the previous schema
+        conn.clear_statement_cache().await;
+        self.statement_caches.remove(conn.id());
+        // Marked first, so a session left half switched is still reset
+        self.schemas.set(conn.id(), schema.to_string());
+        let sql = schema::alter_session_sql(schema);
+        conn.execute(&sql, &[]).await?;
+        Ok(())
+    }
+
+    /// Switch a connection back to the schema of its user
+    async fn reset_schema(&self, conn: &Connection) -> Result<(), Error> {
+        conn.clear_statement_cache().await;
+        self.statement_caches.remove(conn.id());
+        schema::reset(conn).await?;
+        self.schemas.remove(conn.id());
+        Ok(())
+    }
+
+    /// Open a new connection and apply the session settings
+    async fn connect(&self) -> Result<Connection, Error> {
+        // Held until the session is set up, the semaphore is never closed
+        let _permit = match &self.create_permits {
+            Some(permits) => {
+                let acquire = permits.acquire();
+                #[cfg(feature = "tracing")]
+                let acquire = tracing::Instrument::instrument(
+                    acquire,
+                    tracing::debug_span!("oracle_pool.create_permit_wait"),
+                );
+                Some(acquire.await.expect("semaphore closed"))
+            }
+            None => None,
+        };
+
+        #[cfg(feature = "testing")]
+        if let Some(delay) = self.fault_injector.as_ref().and_then(|f| f.create_delay()) {
+            self.clock.sleep(delay).await;
+        }
+
+        let conn = self.connect_endpoint().await?;
+
+        if let Some(level) = self.isolation_level {
+            conn.execute(level.alter_session_sql(), &[]).await?;
+        }
+        if let Some(mode) = self.result_cache_mode {
+            conn.execute(mode.alter_session_sql(), &[]).await?;
+        }
+        if let Some(sql) = &self.nls_sql {
+            conn.execute(sql, &[]).await?;
+        }
+        if let Some(sql) = &self.time_zone_sql {
+            conn.execute(sql, &[]).await?;
+        }
+        if let Some(sql) = session_params::alter_session_sql(&self.session_params)? {
+            conn.execute(&sql, &[]).await?;
+        }
+
+        // Apply session settings so every pooled session starts out the same
+        for sql in &self.session_init_sql {
+            conn.execute(sql, &[]).await?;
+        }
+
+        if let Some(expected) = &self.identity {
+            self.verify_identity(&conn, expected).await?;
+        }
+
+        self.prepare_statements(&conn).await;
+
+        Ok(conn)
+    }
+
+    /// Connect to the last healthy endpoint, failing over to the others
+    ///
+    /// Endpoints are tried in order starting at the one that last accepted a
+    /// connection, wrapping around to the primary, so new connections stick
+    /// to a standby once it took over. With endpoint weights, the balancer
+    /// picks the order instead. Only failures that a different host may not
+    /// have move on to the next endpoint.
+    async fn connect_endpoint(&self) -> Result<Connection, Error> {
+        let Some(cache) = &self.credentials else {
+            return self.connect_endpoint_as(None).await;
+        };
+        let credentials = cache.get(self.clock.now()).await?;
+        match self.connect_endpoint_as(Some(&credentials)).await {
+            // The secret may have been rotated since it was cached
+            Err(e) if is_invalid_credentials(&e) => {
+                let fresh = self.refresh_credentials(cache).await?;
+                if fresh == credentials {
+                    return Err(e);
+                }
+                self.connect_endpoint_as(Some(&fresh)).await
+            }
+            result => result,
+        }
+    }
+
+    /// Ask the credential provider again, retiring the connections of
+    /// replaced credentials that expire
+    async fn refresh_credentials(&self, cache: &CredentialCache) -> Result<Credentials, Error> {
+        let (credentials, expiring) = cache.refresh(self.clock.now()).await?;
+        if expiring {
+            self.retire_connections();
+        }
+        Ok(credentials)
+    }
+
+    /// Connect to the first endpoint that accepts, as `credentials` if given
+    async fn connect_endpoint_as(
+        &self,
+        credentials: Option<&Credentials>,
+    ) -> Result<Connection, Error> {
+        let primary = self.config.read().unwrap().clone();
+        let count = 1 + self.failover_endpoints.len();
+        let order: Vec<usize> = match &self.balancer {
+            Some(balancer) => balancer.order(count, self.clock.now()),
+            None => {
+                let active = self.active_endpoint.load(Ordering::Relaxed);
+                (active..count).chain(0..active).collect()
+            }
+        };
+        let preferred = order[0];
+        let mut last_error = None;
+
+        for index in order {
+            let mut config = match index {
+                0 => primary.clone(),
+                _ => self.failover_endpoints[index - 1].clone(),
+            };
+            config.stmtcachesize = primary.stmtcachesize;
+            if let Some(credentials) = credentials {
+                config.set_username(credentials.username());
+                config.set_password(credentials.password());
+            }
+            let endpoint = default_pool_name(&config);
+            if let Some(options) = &self.drcp {
+                drcp::apply(&mut config, options)?;
+            }
+            if let Some(options) = &self.tls {
+                tls::apply(&mut config, options);
+            }
+
+            let username = config.username.clone();
+            let connected = Connection::connect_with_config(config).await;
+            match connected.map_err(|e| self.scrub(e, &username, credentials)) {
+                Ok(conn) => {
+                    if let Some(balancer) = &self.balancer {
+                        balancer.record_success(index);
+                    }
+                    if index != preferred {
+                        self.active_endpoint.store(index, Ordering::Relaxed);
+
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(endpoint = %endpoint, "failed over to another endpoint");
+
+                        self.events.emit(PoolEvent::EndpointFailover { endpoint });
+                    }
+                    return Ok(conn);
+                }
+                Err(e) if is_transient_connect_error(&e) && count > 1 => {
+                    if let Some(balancer) = &self.balancer {
+                        balancer.record_failure(index, self.clock.now());
+                    }
+
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(endpoint = %endpoint, error = %e, "endpoint unavailable");
+
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.expect("at least one endpoint was tried"))
+    }
+
+    /// Remove the password logged in with, and the wallet password, from a
+    /// connection error
+    fn scrub(&self, err: Error, username: &str, credentials: Option<&Credentials>) -> Error {
+        let mut secrets = Vec::new();
+        if let Some(credentials) = credentials {
+            secrets.push(credentials.password());
+        }
+        if let Some((_, Some(password))) = self.tls.as_ref().and_then(|tls| tls.wallet.as_ref()) {
+            secrets.push(password.as_str());
+        }
+        redact::scrub(err, username, &secrets)
+    }
+
+    /// Connect, retrying transient failures as configured
+    async fn connect_with_retries(&self) -> Result<Connection, Error> {
+        let mut backoff = self.create_retry_backoff;
+        let mut attempt = 0;
+
+        loop {
+            match self.connect().await {
+                Err(e) if is_transient_connect_error(&e) && attempt < self.create_retries => {
+                    attempt += 1;
+
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(attempt, ?backoff, error = %e, "retrying connection attempt");
+
+                    let sleep = self.clock.sleep(backoff);
+                    #[cfg(feature = "tracing")]
+                    let sleep = tracing::Instrument::instrument(
+                        sleep,
+                        tracing::debug_span!("oracle_pool.connect_backoff", attempt),
+                    );
+                    sleep.await;
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Parse the warm-up queries so the first borrower finds them cached
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    async fn prepare_statements(&self, conn: &Connection) {
+        if self.config.read().unwrap().stmtcachesize == 0 {
+            return;
+        }
+        for sql in &self.prepare_on_create {
+            match statement_cache::prepare(conn, sql).await {
+                Ok(()) => self.statement_caches.record(conn.id(), sql),
+                Err(e) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(sql = %sql, error = %e, "failed to prepare statement");
+                }
+            }
+        }
+    }
+
+    /// Report the outcome of a connection attempt
+    #[cfg_attr(
+        not(any(feature = "tracing", feature = "otel", feature = "metrics")),
+        allow(unused_variables)
+    )]
+    fn on_create(&self, result: &Result<Connection, Error>, elapsed: Duration) {
+        self.counters.record_create(result.is_ok());
+        self.record_circuit_outcome(result.is_ok());
+        if result.is_err() {
+            self.quarantine_after_failure();
+        }
+
+        #[cfg(feature = "metrics")]
+        metrics_facade::record_create(&self.name, result.is_ok(), elapsed);
+
+        if let Ok(conn) = result {
+            self.events.emit(PoolEvent::ConnectionCreated {
+                connection_id: conn.id(),
+            });
+        }
+
+        #[cfg(feature = "tracing")]
+        match result {
+            Ok(conn) => {
+                tracing::Span::current().record("connection_id", conn.id());
+                tracing::debug!(?elapsed, outcome = "created", "connection created");
+            }
+            Err(e) => {
+                tracing::warn!(?elapsed, outcome = "failed", error = %e, "failed to create connection");
+            }
+        }
+
+        #[cfg(feature = "otel")]
+        if result.is_ok() {
+            self.otel.record_create(elapsed);
+        }
+    }
+
+    /// Feed the outcome of a create or recycle to the circuit breaker
+    fn record_circuit_outcome(&self, success: bool) {
+        let Some(circuit) = &self.circuit_breaker else {
+            return;
+        };
+        if success {
+            if circuit.record_success() {
+                self.on_circuit_closed();
+            }
+        } else if let Some(failures) = circuit.record_failure(self.clock.now()) {
+            let cool_down = circuit.cool_down();
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!(failures, ?cool_down, "circuit breaker opened");
+
+            self.events.emit(PoolEvent::CircuitOpened {
+                failures,
+                cool_down,
+            });
+        }
+    }
+
+    /// Resolve the database host if due, retiring all connections when its
+    /// addresses changed
+    async fn refresh_dns(&self, force: bool) {
+        let Some(dns_watch) = &self.dns_watch else {
+            return;
+        };
+        if !dns_watch.claim(force, self.clock.now()) {
+            return;
+        }
+        let (host, port) = {
+            let config = self.config.read().unwrap();
+            (config.host.clone(), config.port)
+        };
+
+        let addresses = dns::resolve(&host, port).await;
+        if dns_watch.update(&host, port, addresses) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(host = %host, "database host moved to new addresses, retiring connections");
+
+            self.retire_connections();
+            self.events.emit(PoolEvent::HostAddressChanged { host });
+        }
+    }
+
+    /// Extend the quarantine, if enabled, after a failed recycle or create
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn quarantine_after_failure(&self) {
+        let Some(quarantine) = &self.quarantine else {
+            return;
+        };
+        let backoff = quarantine.record_failure(self.clock.now());
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(?backoff, "holding back new connections");
+    }
+
+    /// Time new connections are still held back after repeated recycle failures
+    fn quarantine_remaining(&self) -> Option<Duration> {
+        let now = self.clock.now();
+        self.quarantine.as_ref().and_then(|q| q.remaining(now))
+    }
+
+    fn on_circuit_closed(&self) {
+        #[cfg(feature = "tracing")]
+        tracing::info!("circuit breaker closed");
+
+        self.events.emit(PoolEvent::CircuitClosed);
+    }
+
+    /// Evict a killed session without further round trips
+    fn evict_killed(&self, conn: &Connection) -> RecycleError<Error> {
+        conn.mark_closed();
+        self.record_eviction(conn, EvictionReason::SessionKilled);
+        RecycleError::message("session killed")
+    }
+
+    fn record_eviction(&self, conn: &Connection, reason: EvictionReason) {
+        self.recycle_stats.record_eviction(reason);
+        self.statement_caches.remove(conn.id());
+
+        #[cfg(feature = "metrics")]
+        metrics_facade::record_eviction(&self.name, reason);
+
+        self.events.emit(PoolEvent::ConnectionEvicted {
+            connection_id: conn.id(),
+            reason,
+        });
+    }
+
+    fn record_recycle_failure(&self, conn: &Connection, err: &Error) {
+        self.events.emit(PoolEvent::RecycleFailed {
+            connection_id: conn.id(),
+            error: err.to_string(),
+        });
+    }
+
+    /// Subscribe to lifecycle events of the pool using this manager
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<PoolEvent> {
+        self.events.subscribe()
+    }
+}
+
+impl std::fmt::Debug for OracleConnectionManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let config = self.config.read().unwrap();
+        let failover: Vec<_> = self.failover_endpoints.iter().map(ConfigDebug).collect();
+        f.debug_struct("OracleConnectionManager")
+            .field("name", &self.name)
+            .field("config", &ConfigDebug(&config))
+            .field("failover_endpoints", &failover)
+            .field("drcp", &self.drcp)
+            .field("tls", &self.tls)
+            .field("credential_provider", &self.credentials.is_some())
+            .field("idle_timeout", &self.idle_timeout)
+            .field("call_timeout", &self.call_timeout)
+            .field("commit_on_drop", &self.commit_on_drop)
+            .field("deterministic", &self.deterministic)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Manager for OracleConnectionManager {
+    type Type = Connection;
+    type Error = Error;
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "oracle_pool.create", skip_all, fields(connection_id))
+    )]
+    async fn create(&self) -> Result<Connection, Error> {
+        // Looking for a tagged connection never opens a session
+        if TAG_SCAN.try_with(|_| ()).is_ok() {
+            return Err(Error::SqlError("no idle connection left to scan".into()));
+        }
+        if let Some(wait) = self.quarantine_remaining() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?wait, "connection held back by quarantine");
+
+            let sleep = self.clock.sleep(wait);
+            #[cfg(feature = "tracing")]
+            let sleep = tracing::Instrument::instrument(
+                sleep,
+                tracing::debug_span!("oracle_pool.quarantine_wait"),
+            );
+            sleep.await;
+        }
+        self.refresh_dns(false).await;
+
+        let start = self.clock.now();
+        let result = self.connect_with_retries().await;
+        let elapsed = self.elapsed(start);
+        // Attributed to the checkout this create runs for
+        let _ = CREATE_TIME.try_with(|total| {
+            total.set(Some(total.get().unwrap_or_default() + elapsed));
+        });
+        self.on_create(&result, elapsed);
+        if result.is_err() {
+            self.refresh_dns(true).await;
+        }
+        result
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "oracle_pool.recycle", skip_all, fields(connection_id = conn.id()))
+    )]
+    async fn recycle(
+        &self,
+        conn: &mut Connection,
+        metrics: &Metrics,
+    ) -> RecycleResult<Error> {
+        // Passed over by a tag scan, it goes straight back to the pool and
+        // is checked when it is next handed out
+        let passed_over = TAG_SCAN.try_with(|tag| self.tags.get(conn.id()).as_ref() != Some(tag));
+        if passed_over == Ok(true) {
+            return Ok(());
+        }
+        let start = self.clock.now();
+        let result = self.check_connection(conn, metrics).await;
+        let elapsed = self.elapsed(start);
+        self.recycle_stats.record_duration(elapsed);
+
+        #[cfg(feature = "metrics")]
+        metrics_facade::record_recycle(&self.name, elapsed);
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(()) => tracing::trace!(?elapsed, outcome = "recycled", "connection recycled"),
+            Err(e) => {
+                tracing::debug!(?elapsed, outcome = "evicted", error = %e, "connection evicted")
+            }
+        }
+
+        result
+    }
+
+    fn detach(&self, conn: &mut Connection) {
+        self.tags.remove(conn.id());
+        self.containers.remove(conn.id());
+        self.schemas.remove(conn.id());
+        self.session_settings.remove(conn.id());
+        self.app_info.remove(conn.id());
+        if let Some(context) = &self.session_context {
+            context.remove(conn.id());
+        }
+        self.handed_out_at.lock().unwrap().remove(&conn.id());
+    }
+}
+
+/// Connection pool for Oracle databases
+///
+/// Cloning a `Pool` is cheap; all clones share the same set of connections.
+#[derive(Clone)]
+pub struct Pool {
+    inner: managed::Pool<OracleConnectionManager>,
+}
+
+impl Pool {
+    /// Get a connection from the pool, waiting for one to become available
+    ///
+    /// Idle sessions killed by a DBA (ORA-00028) or timed out by a profile
+    /// (ORA-02396) are evicted during recycle, and a new connection whose
+    /// session is killed while it is set up is created once more before the
+    /// error is returned. If the first statement run through the `Object`
+    /// then fails because the connection is dead or its session was killed,
+    /// the connection is evicted and the statement runs once more on another
+    /// one, if one is available without waiting. This applies to the
+    /// statement methods of `Object` and `Transaction`; what runs on the
+    /// `Connection` through `Deref` is not retried. Set
+    /// `PoolBuilder::reconnect_retries` to 0 to turn this off.
+    pub async fn get(&self) -> Result<Object, PoolError> {
+        let mut conn = self.checkout(&self.timeouts()).await?;
+        conn.reconnect = self.manager().reconnect_retries > 0;
+        Ok(conn)
+    }
+
+    /// Check out a connection set up like one from `get()`, but without
+    /// replacing it if the first statement finds it dead
+    async fn checkout(&self, timeouts: &Timeouts) -> Result<Object, PoolError> {
+        // Read before entering the pool's own span
+        #[cfg(feature = "tracing")]
+        let span_info = self
+            .manager()
+            .context_from_span
+            .then(AppInfo::from_current_span);
+
+        let conn = self.timeout_get(timeouts).await?;
+        self.set_up_checkout(
+            conn,
+            #[cfg(feature = "tracing")]
+            span_info,
+        )
+        .await
+    }
+
+    /// Prepare a connection for the caller of `get()` or `get_tagged()`:
+    /// label it with the caller's span and set the session context
+    async fn set_up_checkout(
+        &self,
+        conn: Object,
+        #[cfg(feature = "tracing")] span_info: Option<Option<AppInfo>>,
+    ) -> Result<Object, PoolError> {
+        #[cfg(feature = "tracing")]
+        if let Some(info) = span_info {
+            // A session without a label beats failing the checkout
+            if let Err(e) = conn.setting_up(conn.update_app_info(info)).await {
+                tracing::warn!(error = %e, "failed to set the module and action");
+            }
+        }
+        if let Some(context) = &self.manager().session_context {
+            if let Err(e) = conn.setting_up(context.set(&conn)).await {
+                // The session may be half set up, so it is not reused
+                conn.mark_closed();
+                return Err(managed::PoolError::Backend(e));
+            }
+        }
+        Ok(conn)
+    }
+
+    /// Get a connection from the pool using different timeouts than the configured ones
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "oracle_pool.get", skip_all, fields(connection_id))
+    )]
+    pub async fn timeout_get(&self, timeouts: &Timeouts) -> Result<Object, PoolError> {
+        let start = self.manager().clock.now();
+        let result = self.acquire(timeouts).await;
+        self.on_acquire(&result, self.manager().elapsed(start));
+        result
+    }
+
+    /// Get a connection only if one is available without waiting
+    ///
+    /// Returns `None` at once if every connection is checked out and the
+    /// pool is at its maximum size, for latency-critical paths that would
+    /// rather degrade than queue. An idle connection is still health-checked,
+    /// and below the maximum size a new connection is created as usual.
+    /// Returning `None` is not counted as an acquisition timeout.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+    /// match pool.try_get().await? {
+    ///     Some(conn) => {
+    ///         conn.execute("INSERT INTO page_views (path) VALUES ('/')", &[]).await?;
+    ///     }
+    ///     None => eprintln!("pool busy, skipping page view"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "oracle_pool.try_get", skip_all, fields(connection_id))
+    )]
+    pub async fn try_get(&self) -> Result<Option<Object>, PoolError> {
+        let start = self.manager().clock.now();
+        let result = match self.acquire(&self.no_wait_timeouts()).await {
+            Err(managed::PoolError::Timeout(managed::TimeoutType::Wait)) => return Ok(None),
+            result => result,
+        };
+        self.on_acquire(&result, self.manager().elapsed(start));
+        result.map(Some)
+    }
+
+    /// Check out a connection, creating it once more if its session was
+    /// killed while being set up
+    async fn acquire(&self, timeouts: &Timeouts) -> Result<Object, PoolError> {
+        let manager = self.manager();
+        let start = manager.clock.now();
+        // In deterministic mode, the first checkout after the cool-down is the probe
+        let probe = manager.deterministic
+            && manager
+                .circuit_breaker
+                .as_ref()
+                .is_some_and(|circuit| circuit.start_inline_probe(start));
+        if self.is_circuit_open() && !probe {
+            self.probe_circuit();
+            return Err(managed::PoolError::Backend(Error::ConnectionRefused {
+                error_code: None,
+                message: Some("circuit breaker open after repeated connection failures".into()),
+            }));
+        }
+
+        let checkout = async {
+            let result = match self.inner.timeout_get(timeouts).await {
+                Err(managed::PoolError::Backend(e)) if is_session_killed(&e) => {
+                    self.inner.timeout_get(timeouts).await
+                }
+                result => result,
+            };
+            (result, CREATE_TIME.with(Cell::get))
+        };
+        let (result, create) = CREATE_TIME.scope(Cell::new(None), checkout).await;
+        if probe && result.is_err() {
+            if let Some(circuit) = &manager.circuit_breaker {
+                circuit.inline_probe_failed(manager.clock.now());
+            }
+        }
+        if result.is_err() && self.is_circuit_open() {
+            self.probe_circuit();
+        }
+        let latency = AcquireLatency::new(manager.elapsed(start), create);
+        Ok(self.hand_out(result?, latency).await)
+    }
+
+    /// Wrap a connection taken from deadpool for the caller
+    async fn hand_out(
+        &self,
+        inner: managed::Object<OracleConnectionManager>,
+        latency: AcquireLatency,
+    ) -> Object {
+        let manager = self.manager();
+        let mut conn = Object::new(inner, manager);
+        conn.acquire_latency = latency;
+        manager.record_hand_out(conn.connection());
+
+        #[cfg(feature = "testing")]
+        if let Some(faults) = &self.manager().fault_injector {
+            if faults.disconnect() {
+                let _ = conn.connection().close().await;
+            }
+        }
+        conn
+    }
+
+    /// Whether the circuit breaker is open, failing `get()` without trying
+    /// to connect
+    ///
+    /// Always `false` unless enabled with `PoolBuilder::circuit_breaker()`.
+    pub fn is_circuit_open(&self) -> bool {
+        self.manager()
+            .circuit_breaker
+            .as_ref()
+            .is_some_and(CircuitBreaker::is_open)
+    }
+
+    /// Start the background probe of an open circuit, unless it is running
+    ///
+    /// The probe connects after every cool-down until a connection succeeds,
+    /// and stops early once the pool is closed or dropped.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn probe_circuit(&self) {
+        if self.manager().deterministic {
+            return;
+        }
+        let Some(mut due) = self
+            .manager()
+            .circuit_breaker
+            .as_ref()
+            .and_then(CircuitBreaker::start_probe)
+        else {
+            return;
+        };
+        let pool = self.inner.weak();
+        let clock = self.manager().clock.clone();
+
+        task::spawn("oracle_pool.circuit_probe", async move {
+            loop {
+                let wait = due.saturating_duration_since(clock.now());
+                clock.sleep(wait).await;
+                let Some(pool) = pool.upgrade().filter(|pool| !pool.is_closed()) else {
+                    return;
+                };
+                let manager = pool.manager();
+                let Some(circuit) = &manager.circuit_breaker else {
+                    return;
+                };
+
+                match manager.connect().await {
+                    Ok(conn) => {
+                        let _ = conn.close().await;
+                        if circuit.record_success() {
+                            manager.on_circuit_closed();
+                        }
+                        return;
+                    }
+                    Err(e) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(error = %e, "circuit breaker probe failed");
+
+                        due = circuit.probe_failed(clock.now());
+                    }
+                }
+            }
+        });
+    }
+
+    /// The configured timeouts, but without waiting for a connection
+    fn no_wait_timeouts(&self) -> Timeouts {
+        Timeouts {
+            wait: Some(Duration::ZERO),
+            ..self.timeouts()
+        }
+    }
+
+    /// Get a connection to the shard holding the sharding key `key`
+    ///
+    /// Requires shards configured with `PoolBuilder::shards()`; the
+    /// resolver given there picks the shard, and the connection comes from
+    /// that shard's sub-pool. Fails with `Error::SqlError` if no shard
+    /// holds the key. Use `get()` for cross-shard queries through the
+    /// shard catalog.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+    /// use oracle_rs::Value;
+    ///
+    /// let conn = pool.get_for_shard(42).await?;
+    /// conn.execute("UPDATE customers SET status = 'GOLD' WHERE id = :1", &[Value::Integer(42)])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_for_shard(&self, key: impl Into<Value>) -> Result<Object, PoolError> {
+        let key = key.into();
+        let shards = self.manager().shards.as_ref();
+        let Some(pool) = shards.and_then(|shards| shards.pool_for(&key)) else {
+            let message = "no shard holds the sharding key".to_string();
+            return Err(managed::PoolError::Backend(Error::SqlError(message)));
+        };
+        pool.get().await
+    }
+
+    /// Sub-pools of the shards configured with `PoolBuilder::shards()`
+    pub fn shards(&self) -> &[Pool] {
+        let shards = self.manager().shards.as_ref();
+        shards.map_or(&[], |shards| &shards.pools)
+    }
+
+    /// Get a connection logged in with the read-only credentials
+    ///
+    /// Requires credentials configured with
+    /// `PoolBuilder::readonly_credentials()`; the connection comes from the
+    /// sub-pool logging in with them, so a write on it is refused by the
+    /// database with ORA-01031. Fails with `Error::SqlError` if none are
+    /// configured, rather than handing out a connection that can write.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+    /// let conn = pool.get_readonly().await?;
+    /// let rows = conn.query("SELECT region, SUM(total) FROM orders GROUP BY region", &[])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_readonly(&self) -> Result<Object, PoolError> {
+        let Some(pool) = self.readonly() else {
+            let message = "no read-only credentials configured".to_string();
+            return Err(managed::PoolError::Backend(Error::SqlError(message)));
+        };
+        pool.get().await
+    }
+
+    /// Sub-pool of `get_readonly()`, if configured with
+    /// `PoolBuilder::readonly_credentials()`
+    pub fn readonly(&self) -> Option<&Pool> {
+        self.manager().readonly.as_ref()
+    }
+
+    /// Get a connection switched to the pluggable database `container`
+    ///
+    /// For pools of a common user connected to a container database: the
+    /// session is moved with `ALTER SESSION SET CONTAINER` unless it is
+    /// already in `container`, and moved back to the container it was
+    /// opened in when the connection is recycled. A session that cannot be
+    /// moved back is evicted. The statement cache of the connection is
+    /// cleared on every move, since its cursors belong to the previous
+    /// container.
+    ///
+    /// `container` must be a plain identifier; it is upper-cased like any
+    /// unquoted name. Moving a session needs the `SET CONTAINER` privilege
+    /// in the target container.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+    /// let conn = pool.get_for_container("PDB2").await?;
+    /// let rows = conn.query("SELECT id FROM orders", &[]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_for_container(&self, container: &str) -> Result<Object, PoolError> {
+        let container = container::container_name(container);
+        let container = container.map_err(managed::PoolError::Backend)?;
+        // A replacement for a dead connection wouldn't be switched
+        let conn = self.checkout(&self.timeouts()).await?;
+        let switched = self
+            .manager()
+            .switch_container(conn.connection(), &container)
+            .await;
+        switched.map_err(managed::PoolError::Backend)?;
+        Ok(conn)
+    }
+
+    /// Get a connection with its current schema set to `schema`
+    ///
+    /// Unqualified names in the statements run on the connection resolve
+    /// to `schema` instead of the schema of the pool's user, so a pool of a
+    /// technical user with grants on several schemas can serve all of them.
+    /// The session is switched with `ALTER SESSION SET CURRENT_SCHEMA`
+    /// unless it already is in `schema`, and switched back when the
+    /// connection is recycled; a session that cannot be switched back is
+    /// evicted. The statement cache of the connection is cleared on every
+    /// switch, since its cursors resolved names in the previous schema.
+    /// Privileges are still those of the pool's user.
+    ///
+    /// `schema` must be a plain identifier; it is upper-cased like any
+    /// unquoted name.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+    /// let conn = pool.get_with_schema("HR").await?;
+    /// let rows = conn.query("SELECT last_name FROM employees", &[]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_with_schema(&self, schema: &str) -> Result<Object, PoolError> {
+        let schema = schema::schema_name(schema).map_err(managed::PoolError::Backend)?;
+        // A replacement for a dead connection wouldn't be switched
+        let conn = self.checkout(&self.timeouts()).await?;
+        let switched = self
+            .manager()
+            .switch_schema(conn.connection(), &schema)
+            .await;
+        switched.map_err(managed::PoolError::Backend)?;
+        Ok(conn)
+    }
+
+    /// Get a connection with its client identifier set to `identifier`
+    ///
+    /// See `Object::set_client_identifier()`. The identifier is cleared
+    /// when the connection is returned. If it can't be set, the connection
+    /// is returned to the pool and the error returned.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool, user_id: &str) -> Result<(), deadpool_oracle::PoolError> {
+    /// let conn = pool.get_with_client_identifier(user_id).await?;
+    /// conn.execute("UPDATE accounts SET locked = 1 WHERE owner = :1", &[user_id.into()])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_with_client_identifier(&self, identifier: &str) -> Result<Object, PoolError> {
+        let conn = self.get().await?;
+        let set = conn.set_client_identifier(identifier).await;
+        set.map_err(managed::PoolError::Backend)?;
+        Ok(conn)
+    }
+
+    /// Get a connection, preferring one previously tagged `tag`
+    ///
+    /// Useful when borrowers set up expensive session state, such as
+    /// temporary tables or application contexts: tag the connection with
+    /// `Object::set_tag()` once the state is in place, and later checkouts
+    /// asking for the tag get that connection back if it is idle. Otherwise
+    /// any connection is returned; check `Object::tag()` to tell whether the
+    /// state has to be set up, or let the fixup callback registered with
+    /// `PoolBuilder::tag_fixup()` do it.
+    ///
+    /// Idle connections are handed out in queue order, so finding a tagged
+    /// one may pass over others; only the connection handed out is
+    /// health-checked. The search runs while an idle connection carries the
+    /// tag, and no new connections are created for it.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let conn = pool.get_tagged("reporting").await?;
+    /// if conn.tag().as_deref() != Some("reporting") {
+    ///     conn.execute("BEGIN reporting_pkg.init_session; END;", &[]).await?;
+    ///     conn.set_tag(Some("reporting"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_tagged(&self, tag: &str) -> Result<Object, PoolError> {
+        // Read before entering the pool's own span
+        #[cfg(feature = "tracing")]
+        let span_info = self
+            .manager()
+            .context_from_span
+            .then(AppInfo::from_current_span);
+
+        let conn = self.timeout_get(&self.timeouts()).await?;
+        let conn = match conn.tag().as_deref() == Some(tag) {
+            true => conn,
+            false => self.find_tagged(conn, tag).await,
+        };
+        // Only the connection handed out is set up, not the ones skipped
+        let conn = self
+            .set_up_checkout(
+                conn,
+                #[cfg(feature = "tracing")]
+                span_info,
+            )
+            .await?;
+        let current = conn.tag();
+        if current.as_deref() == Some(tag) {
+            return Ok(conn);
+        }
+        let Some(fixup) = self.manager().tag_fixup.clone() else {
+            return Ok(conn);
+        };
+
+        let fixed = conn.setting_up(fixup(&conn, tag, current.as_deref())).await;
+        if let Err(e) = fixed {
+            // The session may be half set up, so it is not reused
+            conn.mark_closed();
+            return Err(managed::PoolError::Backend(e));
+        }
+        conn.set_tag(Some(tag));
+        Ok(conn)
+    }
+
+    /// Look for an idle connection tagged `tag`, falling back to `conn`
+    ///
+    /// Only idle connections are taken, never new ones: those passed over
+    /// skip the health check, and the search stops once no idle connection
+    /// carries the tag.
+    async fn find_tagged(&self, conn: Object, tag: &str) -> Object {
+        let manager = self.manager();
+        if !manager.tags.contains(tag) {
+            return conn;
+        }
+        let no_wait = self.no_wait_timeouts();
+        // Returned to the pool once the search is over
+        let mut skipped = Vec::new();
+
+        while self.has_idle_tagged(tag) {
+            let start = manager.clock.now();
+            let scan = TAG_SCAN.scope(tag.to_string(), self.inner.timeout_get(&no_wait));
+            let Ok(candidate) = scan.await else {
+                break;
+            };
+            if manager.tags.get(candidate.id()).as_deref() == Some(tag) {
+                let latency = AcquireLatency::new(manager.elapsed(start), None);
+                return self.hand_out(candidate, latency).await;
+            }
+            skipped.push(candidate);
+        }
+        conn
+    }
+
+    /// Whether an idle connection carries `tag`
+    fn has_idle_tagged(&self, tag: &str) -> bool {
+        let tags = &self.manager().tags;
+        let mut found = false;
+        self.inner.retain(|conn, _| {
+            found |= tags.get(conn.id()).as_deref() == Some(tag);
+            true
+        });
+        found
+    }
+
+    /// Report the outcome of an acquisition
+    fn on_acquire(&self, result: &Result<Object, PoolError>, wait: Duration) {
+        let manager = self.manager();
+        let outcome = match result {
+            Ok(_) => AcquireOutcome::Acquired,
+            Err(managed::PoolError::Timeout(_)) => {
+                manager.events.emit(PoolEvent::AcquireTimeout { wait });
+                AcquireOutcome::TimedOut
+            }
+            Err(_) => AcquireOutcome::Failed,
+        };
+        manager.counters.record_acquire(wait, outcome);
+
+        #[cfg(feature = "metrics")]
+        metrics_facade::record_acquire(
+            manager.name(),
+            wait,
+            outcome == AcquireOutcome::TimedOut,
+            self.status(),
+        );
+
+        if manager
+            .slow_acquire_threshold
+            .is_some_and(|threshold| wait > threshold)
+        {
+            let status = self.status();
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                ?wait,
+                size = status.size,
+                available = status.available,
+                waiting = status.waiting,
+                max_size = status.max_size,
+                "slow connection acquisition"
+            );
+
+            manager.events.emit(PoolEvent::SlowAcquire {
+                wait,
+                size: status.size,
+                available: status.available,
+                waiting: status.waiting,
+            });
+        }
+
+        #[cfg(feature = "tracing")]
+        match result {
+            Ok(conn) => {
+                tracing::Span::current().record("connection_id", conn.id());
+                tracing::debug!(?wait, outcome = "acquired", "connection acquired");
+            }
+            Err(e) => {
+                tracing::warn!(?wait, outcome = "failed", error = %e, "failed to acquire connection");
+            }
+        }
+
+        #[cfg(feature = "otel")]
+        self.manager().otel.record_acquire(result, wait);
+    }
+
+    /// Get the name identifying this pool in telemetry
+    pub fn name(&self) -> &str {
+        self.manager().name()
+    }
+
+    /// Get the current status of the pool
+    pub fn status(&self) -> Status {
+        self.inner.status()
+    }
+
+    /// Get the configured timeouts
+    pub fn timeouts(&self) -> Timeouts {
+        self.inner.timeouts()
+    }
+
+    /// Change the maximum number of connections in the pool
+    ///
+    /// Excess idle connections are dropped when shrinking.
+    pub fn resize(&self, max_size: usize) {
+        self.inner.resize(max_size)
+    }
+
+    /// Close the pool and its shard and read-only sub-pools
+    ///
+    /// All current and future waiters get `PoolError::Closed`.
+    pub fn close(&self) {
+        for shard in self.shards() {
+            shard.close();
+        }
+        if let Some(readonly) = self.readonly() {
+            readonly.close();
+        }
+        self.inner.close()
+    }
+
+    /// Check whether the pool has been closed
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Get the connection manager used by this pool
+    pub fn manager(&self) -> &OracleConnectionManager {
+        self.inner.manager()
+    }
+
+    /// Subscribe to lifecycle events of this pool
+    ///
+    /// Events include created and evicted connections, acquisition timeouts
+    /// and failed health checks. Each subscriber gets its own copy of every
+    /// event emitted after subscribing; slow subscribers skip the oldest
+    /// events once 256 are buffered.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) {
+    /// let mut events = pool.subscribe_events();
+    /// tokio::spawn(async move {
+    ///     while let Ok(event) = events.recv().await {
+    ///         println!("pool event: {:?}", event);
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<PoolEvent> {
+        self.manager().subscribe_events()
+    }
+
+    /// Get statistics accumulated since the pool was created
+    ///
+    /// Includes acquisition wait-time percentiles, queue depth, creations,
+    /// evictions and recycle failures.
+    pub fn stats(&self) -> PoolStats {
+        let manager = self.manager();
+        manager.counters.snapshot(
+            self.status(),
+            manager.recycle_metrics(),
+            manager.statement_caches.snapshot(),
+            manager.quarantine_remaining(),
+        )
+    }
+
+    /// Get the status, counters and settings of the pool in one value
+    ///
+    /// Meant for admin and debug endpoints; with the `serde` feature it
+    /// serializes as is:
+    ///
+    /// ```rust,ignore
+    /// async fn pool_status(State(pool): State<Pool>) -> Json<PoolSnapshot> {
+    ///     Json(pool.status_snapshot())
+    /// }
+    /// ```
+    pub fn status_snapshot(&self) -> PoolSnapshot {
+        let manager = self.manager();
+        let status = self.status();
+        let stats = self.stats();
+        let timeouts = self.timeouts();
+        let config = manager.config.read().unwrap();
+        let service = match &config.service {
+            ServiceMethod::ServiceName(name) | ServiceMethod::Sid(name) => name.clone(),
+        };
+
+        PoolSnapshot {
+            name: self.name().to_string(),
+            size: status.size,
+            available: status.available,
+            waiting: status.waiting,
+            max_size: status.max_size,
+            connections_created: stats.connections_created,
+            create_failures: stats.create_failures,
+            evictions: stats.evictions(),
+            acquire_failures: stats.acquire_failures,
+            timeouts: stats.timeouts,
+            circuit_open: self.is_circuit_open(),
+            config: ConfigSummary {
+                host: config.host.clone(),
+                port: config.port,
+                service,
+                username: config.username.clone(),
+                wait_timeout_ms: stats::millis(timeouts.wait),
+                create_timeout_ms: stats::millis(timeouts.create),
+                recycle_timeout_ms: stats::millis(timeouts.recycle),
+                idle_timeout_ms: stats::millis(manager.idle_timeout),
+                statement_cache_size: config.stmtcachesize,
+            },
+        }
+    }
+
+    /// Retire every connection and connect anew, to `config` if given
+    ///
+    /// Idle connections are closed at once. Connections checked out are
+    /// closed when they are returned, so in-flight work finishes
+    /// undisturbed; with commit-on-drop they are committed first and closed
+    /// on their next checkout instead. Later `get()` calls create new
+    /// connections as usual, using `config` from now on if given, apart
+    /// from its statement cache size, which stays as configured. Failover
+    /// endpoints are kept.
+    ///
+    /// Useful for rolling database maintenance: drain the pools of one
+    /// instance before taking it down, or point them at another host.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) {
+    /// use oracle_rs::Config;
+    ///
+    /// let standby = Config::new("db-2.example.com", 1521, "ORDERS", "app", "secret");
+    /// pool.drain_and_refresh(Some(standby)).await;
+    /// # }
+    /// ```
+    pub async fn drain_and_refresh(&self, config: Option<Config>) {
+        let manager = self.manager();
+        if let Some(mut config) = config {
+            let mut current = manager.config.write().unwrap();
+            config.stmtcachesize = current.stmtcachesize;
+            *current = config;
+        }
+        manager.retire_connections();
+
+        let keep = |_: &Connection, metrics: Metrics| !manager.is_retired(&metrics);
+        for conn in self.inner.retain(keep).removed {
+            manager.record_eviction(&conn, EvictionReason::Retired);
+            let _ = conn.close().await;
+        }
+    }
+
+    /// Close all idle connections, returning how many were closed
+    ///
+    /// Connections checked out at the time are not affected. Serverless
+    /// functions can call this before the platform freezes them, so their
+    /// sessions don't outlive the instance on the server:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+    /// let conn = pool.get().await?;
+    /// // ... handle the invocation ...
+    /// drop(conn);
+    /// pool.close_all_idle().await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn close_all_idle(&self) -> usize {
+        self.close_idle(|_, _| false).await
+    }
+
+    /// Close the idle connections that `keep` rejects
+    async fn close_idle(&self, mut keep: impl FnMut(&Connection, &Metrics) -> bool) -> usize {
+        let retained = self.inner.retain(|conn, metrics| keep(conn, &metrics));
+        let count = retained.removed.len();
+        for conn in retained.removed {
+            self.manager().record_eviction(&conn, EvictionReason::Idle);
+            let _ = conn.close().await;
+        }
+        count
+    }
+
+    /// Start the background task closing connections idle for too long
+    ///
+    /// Runs only if the pool is built within a Tokio runtime; otherwise
+    /// such connections are closed on their next checkout.
+    fn start_idle_reaper(&self, timeout: Duration) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let pool = self.inner.weak();
+        let clock = self.manager().clock.clone();
+        let period = (timeout / 2).max(Duration::from_secs(1));
+
+        let reaper = async move {
+            loop {
+                clock.sleep(period).await;
+                let Some(inner) = pool.upgrade().filter(|pool| !pool.is_closed()) else {
+                    return;
+                };
+                let pool = Pool { inner };
+                let manager = pool.manager();
+                pool.close_idle(|conn, metrics| !manager.is_idle_expired(conn, metrics))
+                    .await;
+            }
+        };
+        task::spawn_on("oracle_pool.idle_reaper", reaper, &runtime);
+    }
+
+    /// Start the background task asking the credential provider again every
+    /// `interval`, and `margin` before the cached credentials expire
+    fn start_credential_refresh(&self, interval: Option<Duration>, margin: Duration) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let pool = self.inner.weak();
+        let clock = self.manager().clock.clone();
+
+        let refresh = async move {
+            let mut failed = false;
+            loop {
+                let due = {
+                    let Some(inner) = pool.upgrade().filter(|pool| !pool.is_closed()) else {
+                        return;
+                    };
+                    let Some(cache) = &inner.manager().credentials else {
+                        return;
+                    };
+                    let due = cache.refresh_due(margin).await;
+                    due.map(|at| at.saturating_duration_since(clock.now()))
+                };
+                let wait = match (interval, due) {
+                    (Some(interval), Some(due)) => Some(interval.min(due)),
+                    (interval, due) => interval.or(due),
+                };
+                // Credentials fetched later may expire, so look again then
+                let Some(mut wait) = wait else {
+                    clock.sleep(margin).await;
+                    continue;
+                };
+                if failed {
+                    wait = wait.max(CREDENTIAL_RETRY_DELAY);
+                }
+
+                clock.sleep(wait).await;
+                let Some(inner) = pool.upgrade().filter(|pool| !pool.is_closed()) else {
+                    return;
+                };
+                let Some(cache) = &inner.manager().credentials else {
+                    return;
+                };
+                // Failures keep the cached credentials until the next round
+                failed = match inner.manager().refresh_credentials(cache).await {
+                    Ok(_) => false,
+                    Err(_e) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(error = %_e, "failed to refresh credentials");
+
+                        true
+                    }
+                };
+            }
+        };
+        task::spawn_on("oracle_pool.credential_refresh", refresh, &runtime);
+    }
+
+    /// Start the background task killing idle connections for `injector`
+    #[cfg(feature = "testing")]
+    fn start_chaos(&self, injector: testing::FaultInjector, interval: Duration) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let pool = self.inner.weak();
+        let clock = self.manager().clock.clone();
+
+        let chaos = async move {
+            loop {
+                clock.sleep(interval).await;
+                let Some(inner) = pool.upgrade().filter(|pool| !pool.is_closed()) else {
+                    return;
+                };
+                // Closed in place, so the next checkout has to notice
+                inner.retain(|conn, _| {
+                    if injector.kill_idle() {
+                        conn.mark_closed();
+                    }
+                    true
+                });
+            }
+        };
+        task::spawn_on("oracle_pool.chaos", chaos, &runtime);
+    }
+
+    /// Get recycle durations, health check failures and eviction counts
+    ///
+    /// Useful to tell whether health checks are a source of tail latency.
+    pub fn recycle_metrics(&self) -> RecycleMetrics {
+        self.manager().recycle_metrics()
+    }
+
+    /// Check that the database is reachable through this pool
+    ///
+    /// Acquires a connection, creating one if the pool is empty, and pings
+    /// it. The whole check is bounded by `timeout`, measured on the pool's
+    /// clock. Intended for readiness probes:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) {
+    /// use std::time::Duration;
+    ///
+    /// let report = pool.health_check(Duration::from_secs(2)).await;
+    /// if !report.healthy {
+    ///     eprintln!("not ready: {:?}", report.error);
+    /// }
+    /// # }
+    /// ```
+    pub async fn health_check(&self, timeout: Duration) -> HealthReport {
+        let start = self.manager().clock.now();
+
+        let check = async {
+            let conn = self.get().await.map_err(|e| e.to_string())?;
+            conn.connection().ping().await.map_err(|e| e.to_string())
+        };
+        let result = clock::timeout(&*self.manager().clock, timeout, check)
+            .await
+            .unwrap_or_else(|| Err(format!("health check timed out after {:?}", timeout)));
+
+        let latency = self.manager().elapsed(start);
+        let error = result.err();
+        let last_error = self.manager().health.record(error.as_deref());
+
+        HealthReport {
+            healthy: error.is_none(),
+            latency,
+            status: self.status(),
+            error,
+            last_error,
+        }
+    }
+
+    /// Run `f` on a pooled connection, moving to another one if it is dead
+    ///
+    /// A connection passes its health check when it is handed out, but the
+    /// session can still be lost before `f` gets to use it, for example when
+    /// the network drops or a DBA kills it. If the first statement `f` runs
+    /// fails with a connection error or because the session was killed
+    /// (ORA-00028, ORA-02396, ...), the connection is evicted and `f` is
+    /// called again from the start on another connection, as often as
+    /// configured with `PoolBuilder::reconnect_retries`. Failures of later
+    /// statements are returned as they are, since work done by the earlier
+    /// ones may be lost. Every use of the `Connection` through `Deref`, such
+    /// as `conn.execute_plsql()`, as well as `commit()` and `rollback()`,
+    /// counts as a statement, since the pool can't tell whether it reached
+    /// the database.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+    /// let result = pool
+    ///     .with_connection(|conn| {
+    ///         Box::pin(async move { conn.query("SELECT name FROM customers", &[]).await })
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_connection<T, F>(&self, mut f: F) -> Result<T, PoolError>
+    where
+        F: for<'c> FnMut(&'c Object) -> Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'c>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            let conn = self.get().await?;
+            let before = conn.statements.load(Ordering::Relaxed);
+            match f(&conn).await {
+                Err(e)
+                    if (e.is_connection_error() || is_session_killed(&e))
+                        && conn.statements.load(Ordering::Relaxed) - before <= 1
+                        && attempt < self.manager().reconnect_retries =>
+                {
+                    attempt += 1;
+                    // Evicted instead of recycled once dropped
+                    conn.mark_closed();
+
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(attempt, connection_id = conn.id(), error = %e, "retrying on another connection");
+                }
+                result => return result.map_err(managed::PoolError::Backend),
+            }
+        }
+    }
+
+    /// Run `f` in a transaction on a pooled connection
+    ///
+    /// The transaction is committed if `f` returns `Ok` and rolled back if it
+    /// returns `Err`. Serialization failures (ORA-08177) are retried on the
+    /// same connection as configured with `PoolBuilder::transaction_retries`,
+    /// calling `f` again from the start.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+    /// let moved = pool
+    ///     .with_transaction(|tx| {
+    ///         Box::pin(async move {
+    ///             tx.execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1", &[])
+    ///                 .await?;
+    ///             let result = tx
+    ///                 .execute("UPDATE accounts SET balance = balance + 10 WHERE id = 2", &[])
+    ///                 .await?;
+    ///             Ok(result.rows_affected)
+    ///         })
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_transaction<T, F>(&self, mut f: F) -> Result<T, PoolError>
+    where
+        F: for<'c> FnMut(
+            &'c mut Transaction<'_>,
+        ) -> Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'c>>,
+    {
+        let manager = self.manager();
+        let mut conn = self.get().await?;
+        let mut backoff = manager.transaction_retry_backoff;
+        let mut attempt = 0;
+
+        loop {
+            let result = async {
+                let mut tx = conn.begin().await?;
+                match f(&mut tx).await {
+                    Ok(value) => {
+                        tx.commit().await?;
+                        Ok(value)
+                    }
+                    Err(e) => {
+                        // Report the original error; the guard rolls back on
+                        // its next use if this fails.
+                        let _ = tx.rollback().await;
+                        Err(e)
+                    }
+                }
+            }
+            .await;
+
+            match result {
+                Err(e) if is_serialization_failure(&e) && attempt < manager.transaction_retries => {
+                    attempt += 1;
+
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(attempt, ?backoff, "retrying serialized transaction");
+
+                    let sleep = manager.clock.sleep(backoff);
+                    #[cfg(feature = "tracing")]
+                    let sleep = tracing::Instrument::instrument(
+                        sleep,
+                        tracing::debug_span!("oracle_pool.transaction_backoff", attempt),
+                    );
+                    sleep.await;
+                    backoff *= 2;
+                }
+                result => return result.map_err(managed::PoolError::Backend),
+            }
+        }
+    }
+}
+
+/// A connection checked out from the pool
+///
+/// This wraps a `Connection` and automatically returns it to the pool when dropped.
+pub struct Object {
+    /// Only `None` while being dropped with commit-on-drop
+    inner: Option<managed::Object<OracleConnectionManager>>,
+    /// Connection taken over from `inner` by `replace_dead()`, moved there
+    /// on the next mutable access
+    replacement: OnceLock<managed::Object<OracleConnectionManager>>,
+    /// Whether a connection found dead by the first statement is replaced,
+    /// see `Pool::get()`
+    reconnect: bool,
+    checked_out_at: Instant,
+    acquire_latency: AcquireLatency,
+    commit_on_drop: bool,
+    call_timeout: Option<Duration>,
+    hold_limit: Option<HoldLimit>,
+    hold_watch: Option<HoldWatch>,
+    rollback_pending: Mutex<Option<PendingRollback>>,
+    savepoint_seq: u32,
+    hooks: Mutex<Vec<Hook>>,
+    statement_caches: Arc<StatementCaches>,
+    fetch_array_size: u32,
+    tags: Arc<ConnectionTags>,
+    app_info: Arc<AppInfos>,
+    session_settings: Arc<SessionSettings>,
+    query_observer: Option<Arc<dyn QueryObserver>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    clock: Arc<dyn Clock>,
+    /// Statements run through this checkout, counting each use of the
+    /// `Connection` through `Deref`
+    statements: AtomicU32,
+    /// Set while the pool prepares the checkout, whose statements are not
+    /// the caller's
+    setting_up: AtomicBool,
+    _leak_watch: Option<LeakWatch>,
+}
+
+impl Object {
+    fn new(
+        inner: managed::Object<OracleConnectionManager>,
+        manager: &OracleConnectionManager,
+    ) -> Self {
+        let leak_watch = manager.leak_detector.as_ref().map(|detector| {
+            detector.watch(
+                inner.id(),
+                manager.events.clone(),
+                &manager.clock,
+                manager.deterministic,
+            )
+        });
+
+        Self {
+            inner: Some(inner),
+            replacement: OnceLock::new(),
+            reconnect: false,
+            checked_out_at: manager.clock.now(),
+            acquire_latency: AcquireLatency::default(),
+            commit_on_drop: manager.commit_on_drop,
+            call_timeout: manager.call_timeout,
+            hold_limit: manager.hold_limit,
+            hold_watch: None,
+            rollback_pending: Mutex::new(None),
+            savepoint_seq: 0,
+            hooks: Mutex::new(Vec::new()),
+            statement_caches: manager.statement_caches.clone(),
+            fetch_array_size: manager.fetch_array_size,
+            tags: manager.tags.clone(),
+            app_info: manager.app_info.clone(),
+            session_settings: manager.session_settings.clone(),
+            query_observer: manager.query_observer.clone(),
+            audit_sink: manager.audit_sink.clone(),
+            clock: manager.clock.clone(),
+            statements: AtomicU32::new(0),
+            setting_up: AtomicBool::new(false),
+            _leak_watch: leak_watch,
+        }
+    }
+
+    /// Take this connection out of the pool permanently
+    ///
+    /// This reduces the size of the pool.
+    pub fn take(mut this: Self) -> Connection {
+        this.settle_replacement();
+        this.statement_caches.remove(this.id());
+        managed::Object::take(this.inner.take().expect("connection already returned"))
+    }
+
+    /// Get deadpool's metrics for this connection
+    pub fn metrics(this: &Self) -> &Metrics {
+        managed::Object::metrics(this.inner())
+    }
+
+    /// Get the time this connection was checked out
+    pub fn checked_out_at(this: &Self) -> Instant {
+        this.checked_out_at
+    }
+
+    /// Get how long the checkout of this connection took
+    ///
+    /// Splits the time into waiting for the pool and creating a new
+    /// connection, so per-request logs can tell pool contention from slow
+    /// queries:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+    /// let conn = pool.get().await?;
+    /// let latency = conn.acquire_latency();
+    /// println!("waited {:?}, connected in {:?}", latency.wait, latency.create);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn acquire_latency(&self) -> AcquireLatency {
+        self.acquire_latency
+    }
+
+    /// Get usage statistics for this connection
+    ///
+    /// Useful for decisions such as not starting a long batch job on a
+    /// connection that is about to reach its maximum age.
+    pub fn stats(this: &Self) -> ConnectionStats {
+        ConnectionStats::new(this.id(), Object::metrics(this))
+    }
+
+    /// Get the ID of the underlying connection
+    ///
+    /// Same as `Connection::id()`; defined here so that reading it isn't
+    /// taken for a statement by [`Pool::with_connection()`].
+    pub fn id(&self) -> u32 {
+        self.inner().id()
+    }
+
+    /// Check if the connection is closed
+    ///
+    /// Same as `Connection::is_closed()`, see [`id()`](Self::id).
+    pub fn is_closed(&self) -> bool {
+        self.inner().is_closed()
+    }
+
+    /// Mark the connection as closed, so that it is evicted once returned
+    ///
+    /// Same as `Connection::mark_closed()`, see [`id()`](Self::id).
+    pub fn mark_closed(&self) {
+        self.inner().mark_closed()
+    }
+
+    /// Get the tag of this connection, see [`Pool::get_tagged()`]
+    pub fn tag(&self) -> Option<String> {
+        self.tags.get(self.id())
+    }
+
+    /// Tag this connection, or clear its tag with `None`
+    ///
+    /// The tag stays with the connection when it is returned to the pool.
+    /// Clear it when undoing the session state it stands for.
+    pub fn set_tag(&self, tag: Option<&str>) {
+        self.tags.set(self.id(), tag);
+    }
+
+    /// Set the module and action of this session
+    ///
+    /// Calls `DBMS_APPLICATION_INFO.SET_MODULE`, so `V$SESSION.MODULE` and
+    /// `ACTION`, ASH and AWR attribute the work of the session to them. They
+    /// stay with the session when it is returned to the pool; the round
+    /// trip is skipped when they are already set. The server truncates the
+    /// module to 48 bytes and the action to 32.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let conn = pool.get().await?;
+    /// conn.set_context("orders", "create_order").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_context(&self, module: &str, action: &str) -> Result<(), Error> {
+        let info = AppInfo::new(module, action);
+        self.update_app_info(Some(info)).await
+    }
+
+    /// Set the client identifier of this session for this checkout
+    ///
+    /// Calls `DBMS_SESSION.SET_IDENTIFIER`, so `V$SESSION.CLIENT_IDENTIFIER`,
+    /// ASH and the audit trail name the end user or request the session
+    /// works for, such as the authenticated user id or a trace id. The
+    /// identifier is cleared when the connection is returned, so an idle
+    /// session doesn't show it, and checked again when it is recycled, so
+    /// it never leaks to the next borrower. At most 64 bytes.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let conn = pool.get().await?;
+    /// conn.set_client_identifier("alice@example.com").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_client_identifier(&self, identifier: &str) -> Result<(), Error> {
+        // Marked first, so a session left half set is still cleared
+        self.app_info.set_identifier(self.id(), Some(identifier));
+        app_info::set_identifier(self, identifier).await
+    }
+
+    /// Change a session parameter for this checkout
+    ///
+    /// Issues `ALTER SESSION SET name = value`, for example to gather row
+    /// source statistics with `statistics_level` set to `ALL` or to enable
+    /// `sql_trace` on a single production connection. The value it replaces
+    /// is read first, from `NLS_SESSION_PARAMETERS` for NLS parameters and
+    /// from `V$PARAMETER`, which the user needs to be able to select from,
+    /// for the others. It is set back when the connection is recycled,
+    /// before the pool re-asserts its own NLS settings, so the next borrower
+    /// gets the session as it was. A parameter that had no value can't be
+    /// set back; its connection is closed instead. Values that are plain
+    /// words or integers are used as they are, anything else is quoted as a
+    /// string.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let conn = pool.get().await?;
+    /// conn.with_session_setting("statistics_level", "ALL").await?;
+    /// conn.query_all("SELECT * FROM orders WHERE status = 'OPEN'", &[]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_session_setting(&self, name: &str, value: &str) -> Result<(), Error> {
+        self.session_settings.set(self, name, value).await
+    }
+
+    /// Set or clear the module and action unless the session has them
+    async fn update_app_info(&self, info: Option<AppInfo>) -> Result<(), Error> {
+        if self.app_info.get(self.id()) == info {
+            return Ok(());
+        }
+        app_info::set_module(self, info.as_ref()).await?;
+        self.app_info.set(self.id(), info);
+        Ok(())
+    }
+
+    /// Begin a transaction on this connection
+    ///
+    /// The returned guard rolls back on drop unless it was committed.
+    /// Uncommitted work of a previously dropped guard is rolled back first.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut conn = pool.get().await?;
+    /// let tx = conn.begin().await?;
+    /// tx.execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1", &[]).await?;
+    /// tx.execute("UPDATE accounts SET balance = balance + 10 WHERE id = 2", &[]).await?;
+    /// tx.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn begin(&mut self) -> Result<Transaction<'_>, Error> {
+        self.start_transaction().await?;
+        Ok(Transaction::new(self))
+    }
+
+    /// Settle what an earlier guard left behind and start watching the hold time
+    pub(crate) async fn start_transaction(&mut self) -> Result<(), Error> {
+        if self.rollback_pending.get_mut().unwrap().is_some() {
+            self.rollback().await?;
+            *self.rollback_pending.get_mut().unwrap() = None;
+        }
+        if let Some(limit) = self.hold_limit {
+            if let Some(pool) = managed::Object::pool(self.inner()) {
+                let manager = pool.manager();
+                let events = manager.events.clone();
+                let deterministic = manager.deterministic;
+                self.hold_watch = Some(limit.watch(self.id(), events, &self.clock, deterministic));
+            }
+        }
+        Ok(())
+    }
+
+    /// Begin a read-only transaction on this connection
+    ///
+    /// Issues `SET TRANSACTION READ ONLY`: all queries see the database as
+    /// of the start of the transaction, and any DML fails with ORA-01456.
+    /// Useful for reports that run several queries and need them to agree.
+    /// The read-only mode ends with the transaction.
+    pub async fn begin_read_only(&mut self) -> Result<Transaction<'_>, Error> {
+        let mut tx = self.begin().await?;
+        tx.set_read_only().await?;
+        Ok(tx)
+    }
+
+    /// Commit the current transaction
+    ///
+    /// Same as `Connection::commit()`; defined here so that it is not
+    /// shadowed by [`AnyConnection::commit()`] where that trait is in scope.
+    pub async fn commit(&self) -> Result<(), Error> {
+        self.count_statement();
+        self.inner().commit().await
+    }
+
+    /// Roll back the current transaction
+    ///
+    /// Same as `Connection::rollback()`, see [`commit()`](Self::commit).
+    pub async fn rollback(&self) -> Result<(), Error> {
+        self.count_statement();
+        self.inner().rollback().await
+    }
+
+    /// Execute a statement
+    ///
+    /// Same as `Connection::execute()`, and counted in the statement cache
+    /// metrics and reported to the query observer of the pool.
+    pub async fn execute(&self, sql: &str, params: &[Value]) -> Result<QueryResult, Error> {
+        self.statement_caches.record(self.id(), sql);
+        self.observe(
+            sql,
+            Binds::Values(params),
+            |conn| conn.execute(sql, params),
+            |result| Some(result.rows_affected),
+        )
+        .await
+    }
+
+    /// Run a query
+    ///
+    /// Same as `Connection::query()`, and counted in the statement cache
+    /// metrics and reported to the query observer of the pool.
+    pub async fn query(&self, sql: &str, params: &[Value]) -> Result<QueryResult, Error> {
+        self.statement_caches.record(self.id(), sql);
+        self.observe(
+            sql,
+            Binds::Values(params),
+            |conn| conn.query(sql, params),
+            |result| Some(result.rows.len() as u64),
+        )
+        .await
+    }
+
+    /// Run a query and fetch all of its rows
+    ///
+    /// `query()` returns only the first batch of rows and sets
+    /// `has_more_rows` if there are more. This keeps fetching, with the
+    /// pool's [`fetch_array_size`](PoolBuilder::fetch_array_size) rows per
+    /// round trip, until the result set is exhausted.
+    pub async fn query_all(&self, sql: &str, params: &[Value]) -> Result<QueryResult, Error> {
+        cursor::fetch_all(self, sql, params, self.fetch_array_size).await
+    }
+
+    /// Run a query and fetch all of its rows, `fetch_size` rows per round trip
+    ///
+    /// Overrides the pool's fetch array size for one query, for example a
+    /// large analytics query on a pool tuned for small lookups:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let conn = pool.get().await?;
+    /// let result = conn
+    ///     .query_all_with_fetch_size("SELECT * FROM sales_history", &[], 1000)
+    ///     .await?;
+    /// println!("{} rows", result.row_count());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_all_with_fetch_size(
+        &self,
+        sql: &str,
+        params: &[Value],
+        fetch_size: u32,
+    ) -> Result<QueryResult, Error> {
+        cursor::fetch_all(self, sql, params, fetch_size).await
+    }
+
+    /// Run a query and map every row to `T`
+    ///
+    /// All rows are fetched, see [`Object::query_all()`]. `T` can be a tuple, to read columns by position, or a struct with
+    /// `#[derive(FromRow)]` (`derive` feature), to read them by name.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let conn = pool.get().await?;
+    /// let users: Vec<(i64, String)> = conn.query_as("SELECT id, name FROM users", &[]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_as<T: FromRow>(&self, sql: &str, params: &[Value]) -> Result<Vec<T>, Error> {
+        from_row::query_as(self, sql, params).await
+    }
+
+    /// Run a query that must return exactly one row
+    ///
+    /// Fails with `Error::NoDataFound` if there is no row and with ORA-01422
+    /// if there are several.
+    pub async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, Error> {
+        from_row::query_one(self, sql, params).await
+    }
+
+    /// Run a query that returns at most one row
+    ///
+    /// Fails with ORA-01422 if there are several rows.
+    pub async fn query_opt(&self, sql: &str, params: &[Value]) -> Result<Option<Row>, Error> {
+        from_row::query_opt(self, sql, params).await
+    }
+
+    /// Run a query returning a single value, such as `SELECT COUNT(*)`
+    ///
+    /// The query must return exactly one row; its first column is converted
+    /// to `T`.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let conn = pool.get().await?;
+    /// let count: i64 = conn.query_scalar("SELECT COUNT(*) FROM users", &[]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_scalar<T: FromValue>(
+        &self,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<T, Error> {
+        from_row::query_scalar(self, sql, params).await
+    }
+
+    /// Run one page of a query
+    ///
+    /// With [`PageRequest::offset()`] or [`PageRequest::page()`] the SQL must
+    /// end with its `ORDER BY`; with [`PageRequest::keyset()`] the rows are
+    /// ordered by the key column. Follow [`Page::next`] to get the next page.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use deadpool_oracle::PageRequest;
+    ///
+    /// let conn = pool.get().await?;
+    /// let page = conn
+    ///     .query_paged(
+    ///         "SELECT id, name FROM users ORDER BY id",
+    ///         &[],
+    ///         PageRequest::page(0, 50).with_total(),
+    ///     )
+    ///     .await?;
+    /// println!("{} of {:?} users", page.items.len(), page.total);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_paged(
+        &self,
+        sql: &str,
+        params: &[Value],
+        request: PageRequest,
+    ) -> Result<Page<Row>, Error> {
+        page::query_paged(self, sql, params, &request).await
+    }
+
+    /// Execute a statement with named binds
+    ///
+    /// Placeholders are written as `:name` and bound from `binds`, usually
+    /// built with [`binds!`]:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use deadpool_oracle::binds;
+    ///
+    /// let conn = pool.get().await?;
+    /// conn.execute_named(
+    ///     "UPDATE users SET name = :name WHERE id = :id",
+    ///     &binds! { "id" => 42, "name" => "SMITH" },
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_named(&self, sql: &str, binds: &NamedBinds) -> Result<QueryResult, Error> {
+        let (sql, params) = binds.to_positional(sql)?;
+        self.execute(&sql, &params).await
+    }
+
+    /// Run a query with named binds
+    ///
+    /// See [`Object::execute_named()`].
+    pub async fn query_named(&self, sql: &str, binds: &NamedBinds) -> Result<QueryResult, Error> {
+        let (sql, params) = binds.to_positional(sql)?;
+        self.query(&sql, &params).await
+    }
+
+    /// Execute a DML statement once per row of binds using array binding
+    ///
+    /// Rows are sent a thousand per round trip. A row that fails does not
+    /// stop the others; it is reported in `BatchResult::errors` with its
+    /// index in `rows`. Nothing is committed.
+    ///
+    /// Unlike `Connection::execute_batch()`, this takes plain rows and splits
+    /// large batches into round-trip sized chunks.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use oracle_rs::Value;
+    ///
+    /// let conn = pool.get().await?;
+    /// let rows: Vec<Vec<Value>> = (0..10_000)
+    ///     .map(|i| vec![Value::Integer(i), Value::String(format!("user {}", i))])
+    ///     .collect();
+    /// let rows: Vec<&[Value]> = rows.iter().map(Vec::as_slice).collect();
+    ///
+    /// let result = conn
+    ///     .execute_many("INSERT INTO users (id, name) VALUES (:1, :2)", &rows)
+    ///     .await?;
+    /// for error in &result.errors {
+    ///     eprintln!("row {} failed: {}", error.row_index, error.message);
+    /// }
+    /// conn.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_many(&self, sql: &str, rows: &[&[Value]]) -> Result<BatchResult, Error> {
+        let binds = Binds::Rows { rows, returning: 0 };
+        self.observe(
+            sql,
+            binds,
+            |conn| batch::execute_many(conn, sql, rows),
+            |result| Some(result.total_rows_affected),
+        )
+        .await
+    }
+
+    /// Execute a PL/SQL block
+    ///
+    /// Same as `Connection::execute_plsql()`, and reported to the query
+    /// observer of the pool.
+    pub async fn execute_plsql(
+        &self,
+        sql: &str,
+        params: &[BindParam],
+    ) -> Result<PlsqlResult, Error> {
+        self.observe(
+            sql,
+            Binds::Params(params),
+            |conn| conn.execute_plsql(sql, params),
+            |_| None,
+        )
+        .await
+    }
+
+    /// Execute a batch of DML
+    ///
+    /// Same as `Connection::execute_batch()`, and reported to the query
+    /// observer of the pool.
+    pub async fn execute_batch(&self, batch: &BatchBinds) -> Result<BatchResult, Error> {
+        let binds = Binds::Count(batch.row_count() * batch.column_count());
+        self.observe(
+            batch.sql(),
+            binds,
+            |conn| conn.execute_batch(batch),
+            |result| Some(result.total_rows_affected),
+        )
+        .await
+    }
+
+    /// Execute a DML statement with a `RETURNING ... INTO` clause
+    ///
+    /// Binds are positional: `params` fill the first placeholders and the
+    /// `INTO` targets follow, typed by `returning`. The returned values come
+    /// back as a row in the same round trip. The statement must affect a
+    /// single row.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use oracle_rs::{OracleType, Value};
+    ///
+    /// let conn = pool.get().await?;
+    /// let row = conn
+    ///     .execute_returning(
+    ///         "INSERT INTO users (name) VALUES (:1) RETURNING id INTO :2",
+    ///         &[Value::String("Alice".to_string())],
+    ///         &[OracleType::Number],
+    ///     )
+    ///     .await?;
+    /// let id = row.get_i64(0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_returning(
+        &self,
+        sql: &str,
+        params: &[Value],
+        returning: &[OracleType],
+    ) -> Result<Row, Error> {
+        let mut rows = self
+            .execute_many_returning(sql, &[params], returning)
+            .await?;
+        Ok(rows.remove(0))
+    }
+
+    /// Execute a DML statement with a `RETURNING ... INTO` clause once per
+    /// row of binds
+    ///
+    /// Returns one row of returned values per row of binds. Rows are sent a
+    /// hundred per round trip. See [`Object::execute_returning()`].
+    pub async fn execute_many_returning(
+        &self,
+        sql: &str,
+        rows: &[&[Value]],
+        returning: &[OracleType],
+    ) -> Result<Vec<Row>, Error> {
+        let binds = Binds::Rows {
+            rows,
+            returning: returning.len(),
+        };
+        let statement = |conn| returning::execute_returning(conn, sql, rows, returning);
+        self.observe(
+            sql,
+            binds,
+            statement,
+            |returned| Some(returned.len() as u64),
+        )
+        .await
+    }
+
+    /// Call a stored procedure
+    ///
+    /// `name` may be qualified with a schema and package. Parameters are
+    /// passed in declaration order; OUT and IN OUT values are read back from
+    /// the result by the same position.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use deadpool_oracle::CallParams;
+    /// use oracle_rs::OracleType;
+    ///
+    /// let conn = pool.get().await?;
+    /// let result = conn
+    ///     .call(
+    ///         "hr.emp_pkg.get_name",
+    ///         &CallParams::new().input(42).output(OracleType::Varchar, 200),
+    ///     )
+    ///     .await?;
+    /// let name: Option<String> = result.get(1)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn call(&self, name: &str, params: &CallParams) -> Result<CallResult, Error> {
+        call::call(self, name, params).await
+    }
+
+    /// Iterate the rows of a REF CURSOR returned by a call on this connection
+    ///
+    /// Rows are fetched in batches as they are consumed:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use deadpool_oracle::CallParams;
+    ///
+    /// let conn = pool.get().await?;
+    /// let result = conn
+    ///     .call("hr.emp_pkg.list_employees", &CallParams::new().input(10).output_cursor())
+    ///     .await?;
+    /// let mut rows = conn.cursor_rows(result.cursor(1)?);
+    /// while let Some(row) = rows.next().await? {
+    ///     println!("{:?}", row.get_by_name("last_name"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cursor_rows(&self, cursor: RefCursor) -> CursorRows<'_> {
+        CursorRows::new(self, cursor, self.fetch_array_size)
+    }
+
+    /// Stream the contents of a CLOB or BLOB
+    ///
+    /// The returned reader implements `tokio::io::AsyncRead` and fetches the
+    /// LOB in chunks; CLOBs are read as UTF-8.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use oracle_rs::{LobValue, Value};
+    ///
+    /// let conn = pool.get().await?;
+    /// let row = conn
+    ///     .query_one("SELECT content FROM documents WHERE id = :1", &[Value::Integer(1)])
+    ///     .await?;
+    /// if let Some(Value::Lob(LobValue::Locator(locator))) = row.get(0) {
+    ///     let mut reader = conn.lob_reader(locator.clone());
+    ///     let mut file = tokio::fs::File::create("document.pdf").await?;
+    ///     tokio::io::copy(&mut reader, &mut file).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lob_reader(&self, locator: LobLocator) -> LobReader<'_> {
+        LobReader::new(self, locator)
+    }
+
+    /// Stream data into a CLOB or BLOB, starting at its beginning
+    ///
+    /// The returned writer implements `tokio::io::AsyncWrite` and sends the
+    /// data in chunks; CLOB data must be UTF-8. Call `shutdown()` to write
+    /// the last chunk. Existing content past the end of the new data is kept;
+    /// write into an `EMPTY_CLOB()`/`EMPTY_BLOB()` or use `lob_trim()` to
+    /// replace a LOB.
+    pub fn lob_writer(&self, locator: LobLocator) -> LobWriter<'_> {
+        LobWriter::new(self, locator)
+    }
+
+    /// Run statements in an autonomous transaction
+    ///
+    /// The statements added by `f` run in a PL/SQL block declared with
+    /// `PRAGMA AUTONOMOUS_TRANSACTION` and are committed on their own,
+    /// whatever happens to the transaction open on this connection. Meant for
+    /// audit logging that has to survive a rollback.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use oracle_rs::Value;
+    ///
+    /// let conn = pool.get().await?;
+    /// conn.autonomous(|block| {
+    ///     block.execute(
+    ///         "INSERT INTO audit_log (msg) VALUES (:1)",
+    ///         &[Value::String("payment rejected".to_string())],
+    ///     );
+    /// })
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn autonomous<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut AutonomousBlock),
+    {
+        autonomous::run(self, f).await
+    }
+
+    /// Run a blocking closure with the connection on a blocking thread
+    ///
+    /// For CPU-heavy processing of large results, which would otherwise
+    /// stall the other tasks of the Tokio worker. The closure runs with
+    /// `spawn_blocking` and can drive the driver's async calls with
+    /// `Handle::block_on()`. A panic in the closure is resumed here.
+    ///
+    /// If the returned future is dropped before the closure has finished,
+    /// the connection goes back to the pool once it has, and this `Object`
+    /// can no longer be used.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use tokio::runtime::Handle;
+    ///
+    /// let mut conn = pool.get().await?;
+    /// let checksum = conn
+    ///     .interact(|conn| {
+    ///         let query = conn.query("SELECT payload FROM events", &[]);
+    ///         let result = Handle::current().block_on(query)?;
+    ///         let sum = result
+    ///             .rows
+    ///             .iter()
+    ///             .filter_map(|row| row.get_string(0))
+    ///             .fold(0u64, |sum, payload| sum.wrapping_add(payload.len() as u64));
+    ///         Ok::<_, oracle_rs::Error>(sum)
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn interact<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Connection) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.settle_replacement();
+        let mut inner = self.inner.take().expect("connection already returned");
+        let task = task::spawn_blocking("oracle_pool.interact", move || {
+            let result = f(&mut inner);
+            (inner, result)
+        });
+        match task.await {
+            Ok((inner, result)) => {
+                self.inner = Some(inner);
+                result
+            }
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    /// Commit outstanding work when this connection is dropped
+    ///
+    /// By default work that was not committed is rolled back when the
+    /// connection is recycled. With this flag set, dropping the connection
+    /// spawns a task that commits first and then returns the connection to
+    /// the pool. Work of a dropped [`Transaction`] guard is still rolled back.
+    /// A failed commit emits `PoolEvent::CommitOnDropFailed`.
+    ///
+    /// The pool-wide default is set with `PoolBuilder::commit_on_drop`.
+    pub fn commit_on_drop(&mut self, enabled: bool) {
+        self.commit_on_drop = enabled;
+    }
+
+    /// Limit how long a single statement on this connection may run
+    ///
+    /// Applies to each round trip of the statements run through this object,
+    /// such as `execute()`, the `query*()` methods, `execute_many()` and
+    /// `call()`, including those of its transactions; every further fetch
+    /// of `query_all()` gets the full timeout again. Rows of
+    /// `cursor_rows()` and calls made on the underlying `Connection` are
+    /// not limited. A call running longer fails with ORA-03156 and the
+    /// connection is closed instead of being returned to the pool, as the
+    /// driver can't interrupt a call and leave the session usable. `None`
+    /// lets statements run as long as they take.
+    ///
+    /// The pool-wide default is set with `PoolBuilder::call_timeout`.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::Duration;
+    ///
+    /// let mut conn = pool.get().await?;
+    /// // This report may take longer than the pool allows
+    /// conn.call_timeout(Some(Duration::from_secs(300)));
+    /// let rows = conn.query_all("SELECT * FROM sales_summary", &[]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call_timeout(&mut self, timeout: Option<Duration>) {
+        self.call_timeout = timeout;
+    }
+
+    /// Run `statement`, reporting it to the query observer and audit sink
+    /// of the pool
+    ///
+    /// Statements of the checkout setup are the pool's, so they are neither
+    /// counted nor reported. If the first statement of a `Pool::get()`
+    /// checkout finds the connection dead, it runs again on a replacement.
+    pub(crate) async fn observe<'a, T, F, Fut>(
+        &'a self,
+        sql: &str,
+        binds: Binds<'_>,
+        statement: F,
+        rows_affected: impl FnOnce(&T) -> Option<u64>,
+    ) -> Result<T, Error>
+    where
+        F: Fn(&'a Connection) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        if self.setting_up.load(Ordering::Relaxed) {
+            return self.limit_call(statement(self.connection())).await;
+        }
+        let first = self.statements.load(Ordering::Relaxed) == 0;
+        self.count_statement();
+        let statement = async {
+            match self.limit_call(statement(self.connection())).await {
+                Err(e) if first && self.replace_dead(&e).await => {
+                    self.limit_call(statement(self.connection())).await
+                }
+                result => result,
+            }
+        };
+        let result = match self.query_observer.as_deref() {
+            Some(observer) => {
+                let observed = Observed {
+                    observer,
+                    connection_id: self.id(),
+                    sql,
+                    bind_count: binds.count(),
+                };
+                observed.run(statement, rows_affected).await
+            }
+            None => statement.await,
+        };
+        if let Some(sink) = self.audit_sink.as_deref() {
+            let identifier = self.app_info.identifier(self.id());
+            sink.record(&AuditEvent {
+                timestamp: std::time::SystemTime::now(),
+                connection_id: self.id(),
+                client_identifier: identifier.as_deref(),
+                sql,
+                binds: &binds.redacted(),
+                bind_count: binds.count(),
+                error: result.as_ref().err(),
+            });
+        }
+        result
+    }
+
+    /// Run `setup` as part of the checkout rather than as the caller's work
+    async fn setting_up<T>(&self, setup: impl Future<Output = T>) -> T {
+        /// Clears the flag even if the checkout is cancelled mid-setup
+        struct Reset<'a>(&'a AtomicBool);
+
+        impl Drop for Reset<'_> {
+            fn drop(&mut self) {
+                self.0.store(false, Ordering::Relaxed);
+            }
+        }
+
+        self.setting_up.store(true, Ordering::Relaxed);
+        let _reset = Reset(&self.setting_up);
+        setup.await
+    }
+
+    /// Await `statement` for at most the call timeout
+    ///
+    /// The abandoned call leaves the connection in the middle of a round
+    /// trip, so it is marked closed and discarded on return.
+    pub(crate) async fn limit_call<T>(
+        &self,
+        statement: impl Future<Output = Result<T, Error>>,
+    ) -> Result<T, Error> {
+        let Some(timeout) = self.call_timeout else {
+            return statement.await;
+        };
+        match clock::timeout(&*self.clock, timeout, statement).await {
+            Some(result) => result,
+            None => {
+                self.inner().mark_closed();
+
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    connection_id = self.id(),
+                    timeout = ?timeout,
+                    "call timed out, closing connection"
+                );
+
+                Err(Error::oracle(
+                    CALL_TIMEOUT_CODE,
+                    format!("call timed out after {:?}", timeout),
+                ))
+            }
+        }
+    }
+
+    /// Close a connection retired by `Pool::drain_and_refresh()` instead of
+    /// returning it to the pool
+    fn close_if_retired(&mut self) {
+        let Some(pool) = self.inner.as_ref().and_then(managed::Object::pool) else {
+            return;
+        };
+        if !pool.manager().is_retired(Object::metrics(self)) {
+            return;
+        }
+        // Without a runtime it is evicted on its next checkout instead
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let manager = pool.manager();
+        let conn = managed::Object::take(self.inner.take().expect("checked above"));
+        manager.record_eviction(&conn, EvictionReason::Retired);
+        let close = async move {
+            let _ = conn.close().await;
+        };
+        task::spawn_on("oracle_pool.close_retired", close, &runtime);
+    }
+
+    fn inner(&self) -> &managed::Object<OracleConnectionManager> {
+        match self.replacement.get() {
+            Some(replacement) => replacement,
+            None => self.inner.as_ref().expect("connection already returned"),
+        }
+    }
+
+    /// Swap in another connection for one found dead by the first statement
+    /// of the checkout, returning whether the statement can run again
+    ///
+    /// The dead connection is evicted at once and detached from the pool by
+    /// `settle_replacement()`. It still holds its slot until then, so the
+    /// replacement is only taken if one is available without waiting.
+    async fn replace_dead(&self, error: &Error) -> bool {
+        let Some(reason) = dead_connection_reason(error).filter(|_| self.reconnect) else {
+            return false;
+        };
+        if self.replacement.get().is_some() {
+            return false;
+        }
+        let Some(pool) = managed::Object::pool(self.inner()) else {
+            return false;
+        };
+        let pool = Pool { inner: pool };
+
+        // This checkout still holds its slot, so don't wait for another one
+        let timeouts = pool.no_wait_timeouts();
+        // Boxed, as the checkout may itself run statements through `observe()`
+        let checkout: Pin<Box<dyn Future<Output = Result<Object, PoolError>> + Send + '_>> =
+            Box::pin(pool.checkout(&timeouts));
+        let Ok(mut fresh) = checkout.await else {
+            // Left to the recycle, which evicts it
+            return false;
+        };
+
+        let dead = self.connection();
+        dead.mark_closed();
+        pool.manager().record_eviction(dead, reason);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            connection_id = dead.id(),
+            replacement = fresh.id(),
+            error = %error,
+            "retrying the first statement on another connection"
+        );
+
+        let replacement = fresh.inner.take().expect("connection already returned");
+        self.replacement.set(replacement).is_ok()
+    }
+
+    /// Detach the connection replaced by `replace_dead()`, if any
+    fn settle_replacement(&mut self) {
+        if let Some(replacement) = self.replacement.take() {
+            if let Some(dead) = self.inner.replace(replacement) {
+                drop(managed::Object::take(dead));
+            }
+        }
+    }
+
+    /// Count a round trip of the caller, see [`Pool::with_connection()`]
+    fn count_statement(&self) {
+        if !self.setting_up.load(Ordering::Relaxed) {
+            self.statements.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// The connection, for the pool's own use
+    ///
+    /// Unlike going through `Deref`, this doesn't count as a statement of the
+    /// caller.
+    pub(crate) fn connection(&self) -> &Connection {
+        self.inner()
+    }
+}
+
+impl Drop for Object {
+    fn drop(&mut self) {
+        self.settle_replacement();
+        let force_rollback = self
+            .hold_watch
+            .as_ref()
+            .is_some_and(HoldWatch::force_rollback);
+        let rollback_pending = self.rollback_pending.get_mut().unwrap().is_some();
+        let commit = !force_rollback && self.commit_on_drop && !rollback_pending;
+        if !force_rollback && !commit {
+            self.close_if_retired();
+        }
+        let Some(id) = self.inner.as_deref().map(Connection::id) else {
+            return;
+        };
+        // The borrower's identifier shouldn't show on the idle session
+        let identified = self.app_info.is_identified(id);
+        if !force_rollback && !commit && !identified {
+            return;
+        }
+        let inner = self.inner.take().expect("checked above");
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                connection_id = id,
+                "no Tokio runtime to finish the checkout on drop, the session is reset on recycle"
+            );
+            return;
+        };
+        let app_info = self.app_info.clone();
+        let clear_identifier = move |inner: managed::Object<OracleConnectionManager>| async move {
+            // On failure it is cleared on recycle instead
+            if identified && app_info::clear_identifier(&inner).await.is_ok() {
+                app_info.set_identifier(inner.id(), None);
+            }
+        };
+
+        if force_rollback {
+            // Release the locks now rather than when the connection is next handed out
+            let rollback = async move {
+                if let Err(_e) = inner.rollback().await {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(connection_id = inner.id(), error = %_e, "forced rollback failed");
+                }
+                clear_identifier(inner).await;
+            };
+            task::spawn_on("oracle_pool.forced_rollback", rollback, &runtime);
+            return;
+        }
+        if !commit {
+            let clear = clear_identifier(inner);
+            task::spawn_on("oracle_pool.clear_identifier", clear, &runtime);
+            return;
+        }
+
+        let commit = async move {
+            if let Err(e) = inner.commit().await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(connection_id = inner.id(), error = %e, "commit on drop failed");
+
+                if let Some(pool) = managed::Object::pool(&inner) {
+                    pool.manager().events.emit(PoolEvent::CommitOnDropFailed {
+                        connection_id: inner.id(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+            clear_identifier(inner).await;
+        };
+        task::spawn_on("oracle_pool.commit_on_drop", commit, &runtime);
+    }
+}
+
+/// What runs on the `Connection` directly is out of the pool's sight, so
+/// reaching it counts as a statement of the checkout
+impl Deref for Object {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.count_statement();
+        self.inner()
+    }
+}
+
+impl DerefMut for Object {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.count_statement();
+        self.settle_replacement();
+        self.inner.as_mut().expect("connection already returned")
+    }
+}
+
+impl AsRef<Connection> for Object {
+    fn as_ref(&self) -> &Connection {
+        self
+    }
+}
+
+/// Builder for creating connection pools with custom configuration
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use oracle_rs::Config;
+/// use deadpool_oracle::PoolBuilder;
+/// use std::time::Duration;
+///
+/// let config = Config::new("localhost", 1521, "FREEPDB1", "user", "password");
+/// let pool = PoolBuilder::new(config)
+///     .max_size(20)
+///     .wait_timeout(Some(Duration::from_secs(30)))
+///     .build()
+///     .expect("Failed to build pool");
+/// ```
+#[derive(Clone)]
+pub struct PoolBuilder {
+    config: Config,
+    failover_endpoints: Vec<Config>,
+    name: Option<String>,
+    max_size: usize,
+    wait_timeout: Option<Duration>,
+    create_timeout: Option<Duration>,
+    recycle_timeout: Option<Duration>,
+    slow_acquire_threshold: Option<Duration>,
+    leak_detection_threshold: Option<Duration>,
+    capture_leak_backtraces: bool,
+    session_init_sql: Vec<String>,
+    session_params: Vec<(String, String)>,
+    prepare_on_create: Vec<String>,
+    isolation_level: Option<IsolationLevel>,
+    result_cache_mode: Option<ResultCacheMode>,
+    nls: Option<NlsConfig>,
+    time_zone: Option<String>,
+    commit_on_drop: bool,
+    call_timeout: Option<Duration>,
+    max_transaction_duration: Option<Duration>,
+    rollback_long_transactions: bool,
+    transaction_retries: u32,
+    transaction_retry_backoff: Duration,
+    reconnect_retries: u32,
+    create_retries: u32,
+    create_retry_backoff: Duration,
+    circuit_breaker: Option<(u32, Duration)>,
+    recycle_failure_backoff: Option<(Duration, Duration)>,
+    dns_refresh_interval: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    deterministic: bool,
+    clock: Option<Arc<dyn Clock>>,
+    max_concurrent_creates: Option<usize>,
+    statement_cache_size: Option<usize>,
+    fetch_array_size: u32,
+    query_observer: Option<Arc<dyn QueryObserver>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    shards: Option<(Vec<Config>, Arc<dyn ShardResolver>)>,
+    readonly_credentials: Option<Arc<dyn CredentialProvider>>,
+    endpoint_weights: Option<Vec<u32>>,
+    drcp: Option<DrcpOptions>,
+    tls_ca_cert: Option<String>,
+    tls_server_name: Option<String>,
+    tls_client_cert: Option<(String, String)>,
+    tls_wallet: Option<(String, Option<String>)>,
+    credential_provider: Option<Arc<dyn CredentialProvider>>,
+    credential_refresh: Option<Duration>,
+    credential_expiry_margin: Duration,
+    expected_identity: Option<SessionIdentity>,
+    tag_fixup: Option<Arc<TagFixup>>,
+    session_context: Option<(Arc<SessionContextHook>, String)>,
+    #[cfg(feature = "tracing")]
+    context_from_span: bool,
+    #[cfg(feature = "testing")]
+    fault_injector: Option<testing::FaultInjector>,
+}
+
+impl PoolBuilder {
+    /// Create a new pool builder with the given connection configuration
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            failover_endpoints: Vec::new(),
+            name: None,
+            max_size: num_cpus() * 4,
+            wait_timeout: Some(Duration::from_secs(30)),
+            create_timeout: Some(Duration::from_secs(30)),
+            recycle_timeout: Some(Duration::from_secs(5)),
+            slow_acquire_threshold: None,
+            leak_detection_threshold: None,
+            capture_leak_backtraces: false,
+            session_init_sql: Vec::new(),
+            session_params: Vec::new(),
+            prepare_on_create: Vec::new(),
+            isolation_level: None,
+            result_cache_mode: None,
+            nls: None,
+            time_zone: None,
+            commit_on_drop: false,
+            call_timeout: None,
+            max_transaction_duration: None,
+            rollback_long_transactions: false,
+            transaction_retries: 0,
+            transaction_retry_backoff: Duration::from_millis(50),
+            reconnect_retries: 1,
+            create_retries: 0,
+            create_retry_backoff: Duration::from_millis(100),
+            circuit_breaker: None,
+            recycle_failure_backoff: None,
+            dns_refresh_interval: None,
+            idle_timeout: None,
+            deterministic: false,
+            clock: None,
+            max_concurrent_creates: None,
+            statement_cache_size: None,
+            fetch_array_size: DEFAULT_FETCH_ARRAY_SIZE,
+            query_observer: None,
+            audit_sink: None,
+            shards: None,
+            readonly_credentials: None,
+            endpoint_weights: None,
+            drcp: None,
+            tls_ca_cert: None,
+            tls_server_name: None,
+            tls_client_cert: None,
+            tls_wallet: None,
+            credential_provider: None,
+            credential_refresh: None,
+            credential_expiry_margin: Duration::from_secs(60),
+            expected_identity: None,
+            tag_fixup: None,
+            session_context: None,
+            #[cfg(feature = "tracing")]
+            context_from_span: false,
+            #[cfg(feature = "testing")]
+            fault_injector: None,
+        }
+    }
+
+    /// Apply settings suited to serverless functions such as AWS Lambda
+    ///
+    /// An instance handles one invocation at a time and may be frozen
+    /// between invocations for any length of time, so the pool keeps a
+    /// single connection (`max_size(1)`), closes it after 60 seconds
+    /// without use (`idle_timeout`), and fails fast on cold starts: 5
+    /// second wait and create timeouts, with 2 connection retries 100 ms
+    /// apart. Any setting can be overridden after this call.
+    ///
+    /// The pool can't tell when the instance is frozen: a connection that
+    /// expired during a freeze is replaced on the next checkout. Call
+    /// [`Pool::close_all_idle()`] at the end of an invocation to close the
+    /// session before the freeze instead.
+    pub fn serverless(self) -> Self {
+        self.max_size(1)
+            .idle_timeout(Some(Duration::from_secs(60)))
+            .wait_timeout(Some(Duration::from_secs(5)))
+            .create_timeout(Some(Duration::from_secs(5)))
+            .create_retries(2, Duration::from_millis(100))
+    }
+
+    /// Set the name identifying this pool in telemetry
+    ///
+    /// Default is `host:port/service`.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set standby endpoints to connect to when the primary one is down
+    ///
+    /// New connections go to the endpoint that last accepted one, starting
+    /// with the primary `Config`. When it refuses or cannot be reached, the
+    /// endpoints are tried in order, and the first one that accepts the
+    /// connection is used from then on, reported as
+    /// `PoolEvent::EndpointFailover`. Connections already in the pool are
+    /// not moved. The statement cache size of the primary applies to all
+    /// endpoints. Default is none.
+    pub fn failover_endpoints(mut self, endpoints: Vec<Config>) -> Self {
+        self.failover_endpoints = endpoints;
+        self
+    }
+
+    /// Spread new connections over all endpoints by weight
+    ///
+    /// Turns the failover endpoints into a load-balanced set, for example
+    /// the nodes of a RAC cluster: every new connection picks an endpoint by
+    /// smooth weighted round-robin, so an endpoint with weight 2 gets twice
+    /// the connections of one with weight 1. `weights` are given for the
+    /// primary `Config` first, then the failover endpoints in order;
+    /// missing weights count as 1, and endpoints with weight 0 are only
+    /// used when all others fail. An endpoint that refuses a connection is
+    /// left out for 10 seconds, and the others are tried in turn. Default
+    /// is none, sticking to one endpoint at a time.
+    ///
+    /// ```rust,no_run
+    /// # fn example(node_1: oracle_rs::Config, node_2: oracle_rs::Config, node_3: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::PoolBuilder;
+    ///
+    /// // node_3 is a smaller machine
+    /// let pool = PoolBuilder::new(node_1)
+    ///     .failover_endpoints(vec![node_2, node_3])
+    ///     .endpoint_weights(vec![2, 2, 1])
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn endpoint_weights(mut self, weights: Vec<u32>) -> Self {
+        self.endpoint_weights = Some(weights);
+        self
+    }
+
+    /// Keep a sub-pool per shard for `Pool::get_for_shard()`
+    ///
+    /// For Oracle Sharding: the pool itself connects to the shard catalog
+    /// given to `new()`, and each of `shards` gets a sub-pool with the same
+    /// settings, apart from failover endpoints. `resolver` maps sharding
+    /// keys to indexes into `shards`. With a name set, sub-pools are named
+    /// `<name>/shard-<index>`. Default is none.
+    ///
+    /// ```rust,no_run
+    /// # fn example(catalog: oracle_rs::Config, shards: Vec<oracle_rs::Config>) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::{HashShardResolver, PoolBuilder};
+    ///
+    /// let pool = PoolBuilder::new(catalog)
+    ///     .shards(shards, HashShardResolver)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shards(mut self, shards: Vec<Config>, resolver: impl ShardResolver) -> Self {
+        self.shards = Some((shards, Arc::new(resolver)));
+        self
+    }
+
+    /// Keep a sub-pool logging in with `credentials` for
+    /// `Pool::get_readonly()`
+    ///
+    /// For reporting paths: with a database user granted only the `SELECT`
+    /// privileges they need, an accidental write is refused by the database
+    /// instead of relying on every query being reviewed. The sub-pool has
+    /// the same settings and endpoints as this pool, apart from the
+    /// credentials, shards and expected identity, and logs in with
+    /// `credentials` the way it would with `credential_provider()`, so they
+    /// can be `Credentials` or come from a secret store. The user and roles
+    /// of `expected_identity()` describe this pool's user, not the
+    /// read-only one, so the sub-pool doesn't check them. With a name set,
+    /// it is named `<name>/readonly`. Default is none.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::{Credentials, PoolBuilder};
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .readonly_credentials(Credentials::new("reporting", "secret"))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn readonly_credentials(mut self, credentials: impl CredentialProvider) -> Self {
+        self.readonly_credentials = Some(Arc::new(credentials));
+        self
+    }
+
+    /// Connect to DRCP pooled servers instead of dedicated ones
+    ///
+    /// With Database Resident Connection Pooling, the database serves
+    /// sessions from a shared pool of server processes, so many client
+    /// pools can connect without a server process each. Sessions of the
+    /// same connection class can be handed between connections along with
+    /// their state; the purity says whether a connection accepts such a
+    /// session (`SessionPurity::Self_`) or wants a fresh one
+    /// (`SessionPurity::New`). The driver does not release pooled servers
+    /// between checkouts, so each pooled connection holds its server until
+    /// it is closed and the pool takes up to `max_size` pooled servers.
+    /// Default is dedicated servers.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::PoolBuilder;
+    /// use oracle_rs::{DrcpOptions, SessionPurity};
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .drcp(
+    ///         DrcpOptions::new()
+    ///             .with_connection_class("ORDERS_APP")
+    ///             .with_purity(SessionPurity::Self_),
+    ///     )
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn drcp(mut self, options: DrcpOptions) -> Self {
+        self.drcp = Some(options);
+        self
+    }
+
+    /// Trust the CA certificates in the PEM file at `path`
+    ///
+    /// For databases whose certificates are issued by a private CA, or are
+    /// self-signed, instead of one of the system roots. Connects every
+    /// endpoint, failover and shard endpoints included, with TLS; settings
+    /// of a `TlsConfig` an endpoint has already, like a wallet, are kept.
+    /// The file is read when a connection is created, so a missing or
+    /// malformed one fails each connection attempt. Default is the system
+    /// roots, or the certificates of the endpoint's wallet.
+    pub fn tls_ca_cert(mut self, path: impl Into<String>) -> Self {
+        self.tls_ca_cert = Some(path.into());
+        self
+    }
+
+    /// Verify the server certificate against `name` instead of the host
+    ///
+    /// For servers reached by an address or alias their certificate doesn't
+    /// name, such as a lab database by IP or a tunnel on `localhost`. Also
+    /// sent as the SNI name. The driver offers no way to skip host name
+    /// verification, or to restrict cipher suites, so this is the way to
+    /// connect to such a server. Connects every endpoint with TLS, like
+    /// [`tls_ca_cert()`](Self::tls_ca_cert). Default is the host of each
+    /// endpoint.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::PoolBuilder;
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .tls_ca_cert("/etc/oracle/lab-ca.pem")
+    ///     .tls_server_name("db.lab.internal")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tls_server_name(mut self, name: impl Into<String>) -> Self {
+        self.tls_server_name = Some(name.into());
+        self
+    }
 
-        // Verify connection still works
-        conn.ping().await.map_err(RecycleError::Backend)?;
+    /// Authenticate with a client certificate, for servers that require
+    /// mutual TLS
+    ///
+    /// `cert_path` is a PEM file with the certificate chain, `key_path` one
+    /// with its unencrypted private key. Takes precedence over the client
+    /// certificate of a wallet. Connects every endpoint with TLS, like
+    /// [`tls_ca_cert()`](Self::tls_ca_cert). Default is no client
+    /// certificate, unless an endpoint's wallet holds one.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::PoolBuilder;
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .tls_ca_cert("/etc/oracle/ca.pem")
+    ///     .tls_client_cert("/etc/oracle/orders-app.crt", "/etc/oracle/orders-app.key")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tls_client_cert(
+        mut self,
+        cert_path: impl Into<String>,
+        key_path: impl Into<String>,
+    ) -> Self {
+        self.tls_client_cert = Some((cert_path.into(), key_path.into()));
+        self
+    }
 
-        Ok(())
+    /// Take the certificates to trust and the client certificate from the
+    /// Oracle wallet in the directory `path`
+    ///
+    /// The wallet must hold an `ewallet.pem`; `password` decrypts its
+    /// private key if that is encrypted. Auto-login (`cwallet.sso`) and
+    /// PKCS#12 wallets can't be read by the driver. A wallet without a
+    /// client certificate only supplies the certificates to trust, which
+    /// [`tls_ca_cert()`](Self::tls_ca_cert) replaces if set. Connects every
+    /// endpoint with TLS. Default is no wallet, unless an endpoint has one.
+    pub fn tls_wallet(mut self, path: impl Into<String>, password: Option<&str>) -> Self {
+        self.tls_wallet = Some((path.into(), password.map(str::to_string)));
+        self
     }
-}
 
-/// Type alias for the connection pool
-pub type Pool = managed::Pool<OracleConnectionManager>;
+    /// Log in with credentials from `provider` instead of those of the config
+    ///
+    /// Keeps passwords out of configuration files: the user name and
+    /// password of the config are replaced by what the provider returns,
+    /// such as a secret read from HashiCorp Vault or AWS Secrets Manager
+    /// (see the `vault` and `aws-secrets-manager` features). The
+    /// credentials are cached; when a login is refused with ORA-01017 the
+    /// provider is asked again, as the secret may have been rotated, and
+    /// the login retried once if they changed. Connections already open
+    /// are not affected, unless the credentials they logged in with expire
+    /// (see [`credential_expiry_margin()`](Self::credential_expiry_margin)).
+    /// Provider failures fail the connection attempt. Default is none.
+    ///
+    /// ```rust,no_run
+    /// # fn example() -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::{CredentialProvider, Credentials, CredentialsFuture, PoolBuilder};
+    /// use oracle_rs::Config;
+    /// use std::time::Duration;
+    ///
+    /// struct Secrets;
+    ///
+    /// impl CredentialProvider for Secrets {
+    ///     fn credentials(&self) -> CredentialsFuture<'_> {
+    ///         Box::pin(async { Ok(Credentials::new("app", "read from a secret store")) })
+    ///     }
+    /// }
+    ///
+    /// let config = Config::new("db.example.com", 1521, "ORDERS", "", "");
+    /// let pool = PoolBuilder::new(config)
+    ///     .credential_provider(Secrets)
+    ///     .credential_refresh(Some(Duration::from_secs(300)))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn credential_provider(mut self, provider: impl CredentialProvider) -> Self {
+        self.credential_provider = Some(Arc::new(provider));
+        self
+    }
 
-/// Type alias for a pooled connection
-///
-/// This wraps a `Connection` and automatically returns it to the pool when dropped.
-pub type Object = managed::Object<OracleConnectionManager>;
+    /// Ask the credential provider again at a fixed interval
+    ///
+    /// A background task replaces the cached credentials, so new
+    /// connections use rotated secrets and dynamic credentials before they
+    /// expire, without waiting for a login to fail. A failed refresh keeps
+    /// the cached credentials and is logged when the `tracing` feature is
+    /// enabled. Runs only if the pool is built within a Tokio runtime, and
+    /// not in deterministic mode. Has no effect without
+    /// `credential_provider()`. Default is `None` (only on refused logins).
+    pub fn credential_refresh(mut self, interval: Option<Duration>) -> Self {
+        self.credential_refresh = interval;
+        self
+    }
 
-/// Builder for creating connection pools with custom configuration
-///
-/// # Example
-///
-/// ```rust,no_run
-/// use oracle_rs::Config;
-/// use deadpool_oracle::PoolBuilder;
-/// use std::time::Duration;
-///
-/// let config = Config::new("localhost", 1521, "FREEPDB1", "user", "password");
-/// let pool = PoolBuilder::new(config)
-///     .max_size(20)
-///     .wait_timeout(Some(Duration::from_secs(30)))
-///     .build()
-///     .expect("Failed to build pool");
-/// ```
-pub struct PoolBuilder {
-    config: Config,
-    max_size: usize,
-    wait_timeout: Option<Duration>,
-    create_timeout: Option<Duration>,
-    recycle_timeout: Option<Duration>,
-}
+    /// Replace expiring credentials this long before they expire
+    ///
+    /// For credentials returned with a lifetime, such as OCI or Azure
+    /// database tokens, a background task asks the provider for new ones
+    /// `margin` before the cached ones expire, or halfway through their
+    /// lifetime if that is sooner, independently of `credential_refresh()`.
+    /// The connections that logged in with the replaced credentials are
+    /// retired: each is closed when it is next returned or checked out, so
+    /// no session outlives its credentials by more than the checkout
+    /// holding it. Expired credentials are never used for a login, whether
+    /// or not the task runs; it doesn't in deterministic mode. Has no effect
+    /// without `credential_provider()`. Default is 60 seconds.
+    pub fn credential_expiry_margin(mut self, margin: Duration) -> Self {
+        self.credential_expiry_margin = margin;
+        self
+    }
 
-impl PoolBuilder {
-    /// Create a new pool builder with the given connection configuration
-    pub fn new(config: Config) -> Self {
-        Self {
-            config,
-            max_size: num_cpus() * 4,
-            wait_timeout: Some(Duration::from_secs(30)),
-            create_timeout: Some(Duration::from_secs(30)),
-            recycle_timeout: Some(Duration::from_secs(5)),
-        }
+    /// Check the user and roles of every session before handing it out
+    ///
+    /// A session keeps the user and roles it logged in with, so after a
+    /// role is revoked or the credentials are rotated to a different user,
+    /// sessions opened before still act with the old privileges. With an
+    /// expected identity, each connection is checked on checkout and when
+    /// it is created: one that is not logged in as the user of `identity`,
+    /// by default the one new connections log in as, or whose enabled roles
+    /// differ from the expected ones, is evicted with
+    /// `EvictionReason::IdentityMismatch`, and a new connection failing the
+    /// check fails the checkout. Costs a round trip per checkout, or two
+    /// with roles. The sub-pool of `readonly_credentials()` logs in as
+    /// another user and is not checked. Default is none.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::{PoolBuilder, SessionIdentity};
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .expected_identity(SessionIdentity::new().roles(["ORDERS_RW"]))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn expected_identity(mut self, identity: SessionIdentity) -> Self {
+        self.expected_identity = Some(identity);
+        self
     }
 
     /// Set the maximum number of connections in the pool
@@ -155,15 +4173,682 @@ impl PoolBuilder {
         self
     }
 
+    /// Report acquisitions that take longer than the given threshold
+    ///
+    /// Every `get()` exceeding the threshold emits a `PoolEvent::SlowAcquire`
+    /// with the current pool status, and a warning when the `tracing` feature
+    /// is enabled. Helps diagnosing pool exhaustion. Default is `None` (disabled).
+    pub fn slow_acquire_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.slow_acquire_threshold = threshold;
+        self
+    }
+
+    /// Report connections that are checked out for longer than the given threshold
+    ///
+    /// Each leaked checkout is reported once, as a `PoolEvent::ConnectionLeaked`
+    /// and a warning when the `tracing` feature is enabled. This helps finding
+    /// handlers that hold on to a connection by mistake. Default is `None` (disabled).
+    pub fn leak_detection_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.leak_detection_threshold = threshold;
+        self
+    }
+
+    /// Capture a backtrace on every checkout and include it in leak reports
+    ///
+    /// Only has an effect together with `leak_detection_threshold`. Capturing
+    /// backtraces is expensive, so this is meant for debugging. Default is `false`.
+    pub fn capture_leak_backtraces(mut self, capture: bool) -> Self {
+        self.capture_leak_backtraces = capture;
+        self
+    }
+
+    /// Set SQL statements to execute on every newly created connection
+    ///
+    /// Useful for `ALTER SESSION` statements such as `NLS_DATE_FORMAT` or
+    /// `CURRENT_SCHEMA`, so that every pooled session starts with the same
+    /// settings. Statements are executed in order. Default is none.
+    pub fn session_init_sql(mut self, statements: Vec<String>) -> Self {
+        self.session_init_sql = statements;
+        self
+    }
+
+    /// Set session parameters on every newly created connection
+    ///
+    /// Standardizes settings such as the optimizer mode across all pooled
+    /// sessions without touching application code. The parameters are set
+    /// with a single `ALTER SESSION` when a connection is created, after
+    /// the NLS settings and before the session init SQL. Names must be
+    /// plain identifiers, or connections fail to be created. Values that
+    /// are plain words or numbers, like `FIRST_ROWS_10` or `50`, are used
+    /// as they are, and anything else is quoted as a string. Default is
+    /// none.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::PoolBuilder;
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .session_params([
+    ///         ("optimizer_mode", "FIRST_ROWS_10"),
+    ///         ("optimizer_index_cost_adj", "50"),
+    ///     ])
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn session_params<I, K, V>(mut self, params: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let params = params.into_iter();
+        self.session_params = params.map(|(k, v)| (k.into(), v.into())).collect();
+        self
+    }
+
+    /// Set queries to parse and cache on every newly created connection
+    ///
+    /// Frequently used queries are otherwise parsed by the first borrower of
+    /// each new connection, which shows up as latency spikes after the pool
+    /// grows. Each query is executed once with all binds set to NULL and its
+    /// rows are discarded, so only queries are accepted. Failing queries are
+    /// skipped. Has no effect with a `statement_cache_size` of 0. Default is
+    /// none.
+    pub fn prepare_on_create(mut self, queries: &[&str]) -> Self {
+        self.prepare_on_create = queries.iter().map(|sql| sql.to_string()).collect();
+        self
+    }
+
+    /// Set the transaction isolation level of every pooled session
+    ///
+    /// Issues `ALTER SESSION SET ISOLATION_LEVEL` when a connection is
+    /// created and again on every recycle, so a borrower that changed it
+    /// does not affect the next one. Default is `None` (the server default,
+    /// READ COMMITTED).
+    pub fn isolation_level(mut self, level: IsolationLevel) -> Self {
+        self.isolation_level = Some(level);
+        self
+    }
+
+    /// Set the server result cache mode of every pooled session
+    ///
+    /// With `ResultCacheMode::Force`, the results of all queries are kept in
+    /// the result cache of the server, which pays off for read-mostly lookup
+    /// data. With the default mode, only queries with the `RESULT_CACHE`
+    /// hint are cached; see [`result_cache_hint()`]. The result cache must
+    /// be enabled on the server (`RESULT_CACHE_MAX_SIZE`). Default is `None`
+    /// (the server default, usually MANUAL).
+    pub fn result_cache_mode(mut self, mode: ResultCacheMode) -> Self {
+        self.result_cache_mode = Some(mode);
+        self
+    }
+
+    /// Set the NLS parameters of every pooled session
+    ///
+    /// Pins the date, timestamp and number formats and the language and
+    /// territory instead of relying on server defaults that may differ
+    /// between environments, so implicit conversions between text and
+    /// dates or numbers behave the same everywhere. Applied when a
+    /// connection is created and asserted again in the rollback round trip
+    /// of every recycle, so a borrower changing them doesn't affect the
+    /// next. Default is none (the server defaults).
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::{NlsConfig, PoolBuilder};
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .nls(NlsConfig::new().date_format("YYYY-MM-DD").numeric_characters(".,"))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nls(mut self, nls: NlsConfig) -> Self {
+        self.nls = Some(nls);
+        self
+    }
+
+    /// Set the time zone of every pooled session
+    ///
+    /// `TIMESTAMP WITH LOCAL TIME ZONE` values are converted to the session
+    /// time zone, as are `CURRENT_TIMESTAMP` and `LOCALTIMESTAMP`, so
+    /// without this the same row reads differently depending on which
+    /// application host created the session. Takes a region name such as
+    /// `"Europe/Oslo"` or an offset such as `"+01:00"`. Applied when a
+    /// connection is created and asserted again in the rollback round trip
+    /// of every recycle, like [`nls()`](Self::nls). Default is none (the
+    /// time zone the session gets at login).
+    pub fn time_zone(mut self, time_zone: impl Into<String>) -> Self {
+        self.time_zone = Some(time_zone.into());
+        self
+    }
+
+    /// Commit outstanding work when a connection is dropped
+    ///
+    /// Meant for short scripts that forget to commit: instead of losing the
+    /// work to the rollback on recycle, it is committed before the
+    /// connection goes back to the pool. Can be overridden per checkout with
+    /// `Object::commit_on_drop()`. Default is `false`.
+    pub fn commit_on_drop(mut self, enabled: bool) -> Self {
+        self.commit_on_drop = enabled;
+        self
+    }
+
+    /// Limit how long a single statement may run
+    ///
+    /// Keeps one runaway query from holding a pooled connection
+    /// indefinitely. A statement run through `Object` that takes longer
+    /// fails with ORA-03156 and its connection is closed, as it can't be
+    /// returned to the pool in the middle of a call. Can be overridden per
+    /// checkout with `Object::call_timeout()`. Default is `None` (no limit).
+    pub fn call_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.call_timeout = timeout;
+        self
+    }
+
+    /// Report transactions that stay open longer than the given duration
+    ///
+    /// Measured from `Object::begin()` until the transaction is committed or
+    /// rolled back. Each overlong transaction is reported once, as a
+    /// `PoolEvent::LongTransaction` and a warning when the `tracing` feature
+    /// is enabled. Long lock holders can starve the database, so this helps
+    /// finding them. Default is `None` (disabled).
+    pub fn max_transaction_duration(mut self, limit: Option<Duration>) -> Self {
+        self.max_transaction_duration = limit;
+        self
+    }
+
+    /// Roll back overlong transactions as soon as their connection is returned
+    ///
+    /// Uncommitted work is normally rolled back when the connection is next
+    /// handed out, which on a quiet pool can be much later. With this set, a
+    /// transaction exceeding `max_transaction_duration` is rolled back
+    /// immediately on return, even with commit-on-drop. Default is `false`.
+    pub fn rollback_long_transactions(mut self, enabled: bool) -> Self {
+        self.rollback_long_transactions = enabled;
+        self
+    }
+
+    /// Set how often `Pool::with_transaction()` retries on serialization failures
+    ///
+    /// ORA-08177 is raised for `SERIALIZABLE` transactions that conflict with
+    /// concurrent updates; retrying the whole transaction usually succeeds.
+    /// Default is 0 (no retries).
+    pub fn transaction_retries(mut self, retries: u32) -> Self {
+        self.transaction_retries = retries;
+        self
+    }
+
+    /// Set the delay before the first transaction retry
+    ///
+    /// The delay doubles on every further retry. Default is 50 milliseconds.
+    pub fn transaction_retry_backoff(mut self, backoff: Duration) -> Self {
+        self.transaction_retry_backoff = backoff;
+        self
+    }
+
+    /// Set how often `Pool::with_connection()` retries on another connection
+    ///
+    /// Applies when the first statement fails because the connection is
+    /// dead. A connection from `Pool::get()` also has its first statement
+    /// run once more on another connection in that case, unless this is 0.
+    /// Set to 0 to return such errors at once. Default is 1.
+    pub fn reconnect_retries(mut self, retries: u32) -> Self {
+        self.reconnect_retries = retries;
+        self
+    }
+
+    /// Retry failed connection attempts with exponential backoff
+    ///
+    /// Listener errors such as ORA-12514 while a service is registering, or
+    /// network timeouts, are retried up to `retries` times before `get()`
+    /// fails; the delay starts at `backoff` and doubles on every retry.
+    /// Invalid credentials and other permanent errors fail at once. All
+    /// attempts must fit into the `create_timeout`. Default is 0 (no
+    /// retries).
+    pub fn create_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.create_retries = retries;
+        self.create_retry_backoff = backoff;
+        self
+    }
+
+    /// Fail `get()` fast during database outages
+    ///
+    /// After `failure_threshold` consecutive failed connection attempts or
+    /// recycle health checks, `get()` returns an error at once instead of
+    /// queueing callers behind connection attempts that time out. A
+    /// background task tries to connect after every `cool_down` and lets
+    /// `get()` through again on the first success. Killed sessions, and
+    /// health checks whose statements the server refused, such as a bad
+    /// re-asserted session setting, do not count as failures. Default is
+    /// none.
+    pub fn circuit_breaker(mut self, failure_threshold: u32, cool_down: Duration) -> Self {
+        self.circuit_breaker = Some((failure_threshold, cool_down));
+        self
+    }
+
+    /// Back off creating connections while recycle health checks keep failing
+    ///
+    /// Every evicted connection is normally replaced on the next `get()`.
+    /// If the database keeps failing health checks, for example while it is
+    /// in restricted mode, this hammers the listener with connection
+    /// attempts. With a backoff, every failed health check or connection
+    /// attempt quarantines the pool: new connections wait `base` before
+    /// connecting, doubling with every further failure up to `max`, within
+    /// the `create_timeout`. Health checks that fail without losing the
+    /// connection don't quarantine the pool, and a successful one lifts the
+    /// quarantine. The remaining time is reported in
+    /// `PoolStats::quarantine`. Default is none.
+    pub fn recycle_failure_backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.recycle_failure_backoff = Some((base, max));
+        self
+    }
+
+    /// Follow DNS changes of the database host
+    ///
+    /// New connections always resolve the host name, but pooled ones stay
+    /// with the address they were opened to. With an interval, the host is
+    /// resolved again at most that often during creates and recycles, and
+    /// after every failed connection attempt. When its addresses change,
+    /// for example because a CNAME was flipped to a standby, all current
+    /// connections are retired as with `Pool::drain_and_refresh()` and
+    /// `PoolEvent::HostAddressChanged` is emitted. Only the primary `Config`
+    /// is watched. Default is none.
+    pub fn dns_refresh_interval(mut self, interval: Option<Duration>) -> Self {
+        self.dns_refresh_interval = interval;
+        self
+    }
+
+    /// Close connections that were not handed out for longer than `timeout`
+    ///
+    /// A background task closes them while they sit in the pool, so idle
+    /// periods don't hold sessions on the server, and a connection found
+    /// expired on checkout is replaced. Evictions are counted as
+    /// `EvictionReason::Idle`. Default is none.
+    pub fn idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Make the pool behave the same on every run, for tests
+    ///
+    /// Waiting callers are served strictly in arrival order and idle
+    /// connections are reused first in, first out. No background tasks are
+    /// started: there is no idle reaper, and an open circuit breaker is
+    /// probed by the first checkout after its cool-down instead of a timer.
+    /// Connections are never evicted for their age, so `idle_timeout` and
+    /// `dns_refresh_interval` are ignored. The pool adds no random jitter
+    /// in either mode. Leak detection and `max_transaction_duration` spawn
+    /// no timers either: a connection or transaction held past its limit is
+    /// reported, on the pool's clock, when it is returned or finished.
+    /// Default is false.
+    pub fn deterministic(mut self, enabled: bool) -> Self {
+        self.deterministic = enabled;
+        self
+    }
+
+    /// Read the time and sleep through `clock`
+    ///
+    /// The pool's own timing goes through the clock: connect and
+    /// transaction retry backoffs, quarantine, the circuit breaker
+    /// cool-down, DNS refreshes, idle expiry, the background tasks, the
+    /// `call_timeout`, leak detection, `max_transaction_duration`, the
+    /// timeout of `Pool::health_check()` and the checkout and connect times
+    /// it reports. Only the wait, create and recycle timeouts are enforced
+    /// by deadpool on Tokio timers. The default clock is Tokio's, so under
+    /// `tokio::time::pause()` tests advance every timer of the pool instead
+    /// of waiting for it. Default is the Tokio clock.
+    pub fn clock(mut self, clock: impl Clock) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Limit how many connections are established at the same time
+    ///
+    /// A cold pool hit by a traffic spike otherwise opens a connection for
+    /// every waiting caller at once, up to `max_size` simultaneous TCP and
+    /// TLS handshakes and logons, which can trip listener rate limits.
+    /// Attempts over the limit wait for a running one to finish, within the
+    /// `create_timeout`. Default is none (no limit).
+    pub fn max_concurrent_creates(mut self, limit: Option<usize>) -> Self {
+        self.max_concurrent_creates = limit;
+        self
+    }
+
+    /// Set how many parsed statements each connection caches
+    ///
+    /// Statements found in the cache skip the parse on the server, which
+    /// matters for hot queries. Each cached statement holds memory on the
+    /// client and an open cursor on the server, so memory-constrained
+    /// deployments may want a smaller cache. Set to 0 to disable it. Default
+    /// is the size configured in the `Config`, 20 unless changed there.
+    pub fn statement_cache_size(mut self, size: usize) -> Self {
+        self.statement_cache_size = Some(size);
+        self
+    }
+
+    /// Set how many rows are fetched per round trip
+    ///
+    /// Used by `Object::query_all()`, `query_as()`, `query_paged()` and
+    /// `CursorRows` once the first 100 rows, which the driver prefetches
+    /// with the query, are consumed. Larger values mean fewer round trips
+    /// for large result sets at the cost of memory per fetch. Can be
+    /// overridden per query with `Object::query_all_with_fetch_size()` and
+    /// `CursorRows::fetch_size()`. Default is 100.
+    pub fn fetch_array_size(mut self, rows: u32) -> Self {
+        self.fetch_array_size = rows.max(1);
+        self
+    }
+
+    /// Report every statement run on the pool's connections to `observer`
+    ///
+    /// The observer sees the SQL text, the number of binds but not their
+    /// values, the duration, the rows affected and any error, for
+    /// statements run through `Object` and `Transaction`. Statements run
+    /// directly on the driver's `Connection` are not seen. Default is none.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::{PoolBuilder, QueryEvent};
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .query_observer(|event: &QueryEvent<'_>| {
+    ///         println!("{:?} {}", event.duration, event.sql);
+    ///     })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_observer(mut self, observer: impl QueryObserver) -> Self {
+        self.query_observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Record every statement run on the pool's connections to `sink`
+    ///
+    /// The sink sees the SQL text, the type of each bind value but not the
+    /// value, the connection id, the client identifier of the checkout and
+    /// any error, for statements run through `Object` and `Transaction`,
+    /// including ones that fail. Statements run directly on the driver's
+    /// `Connection`, and those the pool runs itself, including the session
+    /// context hook and tag fixup, are not seen. Default is none.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::{AuditEvent, PoolBuilder};
+    /// use std::sync::mpsc;
+    ///
+    /// // Drained by a thread shipping the events to the SIEM
+    /// let (tx, rx) = mpsc::channel();
+    /// let tx = std::sync::Mutex::new(tx);
+    /// let pool = PoolBuilder::new(config)
+    ///     .audit_sink(move |event: &AuditEvent<'_>| {
+    ///         let line = format!(
+    ///             "conn={} user={:?} sql={:?} binds={:?} ok={}",
+    ///             event.connection_id,
+    ///             event.client_identifier,
+    ///             event.sql,
+    ///             event.binds,
+    ///             event.error.is_none()
+    ///         );
+    ///         let _ = tx.lock().unwrap().send(line);
+    ///     })
+    ///     .build()?;
+    /// # drop(rx);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn audit_sink(mut self, sink: impl AuditSink) -> Self {
+        self.audit_sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Set up session state when `Pool::get_tagged()` finds no connection
+    /// with the requested tag
+    ///
+    /// `fixup` gets the connection, the requested tag and the tag the
+    /// connection has, if any, and is expected to change the session to
+    /// match the requested tag, for example with `ALTER SESSION`. The
+    /// connection is tagged once it succeeds. If it fails, the error is
+    /// returned from `get_tagged()` and the connection is closed, since its
+    /// session may be half set up. Without a fixup, mismatched connections
+    /// are returned as they are. Default is none.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::PoolBuilder;
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .tag_fixup(|conn, tag, _current| {
+    ///         Box::pin(async move {
+    ///             let sql = match tag {
+    ///                 "de" => "ALTER SESSION SET NLS_LANGUAGE = GERMAN",
+    ///                 _ => "ALTER SESSION SET NLS_LANGUAGE = AMERICAN",
+    ///             };
+    ///             conn.execute(sql, &[]).await.map(drop)
+    ///         })
+    ///     })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tag_fixup<F>(mut self, fixup: F) -> Self
+    where
+        F: for<'c> Fn(&'c Object, &'c str, Option<&'c str>) -> TagFixupFuture<'c>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.tag_fixup = Some(Arc::new(fixup));
+        self
+    }
+
+    /// Set the application context of every connection checked out, and
+    /// clear it before the connection is reused
+    ///
+    /// For Virtual Private Database policies that filter rows by context
+    /// attributes such as the tenant or role of the end user. `hook` runs
+    /// on every connection handed out by `get()`, `get_tagged()` and the
+    /// helpers built on them, typically calling the trusted package of the
+    /// context namespace, which calls `DBMS_SESSION.SET_CONTEXT`. Values
+    /// that belong to the request are usually passed in a task-local. Its
+    /// statements are part of the checkout: they are not reported to the
+    /// query observer or audit sink. `clear_sql` runs when the connection
+    /// is recycled, before anyone else gets it, and must clear whatever the
+    /// hook set; a session it fails on is evicted. If the hook fails, the
+    /// error is returned from `get()` and the connection is closed, since
+    /// its session may be half set up. Default is none.
+    ///
+    /// ```rust,no_run
+    /// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+    /// use deadpool_oracle::PoolBuilder;
+    /// use oracle_rs::Value;
+    ///
+    /// tokio::task_local! {
+    ///     static TENANT_ID: i64;
+    /// }
+    ///
+    /// let pool = PoolBuilder::new(config)
+    ///     .session_context(
+    ///         |conn| {
+    ///             Box::pin(async move {
+    ///                 let tenant = Value::Integer(TENANT_ID.get());
+    ///                 let sql = "BEGIN tenant_ctx_pkg.set_tenant(:1); END;";
+    ///                 conn.execute(sql, &[tenant]).await.map(drop)
+    ///             })
+    ///         },
+    ///         "BEGIN tenant_ctx_pkg.clear; END;",
+    ///     )
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn session_context<F>(mut self, hook: F, clear_sql: impl Into<String>) -> Self
+    where
+        F: for<'c> Fn(&'c Object) -> SessionContextFuture<'c> + Send + Sync + 'static,
+    {
+        self.session_context = Some((Arc::new(hook), clear_sql.into()));
+        self
+    }
+
+    /// Label sessions with the tracing span they are checked out in
+    ///
+    /// `get()` and the helpers built on it set the module of the session to
+    /// the target of the caller's current span, usually its Rust module
+    /// path, and the action to the span's name, as with
+    /// `Object::set_context()`. Spans created by `#[tracing::instrument]` on
+    /// request handlers thus show up in `V$SESSION`, ASH and AWR. Sessions
+    /// checked out outside of an enabled span have both cleared. The round
+    /// trip is only made when they change, and a failure to set them is
+    /// logged without failing the checkout. Only available with the
+    /// `tracing` feature. Default is false.
+    #[cfg(feature = "tracing")]
+    pub fn context_from_span(mut self, enabled: bool) -> Self {
+        self.context_from_span = enabled;
+        self
+    }
+
+    /// Inject faults into this pool, to test how the application copes
+    ///
+    /// Connection attempts are delayed, health checks failed, connections
+    /// disconnected at checkout and idle ones killed as configured on
+    /// `injector`. Only available with the `testing` feature. Default is
+    /// none.
+    #[cfg(feature = "testing")]
+    pub fn fault_injector(mut self, injector: testing::FaultInjector) -> Self {
+        self.fault_injector = Some(injector);
+        self
+    }
+
     /// Build the connection pool
     ///
     /// This creates the pool but does not establish any connections.
     /// Connections are created lazily when first requested.
-    pub fn build(self) -> Result<Pool, BuildError> {
-        let manager = OracleConnectionManager::new(self.config);
+    pub fn build(mut self) -> Result<Pool, BuildError> {
+        // Taken first, so shards don't get a read-only sub-pool of their own
+        let readonly_credentials = self.readonly_credentials.take();
+        let shards = match self.shards.take() {
+            Some((configs, resolver)) => Some((self.build_shards(configs)?, resolver)),
+            None => None,
+        };
+        let readonly = match readonly_credentials {
+            Some(credentials) => Some(self.build_readonly(credentials)?),
+            None => None,
+        };
+
+        let mut manager = OracleConnectionManager::new(self.config)
+            .with_failover_endpoints(self.failover_endpoints)
+            .with_session_init_sql(self.session_init_sql)
+            .with_session_params(self.session_params)
+            .with_prepare_on_create(self.prepare_on_create)
+            .with_fetch_array_size(self.fetch_array_size)
+            .with_isolation_level(self.isolation_level)
+            .with_result_cache_mode(self.result_cache_mode)
+            .with_commit_on_drop(self.commit_on_drop)
+            .with_call_timeout(self.call_timeout)
+            .with_slow_acquire_threshold(self.slow_acquire_threshold)
+            .with_transaction_retries(self.transaction_retries, self.transaction_retry_backoff)
+            .with_reconnect_retries(self.reconnect_retries)
+            .with_create_retries(self.create_retries, self.create_retry_backoff);
+        if let Some(name) = self.name {
+            manager = manager.with_name(name);
+        }
+        if let Some(size) = self.statement_cache_size {
+            manager = manager.with_statement_cache_size(size);
+        }
+        if let Some(nls) = self.nls {
+            manager = manager.with_nls(nls);
+        }
+        if let Some(time_zone) = &self.time_zone {
+            manager = manager.with_time_zone(time_zone);
+        }
+        if let Some(observer) = self.query_observer {
+            manager = manager.with_query_observer(observer);
+        }
+        if let Some(sink) = self.audit_sink {
+            manager = manager.with_audit_sink(sink);
+        }
+        if let Some(clock) = self.clock {
+            manager = manager.with_clock(clock);
+        }
+        if let Some(limit) = self.max_transaction_duration {
+            manager = manager.with_max_transaction_duration(limit, self.rollback_long_transactions);
+        }
+        if let Some(threshold) = self.leak_detection_threshold {
+            manager = manager.with_leak_detection(threshold, self.capture_leak_backtraces);
+        }
+        if let Some((failure_threshold, cool_down)) = self.circuit_breaker {
+            manager = manager.with_circuit_breaker(failure_threshold, cool_down);
+        }
+        if let Some((base, max)) = self.recycle_failure_backoff {
+            manager = manager.with_recycle_failure_backoff(base, max);
+        }
+        if self.deterministic {
+            // Nothing expires with time
+            self.dns_refresh_interval = None;
+            self.idle_timeout = None;
+            self.credential_refresh = None;
+            manager = manager.with_deterministic(true);
+        }
+        if let Some(interval) = self.dns_refresh_interval {
+            manager = manager.with_dns_refresh(interval);
+        }
+        if let Some(timeout) = self.idle_timeout {
+            manager = manager.with_idle_timeout(timeout);
+        }
+        if let Some(limit) = self.max_concurrent_creates {
+            manager = manager.with_max_concurrent_creates(limit);
+        }
+        if let Some(weights) = self.endpoint_weights {
+            manager = manager.with_endpoint_weights(weights);
+        }
+        if let Some(options) = self.drcp {
+            manager = manager.with_drcp(options);
+        }
+        if let Some(path) = &self.tls_ca_cert {
+            manager = manager.with_tls_ca_cert(path);
+        }
+        if let Some(name) = &self.tls_server_name {
+            manager = manager.with_tls_server_name(name);
+        }
+        if let Some((cert_path, key_path)) = &self.tls_client_cert {
+            manager = manager.with_tls_client_cert(cert_path, key_path);
+        }
+        if let Some((path, password)) = self.tls_wallet.clone() {
+            manager = manager.with_tls_wallet(path, password);
+        }
+        if let Some(provider) = self.credential_provider.clone() {
+            manager = manager.with_credential_provider(provider);
+        }
+        if let Some(identity) = self.expected_identity {
+            manager = manager.with_expected_identity(identity);
+        }
+        if let Some(fixup) = self.tag_fixup {
+            manager = manager.with_tag_fixup(fixup);
+        }
+        if let Some((hook, clear_sql)) = self.session_context {
+            manager = manager.with_session_context(hook, clear_sql);
+        }
+        #[cfg(feature = "tracing")]
+        if self.context_from_span {
+            manager = manager.with_context_from_span(true);
+        }
+        #[cfg(feature = "testing")]
+        if let Some(injector) = self.fault_injector {
+            manager = manager.with_fault_injector(injector);
+        }
+        if let Some((pools, resolver)) = shards {
+            manager = manager.with_shards(pools, resolver);
+        }
+        if let Some(pool) = readonly {
+            manager = manager.with_readonly(pool);
+        }
 
+        // FIFO is deadpool's default, pinned as deterministic pools rely on it
         let builder = managed::Pool::builder(manager)
             .max_size(self.max_size)
+            .queue_mode(managed::QueueMode::Fifo)
             .runtime(deadpool::Runtime::Tokio1)
             .timeouts(managed::Timeouts {
                 wait: self.wait_timeout,
@@ -171,7 +4856,82 @@ impl PoolBuilder {
                 recycle: self.recycle_timeout,
             });
 
-        builder.build().map_err(BuildError)
+        let inner = builder.build().map_err(BuildError)?;
+
+        #[cfg(feature = "otel")]
+        inner.manager().otel.observe_pool(&inner);
+
+        let pool = Pool { inner };
+        if let Some(timeout) = self.idle_timeout {
+            pool.start_idle_reaper(timeout);
+        }
+        if pool.manager().credentials.is_some() && !self.deterministic {
+            pool.start_credential_refresh(self.credential_refresh, self.credential_expiry_margin);
+        }
+        #[cfg(feature = "testing")]
+        if let Some(injector) = pool.manager().fault_injector.clone() {
+            if let Some(interval) = injector.idle_kill_interval() {
+                if !self.deterministic {
+                    pool.start_chaos(injector, interval);
+                }
+            }
+        }
+        Ok(pool)
+    }
+
+    /// Build a sub-pool with these settings for every shard
+    fn build_shards(&self, configs: Vec<Config>) -> Result<Vec<Pool>, BuildError> {
+        let shards = configs.into_iter().enumerate().map(|(index, config)| {
+            let mut shard = self.clone();
+            shard.config = config;
+            shard.failover_endpoints = Vec::new();
+            if let Some(name) = &self.name {
+                shard.name = Some(format!("{}/shard-{}", name, index));
+            }
+            shard.build()
+        });
+        shards.collect()
+    }
+
+    /// Build a sub-pool with these settings logging in with `credentials`
+    fn build_readonly(&self, credentials: Arc<dyn CredentialProvider>) -> Result<Pool, BuildError> {
+        let mut readonly = self.clone();
+        readonly.credential_provider = Some(credentials);
+        // Describes the primary user, whom the read-only one never matches
+        readonly.expected_identity = None;
+        if let Some(name) = &self.name {
+            readonly.name = Some(format!("{}/readonly", name));
+        }
+        readonly.build()
+    }
+}
+
+impl std::fmt::Debug for PoolBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let failover: Vec<_> = self.failover_endpoints.iter().map(ConfigDebug).collect();
+        let wallet = self.tls_wallet.as_ref();
+        let wallet = wallet.map(|(path, password)| (path, password.as_ref().map(|_| REDACTED)));
+        f.debug_struct("PoolBuilder")
+            .field("config", &ConfigDebug(&self.config))
+            .field("failover_endpoints", &failover)
+            .field("name", &self.name)
+            .field("max_size", &self.max_size)
+            .field("wait_timeout", &self.wait_timeout)
+            .field("create_timeout", &self.create_timeout)
+            .field("recycle_timeout", &self.recycle_timeout)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("statement_cache_size", &self.statement_cache_size)
+            .field("drcp", &self.drcp)
+            .field("tls_ca_cert", &self.tls_ca_cert)
+            .field("tls_server_name", &self.tls_server_name)
+            .field("tls_client_cert", &self.tls_client_cert)
+            .field("tls_wallet", &wallet)
+            .field("credential_provider", &self.credential_provider.is_some())
+            .field("credential_refresh", &self.credential_refresh)
+            .field("credential_expiry_margin", &self.credential_expiry_margin)
+            .field("readonly_credentials", &self.readonly_credentials.is_some())
+            .field("deterministic", &self.deterministic)
+            .finish_non_exhaustive()
     }
 }
 
@@ -194,6 +4954,74 @@ impl std::error::Error for BuildError {
 /// Error that can occur when getting a connection from the pool
 pub type PoolError = managed::PoolError<Error>;
 
+/// Classification of [`PoolError`] for retry decisions
+///
+/// ```rust,no_run
+/// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::PoolError> {
+/// use deadpool_oracle::PoolErrorExt;
+///
+/// let conn = match pool.get().await {
+///     Ok(conn) => conn,
+///     Err(e) if e.is_retryable() => pool.get().await?,
+///     Err(e) => return Err(e),
+/// };
+/// # Ok(())
+/// # }
+/// ```
+pub trait PoolErrorExt {
+    /// Whether acquiring again may succeed
+    ///
+    /// True for timeouts, connection errors, listener errors that go away
+    /// once the database is up (ORA-12514, ORA-01033, ...) and killed
+    /// sessions. False for errors that repeat on every attempt, such as
+    /// invalid credentials or a closed pool.
+    fn is_retryable(&self) -> bool;
+
+    /// Whether waiting for, creating or recycling a connection timed out
+    fn is_timeout(&self) -> bool;
+
+    /// Whether the error was reported by the database or the driver
+    fn is_backend(&self) -> bool;
+
+    /// ORA error code of a backend error, e.g. 12514 for ORA-12514
+    fn ora_code(&self) -> Option<u32>;
+}
+
+impl PoolErrorExt for PoolError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            managed::PoolError::Timeout(_) => true,
+            managed::PoolError::Backend(e) => is_transient_connect_error(e) || is_session_killed(e),
+            _ => false,
+        }
+    }
+
+    fn is_timeout(&self) -> bool {
+        matches!(self, managed::PoolError::Timeout(_))
+    }
+
+    fn is_backend(&self) -> bool {
+        matches!(self, managed::PoolError::Backend(_))
+    }
+
+    fn ora_code(&self) -> Option<u32> {
+        let managed::PoolError::Backend(e) = self else {
+            return None;
+        };
+        error::ora_code(e)
+    }
+}
+
+/// Default pool name derived from the connection target
+fn default_pool_name(config: &Config) -> String {
+    let service = config
+        .service
+        .service_name()
+        .or_else(|| config.service.sid())
+        .unwrap_or_default();
+    format!("{}:{}/{}", config.host, config.port, service)
+}
+
 /// Helper to get CPU count for default pool size
 fn num_cpus() -> usize {
     std::thread::available_parallelism()
@@ -235,6 +5063,17 @@ mod tests {
         assert!(builder.recycle_timeout.is_some());
     }
 
+    #[test]
+    fn test_serverless_preset() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config).serverless().max_size(2);
+
+        assert_eq!(builder.max_size, 2);
+        assert_eq!(builder.idle_timeout, Some(Duration::from_secs(60)));
+        assert_eq!(builder.wait_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(builder.create_retries, 2);
+    }
+
     #[test]
     fn test_pool_builder_configuration() {
         let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
@@ -250,6 +5089,817 @@ mod tests {
         assert_eq!(builder.recycle_timeout, Some(Duration::from_secs(2)));
     }
 
+    #[test]
+    fn test_pool_builder_name() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let pool = PoolBuilder::new(config.clone()).build().unwrap();
+        assert_eq!(pool.name(), "localhost:1521/FREEPDB1");
+
+        let pool = PoolBuilder::new(config).name("orders").build().unwrap();
+
+        assert_eq!(pool.name(), "orders");
+    }
+
+    #[test]
+    fn test_pool_builder_statement_cache_size() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone())
+            .statement_cache_size
+            .is_none());
+        let pool = PoolBuilder::new(config.clone()).build().unwrap();
+        assert_eq!(pool.stats().statement_cache.capacity, config.stmtcachesize);
+
+        let pool = PoolBuilder::new(config)
+            .statement_cache_size(0)
+            .build()
+            .unwrap();
+        assert_eq!(pool.stats().statement_cache.capacity, 0);
+    }
+
+    #[test]
+    fn test_pool_builder_session_init_sql() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone()).session_init_sql.is_empty());
+        let builder = PoolBuilder::new(config).session_init_sql(vec![
+            "ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY-MM-DD'".to_string(),
+            "ALTER SESSION SET CURRENT_SCHEMA = HR".to_string(),
+        ]);
+
+        assert_eq!(builder.session_init_sql.len(), 2);
+        assert_eq!(
+            builder.session_init_sql[1],
+            "ALTER SESSION SET CURRENT_SCHEMA = HR"
+        );
+    }
+
+    #[test]
+    fn test_pool_builder_prepare_on_create() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone())
+            .prepare_on_create
+            .is_empty());
+        let builder = PoolBuilder::new(config)
+            .prepare_on_create(&["SELECT name FROM emp WHERE id = :1", "SELECT 1 FROM DUAL"]);
+
+        assert_eq!(builder.prepare_on_create.len(), 2);
+        assert_eq!(builder.prepare_on_create[1], "SELECT 1 FROM DUAL");
+    }
+
+    #[test]
+    fn test_pool_builder_isolation_level() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone()).isolation_level.is_none());
+        let builder = PoolBuilder::new(config).isolation_level(IsolationLevel::Serializable);
+
+        assert_eq!(builder.isolation_level, Some(IsolationLevel::Serializable));
+        assert_eq!(
+            IsolationLevel::Serializable.alter_session_sql(),
+            "ALTER SESSION SET ISOLATION_LEVEL = SERIALIZABLE"
+        );
+        assert_eq!(
+            transaction::rollback_block_sql(&[IsolationLevel::ReadCommitted.alter_session_sql()]),
+            "BEGIN ROLLBACK; \
+             EXECUTE IMMEDIATE 'ALTER SESSION SET ISOLATION_LEVEL = READ COMMITTED'; END;"
+        );
+    }
+
+    #[test]
+    fn test_pool_builder_result_cache_mode() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone()).result_cache_mode.is_none());
+        let builder = PoolBuilder::new(config).result_cache_mode(ResultCacheMode::Force);
+
+        assert_eq!(builder.result_cache_mode, Some(ResultCacheMode::Force));
+        assert_eq!(
+            ResultCacheMode::Force.alter_session_sql(),
+            "ALTER SESSION SET RESULT_CACHE_MODE = FORCE"
+        );
+    }
+
+    #[test]
+    fn test_pool_builder_nls() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone()).nls.is_none());
+        let nls = NlsConfig::new().date_format("YYYY-MM-DD");
+        let pool = PoolBuilder::new(config).nls(nls).build().unwrap();
+
+        let nls_sql = pool.manager().nls_sql.as_deref().unwrap();
+        assert_eq!(nls_sql, "ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY-MM-DD'");
+        // Quotes are doubled again inside the block
+        assert_eq!(
+            transaction::rollback_block_sql(&[nls_sql]),
+            "BEGIN ROLLBACK; \
+             EXECUTE IMMEDIATE 'ALTER SESSION SET NLS_DATE_FORMAT = ''YYYY-MM-DD'''; END;"
+        );
+    }
+
+    #[test]
+    fn test_pool_builder_time_zone() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone()).time_zone.is_none());
+        let pool = PoolBuilder::new(config)
+            .time_zone("Europe/Oslo")
+            .build()
+            .unwrap();
+
+        let time_zone_sql = pool.manager().time_zone_sql.as_deref().unwrap();
+        assert_eq!(time_zone_sql, "ALTER SESSION SET TIME_ZONE = 'Europe/Oslo'");
+    }
+
+    #[test]
+    fn test_pool_builder_slow_acquire_threshold() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone())
+            .slow_acquire_threshold
+            .is_none());
+
+        let builder =
+            PoolBuilder::new(config).slow_acquire_threshold(Some(Duration::from_millis(250)));
+        assert_eq!(
+            builder.slow_acquire_threshold,
+            Some(Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn test_pool_builder_fetch_array_size() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert_eq!(PoolBuilder::new(config.clone()).fetch_array_size, 100);
+
+        let builder = PoolBuilder::new(config.clone()).fetch_array_size(500);
+        assert_eq!(builder.fetch_array_size, 500);
+        // At least one row per round trip
+        let builder = PoolBuilder::new(config).fetch_array_size(0);
+        assert_eq!(builder.fetch_array_size, 1);
+    }
+
+    #[test]
+    fn test_pool_builder_failover_endpoints() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config.clone());
+        assert!(builder.failover_endpoints.is_empty());
+        assert!(builder.endpoint_weights.is_none());
+
+        let standby = Config::new("standby", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config)
+            .failover_endpoints(vec![standby])
+            .endpoint_weights(vec![3, 1]);
+        assert_eq!(builder.failover_endpoints.len(), 1);
+        assert_eq!(builder.endpoint_weights, Some(vec![3, 1]));
+    }
+
+    #[test]
+    fn test_pool_builder_retries() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config.clone());
+        assert_eq!(builder.transaction_retries, 0);
+        assert_eq!(builder.reconnect_retries, 1);
+        assert_eq!(builder.create_retries, 0);
+
+        let builder = PoolBuilder::new(config)
+            .transaction_retries(3)
+            .reconnect_retries(0)
+            .create_retries(2, Duration::from_millis(100));
+        assert_eq!(builder.transaction_retries, 3);
+        assert_eq!(builder.reconnect_retries, 0);
+        assert_eq!(builder.create_retries, 2);
+    }
+
+    #[test]
+    fn test_pool_builder_session_params() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone()).session_params.is_empty());
+
+        let builder = PoolBuilder::new(config).session_params([("NLS_SORT", "BINARY_CI")]);
+        assert_eq!(
+            builder.session_params,
+            vec![("NLS_SORT".to_string(), "BINARY_CI".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_pool_builder_circuit_breaker() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config.clone());
+        assert!(builder.circuit_breaker.is_none());
+        assert!(builder.recycle_failure_backoff.is_none());
+
+        let builder = PoolBuilder::new(config)
+            .circuit_breaker(5, Duration::from_secs(30))
+            .recycle_failure_backoff(Duration::from_secs(1), Duration::from_secs(60));
+        assert_eq!(builder.circuit_breaker, Some((5, Duration::from_secs(30))));
+        assert_eq!(
+            builder.recycle_failure_backoff,
+            Some((Duration::from_secs(1), Duration::from_secs(60)))
+        );
+    }
+
+    #[test]
+    fn test_pool_builder_connection_lifetime() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config.clone());
+        assert!(builder.idle_timeout.is_none());
+        assert!(builder.dns_refresh_interval.is_none());
+
+        let builder = PoolBuilder::new(config)
+            .idle_timeout(Some(Duration::from_secs(300)))
+            .dns_refresh_interval(Some(Duration::from_secs(60)));
+        assert_eq!(builder.idle_timeout, Some(Duration::from_secs(300)));
+        assert_eq!(builder.dns_refresh_interval, Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_pool_builder_max_concurrent_creates() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        assert!(PoolBuilder::new(config.clone())
+            .max_concurrent_creates
+            .is_none());
+
+        let builder = PoolBuilder::new(config).max_concurrent_creates(Some(2));
+        assert_eq!(builder.max_concurrent_creates, Some(2));
+    }
+
+    #[test]
+    fn test_pool_builder_deterministic() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config.clone());
+        assert!(!builder.deterministic);
+        assert!(builder.clock.is_none());
+
+        let builder = PoolBuilder::new(config)
+            .deterministic(true)
+            .clock(TokioClock);
+        assert!(builder.deterministic);
+        assert!(builder.clock.is_some());
+    }
+
+    #[test]
+    fn test_pool_builder_sub_pools() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config);
+        assert!(builder.shards.is_none());
+        assert!(builder.readonly_credentials.is_none());
+        assert!(builder.drcp.is_none());
+    }
+
+    #[test]
+    fn test_pool_builder_tls() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config.clone());
+        assert_eq!(builder.tls_ca_cert, None);
+        assert_eq!(builder.tls_server_name, None);
+        assert_eq!(builder.tls_client_cert, None);
+        assert_eq!(builder.tls_wallet, None);
+
+        let builder = PoolBuilder::new(config)
+            .tls_ca_cert("/etc/oracle/ca.pem")
+            .tls_server_name("db.example.com")
+            .tls_client_cert("/etc/oracle/client.pem", "/etc/oracle/client.key");
+        assert_eq!(builder.tls_ca_cert.as_deref(), Some("/etc/oracle/ca.pem"));
+        assert_eq!(builder.tls_server_name.as_deref(), Some("db.example.com"));
+        assert!(builder.tls_client_cert.is_some());
+    }
+
+    #[test]
+    fn test_pool_builder_credentials() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config.clone());
+        assert!(builder.credential_provider.is_none());
+        assert_eq!(builder.credential_refresh, None);
+        assert_eq!(builder.credential_expiry_margin, Duration::from_secs(60));
+        assert!(builder.expected_identity.is_none());
+
+        let builder = PoolBuilder::new(config)
+            .credential_refresh(Some(Duration::from_secs(900)))
+            .credential_expiry_margin(Duration::from_secs(120))
+            .expected_identity(SessionIdentity::new());
+        assert_eq!(builder.credential_refresh, Some(Duration::from_secs(900)));
+        assert_eq!(builder.credential_expiry_margin, Duration::from_secs(120));
+        assert!(builder.expected_identity.is_some());
+    }
+
+    #[test]
+    fn test_pool_builder_session_hooks() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config);
+        assert!(builder.tag_fixup.is_none());
+        assert!(builder.session_context.is_none());
+        assert!(builder.audit_sink.is_none());
+        #[cfg(feature = "tracing")]
+        assert!(!builder.context_from_span);
+        #[cfg(feature = "testing")]
+        assert!(builder.fault_injector.is_none());
+    }
+
+    #[test]
+    fn test_pool_builder_transactions() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let builder = PoolBuilder::new(config.clone());
+        assert!(!builder.commit_on_drop);
+        assert_eq!(builder.call_timeout, None);
+        assert!(builder.max_transaction_duration.is_none());
+
+        let builder = PoolBuilder::new(config)
+            .commit_on_drop(true)
+            .call_timeout(Some(Duration::from_secs(30)))
+            .max_transaction_duration(Some(Duration::from_secs(60)));
+        assert!(builder.commit_on_drop);
+        assert_eq!(builder.call_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(
+            builder.max_transaction_duration,
+            Some(Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_passwords() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "scott", "hunter2");
+        let failover = Config::new("standby", 1521, "FREEPDB1", "scott", "tiger2");
+        let builder = PoolBuilder::new(config)
+            .failover_endpoints(vec![failover])
+            .tls_wallet("/etc/oracle/wallet", Some("w4llet"));
+
+        let debug = format!("{:?}", builder);
+        assert!(debug.contains("scott"));
+        assert!(debug.contains("standby"));
+        assert!(debug.contains("/etc/oracle/wallet"));
+        for secret in ["hunter2", "tiger2", "w4llet"] {
+            assert!(!debug.contains(secret), "{} in {}", secret, debug);
+        }
+
+        let pool = builder.build().unwrap();
+        let debug = format!("{:?}", pool.manager());
+        assert!(debug.contains("scott"));
+        for secret in ["hunter2", "tiger2", "w4llet"] {
+            assert!(!debug.contains(secret), "{} in {}", secret, debug);
+        }
+    }
+
+    #[test]
+    fn test_is_session_killed() {
+        assert!(is_session_killed(&Error::oracle(28, "session killed")));
+        assert!(is_session_killed(&Error::oracle(2396, "idle timeout")));
+        assert!(!is_session_killed(&Error::oracle(942, "no such table")));
+        assert!(!is_session_killed(&Error::ConnectionClosed));
+    }
+
+    #[test]
+    fn test_dead_connection_reason() {
+        assert_eq!(
+            dead_connection_reason(&Error::oracle(28, "session killed")),
+            Some(EvictionReason::SessionKilled)
+        );
+        assert_eq!(
+            dead_connection_reason(&Error::ConnectionClosed),
+            Some(EvictionReason::Closed)
+        );
+        assert!(dead_connection_reason(&Error::oracle(942, "no such table")).is_none());
+    }
+
+    #[test]
+    fn test_is_serialization_failure() {
+        assert!(is_serialization_failure(&Error::oracle(8177, "serialize")));
+        assert!(!is_serialization_failure(&Error::oracle(28, "killed")));
+        assert!(!is_serialization_failure(&Error::ConnectionClosed));
+    }
+
+    #[test]
+    fn test_is_transient_connect_error() {
+        assert!(is_transient_connect_error(&Error::oracle(
+            12514,
+            "unknown service"
+        )));
+        assert!(is_transient_connect_error(&Error::ConnectionTimeout(
+            Duration::from_secs(5)
+        )));
+        assert!(is_transient_connect_error(&Error::InvalidServiceName {
+            service_name: Some("FREEPDB1".to_string()),
+            message: None,
+        }));
+        assert!(!is_transient_connect_error(&Error::InvalidCredentials));
+        assert!(!is_transient_connect_error(&Error::oracle(
+            1017,
+            "invalid password"
+        )));
+    }
+
+    #[test]
+    fn test_is_invalid_credentials() {
+        assert!(is_invalid_credentials(&Error::InvalidCredentials));
+        assert!(is_invalid_credentials(&Error::oracle(
+            1017,
+            "invalid password"
+        )));
+        assert!(!is_invalid_credentials(&Error::oracle(
+            28000,
+            "account locked"
+        )));
+        assert!(!is_invalid_credentials(&Error::ConnectionClosed));
+    }
+
+    #[tokio::test]
+    async fn test_credential_refresh() {
+        struct Counting(Arc<AtomicUsize>);
+
+        impl CredentialProvider for Counting {
+            fn credentials(&self) -> CredentialsFuture<'_> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Ok(Credentials::new("app", "secret")) })
+            }
+        }
+
+        tokio::time::pause();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = Config::new("localhost", 1521, "FREEPDB1", "", "");
+        let pool = PoolBuilder::new(config)
+            .credential_provider(Counting(calls.clone()))
+            .credential_refresh(Some(Duration::from_secs(60)))
+            .build()
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_secs(150)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // The task stops with the pool
+        pool.close();
+        tokio::time::sleep(Duration::from_secs(120)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_credential_expiry_refresh() {
+        struct Tokens(Arc<AtomicUsize>);
+
+        impl CredentialProvider for Tokens {
+            fn credentials(&self) -> CredentialsFuture<'_> {
+                let calls = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+                let token = Credentials::new("app", format!("token-{}", calls))
+                    .with_lifetime(Duration::from_secs(600));
+                Box::pin(async move { Ok(token) })
+            }
+        }
+
+        tokio::time::pause();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = Config::new("localhost", 1521, "FREEPDB1", "", "");
+        let pool = PoolBuilder::new(config)
+            .credential_provider(Tokens(calls.clone()))
+            .build()
+            .unwrap();
+        let manager = pool.manager();
+        let cache = manager.credentials.as_ref().unwrap();
+        cache.get(manager.clock.now()).await.unwrap();
+
+        // Replaced 60 seconds before the token expires
+        tokio::time::sleep(Duration::from_secs(530)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(manager.retired_before.lock().unwrap().is_none());
+        tokio::time::sleep(Duration::from_secs(20)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(manager.retired_before.lock().unwrap().is_some());
+
+        let credentials = cache.get(manager.clock.now()).await.unwrap();
+        assert_eq!(credentials.password(), "token-2");
+        pool.close();
+    }
+
+    #[tokio::test]
+    async fn test_failover_endpoints() {
+        // Accepts a connection and closes it without answering
+        let standby = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = standby.local_addr().unwrap().port();
+        let accepted = std::thread::spawn(move || standby.accept().is_ok());
+
+        let primary = Config::new("127.0.0.1", 1, "FREEPDB1", "test", "test");
+        let standby_config = Config::new("127.0.0.1", port, "FREEPDB1", "test", "test");
+        let manager =
+            OracleConnectionManager::new(primary).with_failover_endpoints(vec![standby_config]);
+
+        // The primary refuses, so the standby is tried next
+        assert!(manager.create().await.is_err());
+        assert!(accepted.join().unwrap());
+        assert_eq!(manager.active_endpoint.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_recycle_failure_backoff() {
+        let config = Config::new("127.0.0.1", 1, "FREEPDB1", "test", "test");
+        let pool = PoolBuilder::new(config)
+            .recycle_failure_backoff(Duration::from_secs(60), Duration::from_secs(600))
+            .build()
+            .unwrap();
+        assert_eq!(pool.stats().quarantine, None);
+
+        assert!(pool.get().await.is_err());
+        let quarantine = pool.stats().quarantine.expect("pool should be quarantined");
+        assert!(quarantine > Duration::from_secs(59));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test(start_paused = true)]
+    async fn test_fault_injector_delays_creates() {
+        let config = Config::new("127.0.0.1", 1, "FREEPDB1", "test", "test");
+        let faults = testing::FaultInjector::new().create_latency(1.0, Duration::from_secs(3));
+        let pool = PoolBuilder::new(config)
+            .fault_injector(faults.clone())
+            .build()
+            .unwrap();
+
+        let start = tokio::time::Instant::now();
+        assert!(pool.get().await.is_err());
+        assert!(start.elapsed() >= Duration::from_secs(3));
+        assert_eq!(faults.creates_delayed(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_creates() {
+        // Accepts connections without ever answering, so attempts hang
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let config = Config::new("127.0.0.1", port, "FREEPDB1", "test", "test");
+        let manager = Arc::new(OracleConnectionManager::new(config).with_max_concurrent_creates(1));
+
+        for _ in 0..2 {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.create().await });
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        listener.set_nonblocking(true).unwrap();
+        let accepted = std::iter::from_fn(|| listener.accept().ok()).count();
+        assert_eq!(accepted, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_for_shard() {
+        // Nothing listens on port 1, so connecting is refused at once
+        let catalog = Config::new("127.0.0.1", 1, "CATALOG", "test", "test");
+        let shards = vec![
+            Config::new("127.0.0.1", 1, "SHARD1", "test", "test"),
+            Config::new("127.0.0.1", 1, "SHARD2", "test", "test"),
+        ];
+        let by_region = |key: &Value, _shards: usize| match key {
+            Value::String(region) if region == "EU" => Some(0),
+            Value::String(region) if region == "US" => Some(1),
+            _ => None,
+        };
+        let pool = PoolBuilder::new(catalog)
+            .name("orders")
+            .shards(shards, by_region)
+            .build()
+            .unwrap();
+        assert_eq!(pool.shards().len(), 2);
+        assert_eq!(pool.shards()[1].name(), "orders/shard-1");
+
+        assert!(pool.get_for_shard("US").await.is_err());
+        assert_eq!(pool.shards()[1].stats().create_failures, 1);
+        assert_eq!(pool.shards()[0].stats().create_failures, 0);
+        assert_eq!(pool.stats().create_failures, 0);
+
+        let Err(err) = pool.get_for_shard("APAC").await else {
+            panic!("resolved a key no shard holds");
+        };
+        assert!(err.to_string().contains("no shard holds"), "{}", err);
+
+        pool.close();
+        assert!(pool.shards().iter().all(Pool::is_closed));
+    }
+
+    #[tokio::test]
+    async fn test_get_readonly() {
+        // Nothing listens on port 1, so connecting is refused at once
+        let config = Config::new("127.0.0.1", 1, "ORDERS", "app", "secret");
+        let pool = PoolBuilder::new(config.clone()).build().unwrap();
+        assert!(pool.readonly().is_none());
+        let Err(err) = pool.get_readonly().await else {
+            panic!("handed out a connection without read-only credentials");
+        };
+        assert!(err.to_string().contains("no read-only"), "{}", err);
+
+        let pool = PoolBuilder::new(config)
+            .name("orders")
+            .readonly_credentials(Credentials::new("reporting", "secret"))
+            .build()
+            .unwrap();
+        let readonly = pool.readonly().unwrap();
+        assert_eq!(readonly.name(), "orders/readonly");
+        assert!(readonly.readonly().is_none());
+
+        assert!(pool.get_readonly().await.is_err());
+        assert_eq!(readonly.stats().create_failures, 1);
+        assert_eq!(pool.stats().create_failures, 0);
+
+        pool.close();
+        assert!(readonly.is_closed());
+    }
+
+    #[test]
+    fn test_readonly_without_expected_identity() {
+        let config = Config::new("127.0.0.1", 1, "ORDERS", "app", "secret");
+        let pool = PoolBuilder::new(config)
+            .expected_identity(SessionIdentity::new().user("app").roles(["ORDERS_RW"]))
+            .readonly_credentials(Credentials::new("reporting", "secret"))
+            .build()
+            .unwrap();
+        assert!(pool.manager().identity.is_some());
+        assert!(pool.readonly().unwrap().manager().identity.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast() {
+        // Nothing listens on port 1, so connecting is refused at once
+        let config = Config::new("127.0.0.1", 1, "FREEPDB1", "test", "test");
+        let pool = PoolBuilder::new(config)
+            .circuit_breaker(2, Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let mut events = pool.subscribe_events();
+
+        assert!(pool.get().await.is_err());
+        assert!(!pool.is_circuit_open());
+        assert!(pool.get().await.is_err());
+        assert!(pool.is_circuit_open());
+
+        let Err(err) = pool.get().await else {
+            panic!("circuit breaker let get() through");
+        };
+        let err = err.to_string();
+        assert!(err.contains("circuit breaker open"), "{}", err);
+        assert_eq!(pool.stats().create_failures, 2);
+
+        let opened = std::iter::from_fn(|| events.try_recv().ok())
+            .any(|event| matches!(event, PoolEvent::CircuitOpened { failures: 2, .. }));
+        assert!(opened);
+    }
+
+    #[test]
+    fn test_new_pool_metrics() {
+        let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
+        let pool = PoolBuilder::new(config).build().unwrap();
+
+        // Nothing recycled yet
+        let metrics = pool.recycle_metrics();
+        assert_eq!(metrics.recycles(), 0);
+        assert_eq!(metrics.evictions(), 0);
+
+        let stats = pool.stats();
+        assert_eq!(stats.acquisitions(), 0);
+        assert_eq!(stats.connections_created, 0);
+        assert_eq!(stats.queue_depth(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_status_snapshot() {
+        let config = Config::new("127.0.0.1", 1, "FREEPDB1", "scott", "tiger");
+        let pool = PoolBuilder::new(config)
+            .name("orders")
+            .max_size(3)
+            .idle_timeout(Some(Duration::from_secs(60)))
+            .build()
+            .unwrap();
+        assert!(pool.get().await.is_err());
+
+        let snapshot = pool.status_snapshot();
+        assert_eq!(snapshot.name, "orders");
+        assert_eq!(snapshot.size, 0);
+        assert_eq!(snapshot.max_size, 3);
+        assert_eq!(snapshot.create_failures, 1);
+        assert_eq!(snapshot.acquire_failures, 1);
+        assert_eq!(snapshot.config.service, "FREEPDB1");
+        assert_eq!(snapshot.config.username, "scott");
+        assert_eq!(snapshot.config.wait_timeout_ms, Some(30_000));
+        assert_eq!(snapshot.config.idle_timeout_ms, Some(60_000));
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_value(&snapshot).unwrap();
+            assert_eq!(json["config"]["host"], "127.0.0.1");
+            assert!(!json.to_string().contains("tiger"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_pool() {
+        let config = Config::new("127.0.0.1", 1, "FREEPDB1", "test", "test");
+        let pool = PoolBuilder::new(config)
+            .circuit_breaker(1, Duration::from_millis(50))
+            .idle_timeout(Some(Duration::from_secs(1)))
+            .dns_refresh_interval(Some(Duration::from_secs(1)))
+            .deterministic(true)
+            .build()
+            .unwrap();
+        let manager = pool.manager();
+        assert!(manager.deterministic);
+        assert!(manager.idle_timeout.is_none());
+        assert!(manager.dns_watch.is_none());
+
+        // The circuit is probed by the first checkout after the cool-down
+        assert!(pool.get().await.is_err());
+        assert!(pool.is_circuit_open());
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let Err(err) = pool.get().await else {
+            panic!("connected to port 1");
+        };
+        assert!(!err.to_string().contains("circuit breaker open"), "{}", err);
+        let Err(err) = pool.get().await else {
+            panic!("circuit breaker let get() through");
+        };
+        assert!(err.to_string().contains("circuit breaker open"), "{}", err);
+        assert_eq!(pool.stats().create_failures, 2);
+    }
+
+    /// A clock that only moves when told to, or when slept on
+    #[derive(Clone)]
+    struct ManualClock(Arc<Mutex<Instant>>);
+
+    impl ManualClock {
+        fn advance(&self, duration: Duration) {
+            *self.0.lock().unwrap() += duration;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+
+        fn sleep(&self, duration: Duration) -> ClockSleep {
+            self.advance(duration);
+            Box::pin(std::future::ready(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_clock() {
+        let clock = ManualClock(Arc::new(Mutex::new(Instant::now())));
+        let config = Config::new("127.0.0.1", 1, "FREEPDB1", "test", "test");
+        let pool = PoolBuilder::new(config)
+            .circuit_breaker(1, Duration::from_secs(60))
+            .deterministic(true)
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+
+        assert!(pool.get().await.is_err());
+        assert!(pool.is_circuit_open());
+        let Err(err) = pool.get().await else {
+            panic!("circuit breaker let get() through");
+        };
+        assert!(err.to_string().contains("circuit breaker open"), "{}", err);
+
+        // The cool-down is over on the pool's clock, not on the wall clock
+        clock.advance(Duration::from_secs(61));
+        let Err(err) = pool.get().await else {
+            panic!("connected to port 1");
+        };
+        assert!(!err.to_string().contains("circuit breaker open"), "{}", err);
+        assert_eq!(pool.stats().create_failures, 2);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_timeout_on_clock() {
+        // Accepts connections but never answers the logon
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let clock = ManualClock(Arc::new(Mutex::new(Instant::now())));
+        let config = Config::new("127.0.0.1", port, "FREEPDB1", "test", "test");
+        let pool = PoolBuilder::new(config)
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+
+        // Times out on the pool's clock at once, not after an hour
+        let report = pool.health_check(Duration::from_secs(3600)).await;
+        assert!(!report.healthy);
+        let error = report.error.unwrap();
+        assert!(error.contains("timed out"), "{}", error);
+        assert_eq!(report.latency, Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_pool_error_ext() {
+        let timeout = PoolError::Timeout(managed::TimeoutType::Wait);
+        assert!(timeout.is_retryable());
+        assert!(timeout.is_timeout());
+        assert!(!timeout.is_backend());
+        assert_eq!(timeout.ora_code(), None);
+
+        let listener = PoolError::Backend(Error::oracle(12514, "unknown service"));
+        assert!(listener.is_retryable());
+        assert!(listener.is_backend());
+        assert_eq!(listener.ora_code(), Some(12514));
+
+        let killed = PoolError::Backend(Error::oracle(28, "session killed"));
+        assert!(killed.is_retryable());
+
+        let credentials = PoolError::Backend(Error::InvalidCredentials);
+        assert!(!credentials.is_retryable());
+        assert_eq!(credentials.ora_code(), Some(1017));
+
+        assert!(!PoolError::Closed.is_retryable());
+        assert!(!PoolError::Closed.is_backend());
+    }
+
     #[test]
     fn test_pool_build_lazy() {
         let config = Config::new("localhost", 1521, "FREEPDB1", "test", "test");
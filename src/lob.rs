@@ -0,0 +1,251 @@
+//! LOB streaming
+//!
+//! [`LobReader`] and [`LobWriter`] adapt a LOB locator to tokio's
+//! `AsyncRead` and `AsyncWrite`, moving the data in chunks of
+//! [`CHUNK_SIZE`] so a large LOB never has to be held in memory. Offsets are
+//! 1-based and count bytes for BLOBs and characters for CLOBs; CLOB data is
+//! read and written as UTF-8.
+
+use oracle_rs::{Connection, Error, LobData, LobLocator};
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Bytes (BLOB) or characters (CLOB) moved per round trip
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+type LobFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+fn io_error(error: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
+
+/// Streaming reader for a CLOB or BLOB
+///
+/// Created with [`Object::lob_reader()`](crate::Object::lob_reader).
+pub struct LobReader<'a> {
+    conn: &'a Connection,
+    locator: LobLocator,
+    offset: u64,
+    buffer: Vec<u8>,
+    position: usize,
+    pending: Option<LobFuture<'a, LobData>>,
+}
+
+impl<'a> LobReader<'a> {
+    pub(crate) fn new(conn: &'a Connection, locator: LobLocator) -> Self {
+        Self {
+            conn,
+            locator,
+            offset: 1,
+            buffer: Vec::new(),
+            position: 0,
+            pending: None,
+        }
+    }
+}
+
+impl AsyncRead for LobReader<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        loop {
+            if this.position < this.buffer.len() {
+                let n = buf.remaining().min(this.buffer.len() - this.position);
+                buf.put_slice(&this.buffer[this.position..this.position + n]);
+                this.position += n;
+                return Poll::Ready(Ok(()));
+            }
+
+            let size = this.locator.size();
+            if this.pending.is_none() {
+                if this.offset > size {
+                    return Poll::Ready(Ok(()));
+                }
+                let (conn, locator, offset) = (this.conn, this.locator.clone(), this.offset);
+                let amount = CHUNK_SIZE.min(size - offset + 1);
+                this.pending = Some(Box::pin(async move {
+                    conn.read_lob_range(&locator, offset, amount).await
+                }));
+            }
+
+            let pending = this.pending.as_mut().expect("read in progress");
+            let result = ready!(pending.as_mut().poll(cx));
+            this.pending = None;
+
+            let (data, read) = match result.map_err(io_error)? {
+                LobData::Bytes(bytes) => {
+                    let read = bytes.len() as u64;
+                    (bytes.to_vec(), read)
+                }
+                LobData::String(text) => {
+                    let read = text.chars().count() as u64;
+                    (text.into_bytes(), read)
+                }
+            };
+            // A short LOB read means the LOB shrank; stop instead of looping
+            this.offset = if read == 0 {
+                size + 1
+            } else {
+                this.offset + read
+            };
+            this.buffer = data;
+            this.position = 0;
+        }
+    }
+}
+
+/// Streaming writer for a CLOB or BLOB
+///
+/// Created with [`Object::lob_writer()`](crate::Object::lob_writer). Data is
+/// written from the start of the LOB; call `shutdown()` to write what is
+/// still buffered.
+pub struct LobWriter<'a> {
+    conn: &'a Connection,
+    locator: LobLocator,
+    offset: u64,
+    buffer: Vec<u8>,
+    pending: Option<LobFuture<'a, u64>>,
+}
+
+impl<'a> LobWriter<'a> {
+    pub(crate) fn new(conn: &'a Connection, locator: LobLocator) -> Self {
+        Self {
+            conn,
+            locator,
+            offset: 1,
+            buffer: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Start writing the buffered data
+    ///
+    /// Returns `false` if there was nothing to write. Unless `all` is set, an
+    /// incomplete UTF-8 sequence at the end of CLOB data is kept back for the
+    /// next write.
+    fn start_write(&mut self, all: bool) -> io::Result<bool> {
+        let len = writable_len(&self.buffer, self.locator.is_clob())?;
+        if all && len < self.buffer.len() {
+            return Err(invalid_utf8());
+        }
+        if len == 0 {
+            return Ok(false);
+        }
+
+        let data: Vec<u8> = self.buffer.drain(..len).collect();
+        let written = if self.locator.is_clob() {
+            String::from_utf8_lossy(&data).chars().count() as u64
+        } else {
+            data.len() as u64
+        };
+        let (conn, locator, offset) = (self.conn, self.locator.clone(), self.offset);
+        self.pending = Some(Box::pin(async move {
+            conn.write_lob(&locator, offset, &data).await?;
+            Ok(written)
+        }));
+        Ok(true)
+    }
+
+    /// Wait for the write in progress, if any
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some(pending) = self.pending.as_mut() {
+            let written = ready!(pending.as_mut().poll(cx));
+            self.pending = None;
+            self.offset += written.map_err(io_error)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_write_all(&mut self, cx: &mut Context<'_>, all: bool) -> Poll<io::Result<()>> {
+        loop {
+            ready!(self.poll_pending(cx))?;
+            if !self.start_write(all)? {
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+}
+
+/// Length of the prefix of `data` that can be written
+///
+/// CLOB data is cut before an incomplete UTF-8 sequence at the end and
+/// rejected if it is not UTF-8 at all.
+fn writable_len(data: &[u8], clob: bool) -> io::Result<usize> {
+    if !clob {
+        return Ok(data.len());
+    }
+    match std::str::from_utf8(data) {
+        Ok(_) => Ok(data.len()),
+        Err(e) if e.error_len().is_none() => Ok(e.valid_up_to()),
+        Err(_) => Err(invalid_utf8()),
+    }
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "CLOB data is not valid UTF-8")
+}
+
+impl AsyncWrite for LobWriter<'_> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        ready!(this.poll_pending(cx))?;
+        if this.buffer.len() as u64 >= CHUNK_SIZE && this.start_write(false)? {
+            ready!(this.poll_pending(cx))?;
+        }
+        this.buffer.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_write_all(cx, false)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_write_all(cx, true)
+    }
+}
+
+impl std::fmt::Debug for LobReader<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LobReader")
+            .field("size", &self.locator.size())
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for LobWriter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LobWriter")
+            .field("offset", &self.offset)
+            .field("buffered", &self.buffer.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writable_len_utf8() {
+        let data = "blåbær".as_bytes();
+        // Cut inside the two-byte 'æ'
+        let partial = &data[..data.len() - 2];
+
+        assert_eq!(writable_len(partial, false).unwrap(), partial.len());
+        assert_eq!(writable_len(partial, true).unwrap(), partial.len() - 1);
+        assert_eq!(writable_len(data, true).unwrap(), data.len());
+        assert!(writable_len(&[0xff, b'a'], true).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+//! Pool telemetry through the `metrics` crate facade
+//!
+//! Whatever recorder the application installs (Prometheus, statsd, OTLP, ...)
+//! receives these metrics. All of them carry a `pool` label with the pool name.
+
+use crate::EvictionReason;
+use deadpool::Status;
+use metrics::{counter, gauge, histogram};
+use std::time::Duration;
+
+pub(crate) fn record_create(pool: &str, success: bool, elapsed: Duration) {
+    if success {
+        counter!("oracle_pool_connections_created_total", "pool" => pool.to_string()).increment(1);
+        histogram!("oracle_pool_create_duration_seconds", "pool" => pool.to_string())
+            .record(elapsed.as_secs_f64());
+    } else {
+        counter!("oracle_pool_create_failures_total", "pool" => pool.to_string()).increment(1);
+    }
+}
+
+pub(crate) fn record_recycle(pool: &str, elapsed: Duration) {
+    histogram!("oracle_pool_recycle_duration_seconds", "pool" => pool.to_string())
+        .record(elapsed.as_secs_f64());
+}
+
+pub(crate) fn record_eviction(pool: &str, reason: EvictionReason) {
+    let reason = match reason {
+        EvictionReason::Closed => "closed",
+        EvictionReason::SessionKilled => "session_killed",
+        EvictionReason::PingFailed => "ping_failed",
+        EvictionReason::Retired => "retired",
+        EvictionReason::ResetFailed => "reset_failed",
+        EvictionReason::Idle => "idle",
+        EvictionReason::IdentityMismatch => "identity_mismatch",
+    };
+    counter!(
+        "oracle_pool_connections_evicted_total",
+        "pool" => pool.to_string(),
+        "reason" => reason
+    )
+    .increment(1);
+}
+
+pub(crate) fn record_acquire(pool: &str, wait: Duration, timed_out: bool, status: Status) {
+    histogram!("oracle_pool_acquire_wait_seconds", "pool" => pool.to_string())
+        .record(wait.as_secs_f64());
+    if timed_out {
+        counter!("oracle_pool_acquire_timeouts_total", "pool" => pool.to_string()).increment(1);
+    }
+
+    gauge!("oracle_pool_connections", "pool" => pool.to_string()).set(status.size as f64);
+    gauge!("oracle_pool_connections_idle", "pool" => pool.to_string()).set(status.available as f64);
+    gauge!("oracle_pool_connections_max", "pool" => pool.to_string()).set(status.max_size as f64);
+    gauge!("oracle_pool_waiting", "pool" => pool.to_string()).set(status.waiting as f64);
+}
@@ -0,0 +1,222 @@
+//! Named bind parameters
+//!
+//! The driver binds by position, so `:name` placeholders are rewritten to
+//! `:1`, `:2`, ... in order of appearance before the statement is sent. A
+//! name used several times is bound once per occurrence. String literals,
+//! quoted identifiers and comments are left alone.
+
+use oracle_rs::{Error, Value};
+
+/// Bind values by placeholder name
+///
+/// Usually built with the [`binds!`](crate::binds) macro. Names are matched
+/// case-insensitively, without the leading colon.
+#[derive(Debug, Clone, Default)]
+pub struct NamedBinds {
+    values: Vec<(String, Value)>,
+}
+
+impl NamedBinds {
+    /// Create an empty set of binds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a value, replacing any earlier value of the same name
+    pub fn bind(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let name = name.into();
+        let name = name.strip_prefix(':').unwrap_or(&name).to_string();
+        let value = value.into();
+        match self
+            .values
+            .iter_mut()
+            .find(|(n, _)| n.eq_ignore_ascii_case(&name))
+        {
+            Some(existing) => existing.1 = value,
+            None => self.values.push((name, value)),
+        }
+        self
+    }
+
+    /// Get the value bound to `name`
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+
+    /// Rewrite `sql` to positional placeholders and order the values to match
+    ///
+    /// Fails if a placeholder has no value, if a value is not used, or if the
+    /// statement already contains positional placeholders.
+    pub(crate) fn to_positional(&self, sql: &str) -> Result<(String, Vec<Value>), Error> {
+        let mut params = Vec::new();
+        let mut used = vec![false; self.values.len()];
+
+        let sql = rewrite_placeholders(sql, |name| {
+            if name.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(Error::SqlError(
+                    "positional placeholder in a statement with named binds".to_string(),
+                ));
+            }
+            let index = self
+                .values
+                .iter()
+                .position(|(n, _)| n.eq_ignore_ascii_case(name))
+                .ok_or_else(|| Error::SqlError(format!("no value for bind :{}", name)))?;
+            used[index] = true;
+            params.push(self.values[index].1.clone());
+            Ok(format!(":{}", params.len()))
+        })?;
+
+        if let Some(index) = used.iter().position(|used| !used) {
+            return Err(Error::SqlError(format!(
+                "bind :{} is not used in the statement",
+                self.values[index].0
+            )));
+        }
+        Ok((sql, params))
+    }
+}
+
+/// Replace every placeholder in `sql` with what `f` returns for its name
+///
+/// Names are passed without the colon; positional placeholders have numeric
+/// names. Literals, quoted identifiers, comments and `:=` are skipped.
+pub(crate) fn rewrite_placeholders<F>(sql: &str, mut f: F) -> Result<String, Error>
+where
+    F: FnMut(&str) -> Result<String, Error>,
+{
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = String::with_capacity(sql.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let end = match c {
+            '\'' => skip_literal(&chars, i),
+            'q' | 'Q' if next == Some('\'') && (i == 0 || !is_ident_char(chars[i - 1])) => {
+                skip_q_literal(&chars, i)
+            }
+            '"' => skip_until(&chars, i + 1, "\""),
+            '-' if next == Some('-') => skip_until(&chars, i + 2, "\n"),
+            '/' if next == Some('*') => skip_until(&chars, i + 2, "*/"),
+            ':' if next.is_some_and(|n| n.is_alphanumeric()) => {
+                let start = i + 1;
+                let mut end = start;
+                while end < chars.len() && is_ident_char(chars[end]) {
+                    end += 1;
+                }
+                let name: String = chars[start..end].iter().collect();
+                out.push_str(&f(&name)?);
+                i = end;
+                continue;
+            }
+            _ => i + 1,
+        };
+        out.extend(&chars[i..end]);
+        i = end;
+    }
+
+    Ok(out)
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '$' | '#')
+}
+
+/// End of the text starting at `from` up to and including `terminator`
+fn skip_until(chars: &[char], from: usize, terminator: &str) -> usize {
+    let terminator: Vec<char> = terminator.chars().collect();
+    (from..chars.len())
+        .find(|&i| chars[i..].starts_with(&terminator))
+        .map_or(chars.len(), |i| i + terminator.len())
+}
+
+/// End of a `'...'` literal, where `''` is an escaped quote
+fn skip_literal(chars: &[char], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == '\'' {
+            if chars.get(i + 1) == Some(&'\'') {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// End of a `q'[...]'` alternative quoting literal
+fn skip_q_literal(chars: &[char], start: usize) -> usize {
+    let Some(&open) = chars.get(start + 2) else {
+        return chars.len();
+    };
+    let close = match open {
+        '[' => ']',
+        '{' => '}',
+        '(' => ')',
+        '<' => '>',
+        c => c,
+    };
+    skip_until(chars, start + 3, &format!("{}'", close))
+}
+
+/// Build [`NamedBinds`] from `name => value` pairs
+///
+/// Values are converted with `Into<oracle_rs::Value>`.
+///
+/// ```rust
+/// use deadpool_oracle::binds;
+///
+/// let name = String::from("SMITH");
+/// let binds = binds! { "id" => 42, "name" => name };
+/// assert!(binds.get("ID").is_some());
+/// ```
+#[macro_export]
+macro_rules! binds {
+    ($($name:expr => $value:expr),* $(,)?) => {
+        $crate::NamedBinds::new()$(.bind($name, $value))*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_positional() {
+        let binds = crate::binds! { "id" => 42, "name" => "SMITH" };
+        let (sql, params) = binds
+            .to_positional(
+                "SELECT ':id', \"A:B\" FROM emp /* :name */ \
+                 WHERE id = :id OR manager_id = :ID -- :x\n AND name = :name",
+            )
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT ':id', \"A:B\" FROM emp /* :name */ \
+             WHERE id = :1 OR manager_id = :2 -- :x\n AND name = :3"
+        );
+        assert_eq!(params.len(), 3);
+        assert_eq!(params[1].as_i64(), Some(42));
+    }
+
+    #[test]
+    fn test_to_positional_plsql_and_errors() {
+        let binds = NamedBinds::new().bind(":total", 1);
+        let (sql, _) = binds
+            .to_positional("BEGIN v := q'[it's :x]'; :total := v; END;")
+            .unwrap();
+        assert_eq!(sql, "BEGIN v := q'[it's :x]'; :1 := v; END;");
+
+        assert!(binds.to_positional("SELECT :missing FROM DUAL").is_err());
+        assert!(binds.to_positional("SELECT 1 FROM DUAL").is_err());
+        assert!(binds.to_positional("SELECT :total, :1 FROM DUAL").is_err());
+    }
+}
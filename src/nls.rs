@@ -0,0 +1,155 @@
+//! NLS settings of pooled sessions
+//!
+//! A session's NLS parameters decide how dates, timestamps and numbers are
+//! converted to and from text. They default to the settings of the server
+//! or the database, which can differ between environments, so SQL relying
+//! on implicit conversions, like `TO_DATE('2024-01-31')`, may work against
+//! one database and fail against another. [`NlsConfig`] pins them for
+//! every session of a pool.
+
+/// NLS parameters applied to every session of a pool
+///
+/// Only the parameters that are set are changed; the others keep their
+/// server defaults. See [`PoolBuilder::nls()`](crate::PoolBuilder::nls).
+///
+/// ```rust
+/// use deadpool_oracle::NlsConfig;
+///
+/// let nls = NlsConfig::new()
+///     .territory("AMERICA")
+///     .date_format("YYYY-MM-DD")
+///     .timestamp_format("YYYY-MM-DD\"T\"HH24:MI:SS.FF6")
+///     .numeric_characters(".,");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NlsConfig {
+    language: Option<String>,
+    territory: Option<String>,
+    date_format: Option<String>,
+    timestamp_format: Option<String>,
+    timestamp_tz_format: Option<String>,
+    numeric_characters: Option<String>,
+}
+
+impl NlsConfig {
+    /// Create a configuration changing nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `NLS_LANGUAGE`, the language of messages and of day and month names
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Set `NLS_TERRITORY`, which sets default date and number formats
+    ///
+    /// The formats set on this configuration take precedence over the
+    /// defaults of the territory.
+    pub fn territory(mut self, territory: impl Into<String>) -> Self {
+        self.territory = Some(territory.into());
+        self
+    }
+
+    /// Set `NLS_DATE_FORMAT`, the format of `DATE` values converted to and
+    /// from text
+    pub fn date_format(mut self, format: impl Into<String>) -> Self {
+        self.date_format = Some(format.into());
+        self
+    }
+
+    /// Set `NLS_TIMESTAMP_FORMAT`, the format of `TIMESTAMP` values
+    /// converted to and from text
+    pub fn timestamp_format(mut self, format: impl Into<String>) -> Self {
+        self.timestamp_format = Some(format.into());
+        self
+    }
+
+    /// Set `NLS_TIMESTAMP_TZ_FORMAT`, the format of `TIMESTAMP WITH TIME
+    /// ZONE` values converted to and from text
+    pub fn timestamp_tz_format(mut self, format: impl Into<String>) -> Self {
+        self.timestamp_tz_format = Some(format.into());
+        self
+    }
+
+    /// Set `NLS_NUMERIC_CHARACTERS`, the decimal character followed by the
+    /// group separator, such as `".,"`
+    pub fn numeric_characters(mut self, characters: impl Into<String>) -> Self {
+        self.numeric_characters = Some(characters.into());
+        self
+    }
+
+    /// Statement applying the parameters that are set, if any
+    ///
+    /// Language and territory come first, as they reset the formats to
+    /// their defaults.
+    pub(crate) fn alter_session_sql(&self) -> Option<String> {
+        let parameters = [
+            ("NLS_LANGUAGE", &self.language),
+            ("NLS_TERRITORY", &self.territory),
+            ("NLS_DATE_FORMAT", &self.date_format),
+            ("NLS_TIMESTAMP_FORMAT", &self.timestamp_format),
+            ("NLS_TIMESTAMP_TZ_FORMAT", &self.timestamp_tz_format),
+            ("NLS_NUMERIC_CHARACTERS", &self.numeric_characters),
+        ];
+        let settings: Vec<String> = parameters
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.as_ref()?;
+                Some(format!("{} = '{}'", name, value.replace('\'', "''")))
+            })
+            .collect();
+        if settings.is_empty() {
+            return None;
+        }
+        Some(format!("ALTER SESSION SET {}", settings.join(" ")))
+    }
+}
+
+/// Statement setting the session time zone to a region name or offset
+pub(crate) fn time_zone_sql(time_zone: &str) -> String {
+    format!(
+        "ALTER SESSION SET TIME_ZONE = '{}'",
+        time_zone.replace('\'', "''")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alter_session_sql() {
+        assert_eq!(NlsConfig::new().alter_session_sql(), None);
+
+        let nls = NlsConfig::new()
+            .numeric_characters(".,")
+            .date_format("YYYY-MM-DD")
+            .territory("AMERICA");
+        assert_eq!(
+            nls.alter_session_sql().unwrap(),
+            "ALTER SESSION SET NLS_TERRITORY = 'AMERICA' \
+             NLS_DATE_FORMAT = 'YYYY-MM-DD' NLS_NUMERIC_CHARACTERS = '.,'"
+        );
+
+        // Values are quoted, so they can't end the literal
+        let nls = NlsConfig::new().date_format("DD' OR 'X");
+        assert_eq!(
+            nls.alter_session_sql().unwrap(),
+            "ALTER SESSION SET NLS_DATE_FORMAT = 'DD'' OR ''X'"
+        );
+    }
+
+    #[test]
+    fn test_time_zone_sql() {
+        assert_eq!(
+            time_zone_sql("+01:00"),
+            "ALTER SESSION SET TIME_ZONE = '+01:00'"
+        );
+        assert_eq!(
+            time_zone_sql("Europe/Oslo' x"),
+            "ALTER SESSION SET TIME_ZONE = 'Europe/Oslo'' x'"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Query observation
+//!
+//! A [`QueryObserver`] registered with
+//! [`PoolBuilder::query_observer()`](crate::PoolBuilder::query_observer) is
+//! told about every statement run through `Object` and `Transaction`, with
+//! its duration and outcome, so APM integrations need not wrap each call
+//! site. Bind values are never passed on, only their number.
+
+use oracle_rs::Error;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// A statement run on a pooled connection
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct QueryEvent<'a> {
+    /// Id of the connection the statement ran on
+    pub connection_id: u32,
+    /// SQL text as sent, with placeholders instead of bind values
+    pub sql: &'a str,
+    /// Number of bind values, across all rows for array DML
+    pub bind_count: usize,
+    /// Time until the result or error was received
+    pub duration: Duration,
+    /// Rows affected or returned, if known and the statement succeeded
+    pub rows_affected: Option<u64>,
+    /// Error the statement failed with
+    pub error: Option<&'a Error>,
+}
+
+/// Receives every statement run on the connections of a pool
+///
+/// Called on the task that ran the statement, right after it completed, so
+/// implementations should be quick and hand expensive work off elsewhere.
+/// Implemented for closures taking a [`QueryEvent`].
+pub trait QueryObserver: Send + Sync + 'static {
+    /// Called once per statement
+    fn on_query(&self, event: &QueryEvent<'_>);
+}
+
+impl<F> QueryObserver for F
+where
+    F: Fn(&QueryEvent<'_>) + Send + Sync + 'static,
+{
+    fn on_query(&self, event: &QueryEvent<'_>) {
+        self(event)
+    }
+}
+
+/// Statement about to be run, reported to `observer` once it completes
+pub(crate) struct Observed<'a> {
+    pub(crate) observer: &'a dyn QueryObserver,
+    pub(crate) connection_id: u32,
+    pub(crate) sql: &'a str,
+    pub(crate) bind_count: usize,
+}
+
+impl Observed<'_> {
+    /// Await `statement` and report its outcome
+    pub(crate) async fn run<T, F>(
+        self,
+        statement: F,
+        rows_affected: impl FnOnce(&T) -> Option<u64>,
+    ) -> Result<T, Error>
+    where
+        F: Future<Output = Result<T, Error>>,
+    {
+        let start = Instant::now();
+        let result = statement.await;
+        let duration = start.elapsed();
+
+        self.observer.on_query(&QueryEvent {
+            connection_id: self.connection_id,
+            sql: self.sql,
+            bind_count: self.bind_count,
+            duration,
+            rows_affected: result.as_ref().ok().and_then(rows_affected),
+            error: result.as_ref().err(),
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_observed_run() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let observer = {
+            let seen = seen.clone();
+            move |event: &QueryEvent<'_>| {
+                seen.lock().unwrap().push((
+                    event.sql.to_string(),
+                    event.rows_affected,
+                    event.error.is_some(),
+                ));
+            }
+        };
+
+        let observed = |sql| Observed {
+            observer: &observer,
+            connection_id: 1,
+            sql,
+            bind_count: 1,
+        };
+        observed("UPDATE t SET x = :1")
+            .run(async { Ok(3u64) }, |n| Some(*n))
+            .await
+            .unwrap();
+        observed("SELECT x FROM t WHERE id = :1")
+            .run(async { Err::<u64, _>(Error::NoDataFound) }, |n| Some(*n))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                ("UPDATE t SET x = :1".to_string(), Some(3), false),
+                ("SELECT x FROM t WHERE id = :1".to_string(), None, true),
+            ]
+        );
+    }
+}
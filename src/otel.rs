@@ -0,0 +1,163 @@
+//! OpenTelemetry metrics and spans for pool operations
+//!
+//! Instruments follow the `db.client.connections.*` semantic conventions and
+//! are registered with the global meter provider, so they are exported by
+//! whatever OpenTelemetry pipeline the application installs.
+
+use crate::{Object, OracleConnectionManager, PoolError};
+use deadpool::managed::{self, PoolError as ManagedPoolError};
+use opentelemetry::global;
+use opentelemetry::metrics::{Counter, Histogram};
+use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
+use opentelemetry::KeyValue;
+use std::time::{Duration, SystemTime};
+
+/// Instrumentation scope used for the meter and tracer
+const SCOPE: &str = "deadpool-oracle";
+
+/// Attribute identifying the pool in all instruments
+const POOL_NAME: &str = "pool.name";
+
+/// OpenTelemetry instruments for one pool
+pub(crate) struct OtelMetrics {
+    pool_name: String,
+    create_time: Histogram<f64>,
+    wait_time: Histogram<f64>,
+    timeouts: Counter<u64>,
+}
+
+impl OtelMetrics {
+    pub(crate) fn new(pool_name: &str) -> Self {
+        let meter = global::meter(SCOPE);
+
+        Self {
+            pool_name: pool_name.to_string(),
+            create_time: meter
+                .f64_histogram("db.client.connections.create_time")
+                .with_unit("ms")
+                .with_description("The time it took to create a new connection")
+                .build(),
+            wait_time: meter
+                .f64_histogram("db.client.connections.wait_time")
+                .with_unit("ms")
+                .with_description("The time it took to obtain an open connection from the pool")
+                .build(),
+            timeouts: meter
+                .u64_counter("db.client.connections.timeouts")
+                .with_description(
+                    "The number of connection timeouts that have occurred trying to obtain a connection from the pool",
+                )
+                .build(),
+        }
+    }
+
+    fn attributes(&self) -> [KeyValue; 1] {
+        [KeyValue::new(POOL_NAME, self.pool_name.clone())]
+    }
+
+    /// Register the observable instruments reporting pool usage
+    ///
+    /// Only a weak reference is kept, so the callbacks stop reporting once
+    /// the pool is dropped.
+    pub(crate) fn observe_pool(&self, pool: &managed::Pool<OracleConnectionManager>) {
+        let meter = global::meter(SCOPE);
+        let name = self.pool_name.clone();
+
+        let usage_pool = pool.weak();
+        let usage_name = name.clone();
+        meter
+            .i64_observable_up_down_counter("db.client.connections.usage")
+            .with_description(
+                "The number of connections that are currently in state described by the state attribute",
+            )
+            .with_callback(move |observer| {
+                if let Some(pool) = usage_pool.upgrade() {
+                    let status = pool.status();
+                    let idle = status.available as i64;
+                    let used = status.size as i64 - idle;
+                    observer.observe(
+                        idle,
+                        &[
+                            KeyValue::new(POOL_NAME, usage_name.clone()),
+                            KeyValue::new("state", "idle"),
+                        ],
+                    );
+                    observer.observe(
+                        used,
+                        &[
+                            KeyValue::new(POOL_NAME, usage_name.clone()),
+                            KeyValue::new("state", "used"),
+                        ],
+                    );
+                }
+            })
+            .build();
+
+        let max_pool = pool.weak();
+        let max_name = name.clone();
+        meter
+            .i64_observable_up_down_counter("db.client.connections.max")
+            .with_description("The maximum number of open connections allowed")
+            .with_callback(move |observer| {
+                if let Some(pool) = max_pool.upgrade() {
+                    observer.observe(
+                        pool.status().max_size as i64,
+                        &[KeyValue::new(POOL_NAME, max_name.clone())],
+                    );
+                }
+            })
+            .build();
+
+        let pending_pool = pool.weak();
+        meter
+            .i64_observable_up_down_counter("db.client.connections.pending_requests")
+            .with_description(
+                "The number of pending requests for an open connection, cumulative for the entire pool",
+            )
+            .with_callback(move |observer| {
+                if let Some(pool) = pending_pool.upgrade() {
+                    observer.observe(
+                        pool.status().waiting as i64,
+                        &[KeyValue::new(POOL_NAME, name.clone())],
+                    );
+                }
+            })
+            .build();
+    }
+
+    /// Record the time it took to create a connection
+    pub(crate) fn record_create(&self, elapsed: Duration) {
+        self.create_time
+            .record(elapsed.as_secs_f64() * 1000.0, &self.attributes());
+    }
+
+    /// Record an acquisition and emit a span describing it
+    pub(crate) fn record_acquire(&self, result: &Result<Object, PoolError>, wait: Duration) {
+        let attributes = self.attributes();
+        self.wait_time
+            .record(wait.as_secs_f64() * 1000.0, &attributes);
+        if let Err(ManagedPoolError::Timeout(_)) = result {
+            self.timeouts.add(1, &attributes);
+        }
+
+        let tracer = global::tracer(SCOPE);
+        let mut span = tracer
+            .span_builder("oracle_pool.get")
+            .with_kind(SpanKind::Client)
+            .with_start_time(SystemTime::now() - wait)
+            .start(&tracer);
+        span.set_attribute(KeyValue::new("db.system", "oracle"));
+        span.set_attribute(KeyValue::new(POOL_NAME, self.pool_name.clone()));
+        span.set_attribute(KeyValue::new(
+            "db.client.connections.wait_time",
+            wait.as_secs_f64() * 1000.0,
+        ));
+        match result {
+            Ok(conn) => {
+                span.set_attribute(KeyValue::new("db.oracle.connection_id", conn.id() as i64));
+            }
+            Err(e) => span.set_status(Status::error(e.to_string())),
+        }
+        span.end();
+    }
+}
@@ -0,0 +1,243 @@
+//! Pagination of query results
+//!
+//! Offset pagination appends `OFFSET ... FETCH NEXT` to the caller's SQL,
+//! which must therefore end with its `ORDER BY`. Keyset pagination wraps the
+//! SQL in an inline view filtered and ordered by a key column. In both cases
+//! one extra row is fetched to tell whether there is a next page.
+
+use crate::from_row::{query_as, query_scalar};
+use crate::Object;
+use oracle_rs::{Error, Row, Value};
+
+/// Which page of a query to fetch
+///
+/// Passed to [`Object::query_paged()`](crate::Object::query_paged).
+#[derive(Debug, Clone)]
+pub struct PageRequest {
+    position: Position,
+    size: u32,
+    with_total: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Position {
+    Offset(u64),
+    Keyset {
+        column: String,
+        after: Option<Value>,
+    },
+}
+
+impl PageRequest {
+    /// Fetch `size` rows starting at row `offset` (zero-based)
+    pub fn offset(offset: u64, size: u32) -> Self {
+        Self {
+            position: Position::Offset(offset),
+            size,
+            with_total: false,
+        }
+    }
+
+    /// Fetch page `index` (zero-based) of `size` rows
+    pub fn page(index: u64, size: u32) -> Self {
+        Self::offset(index.saturating_mul(u64::from(size)), size)
+    }
+
+    /// Fetch `size` rows whose `column` is greater than `after`
+    ///
+    /// Rows are ordered by `column`, which must be unique. It names a column
+    /// of the query's result, so it can't be qualified with a table alias:
+    /// select `e.id` and pass `id`. Pass `None` for the first page. Unlike offsets, keys stay stable while rows are
+    /// inserted and do not get slower for later pages.
+    pub fn keyset(column: impl Into<String>, after: Option<Value>, size: u32) -> Self {
+        Self {
+            position: Position::Keyset {
+                column: column.into(),
+                after,
+            },
+            size,
+            with_total: false,
+        }
+    }
+
+    /// Also count all rows of the query
+    ///
+    /// Costs an additional `SELECT COUNT(*)` round trip.
+    pub fn with_total(mut self) -> Self {
+        self.with_total = true;
+        self
+    }
+
+    /// Build the paged SQL and its binds
+    fn to_sql(&self, sql: &str, params: &[Value]) -> Result<(String, Vec<Value>), Error> {
+        let mut binds = params.to_vec();
+        let paged = match &self.position {
+            Position::Offset(offset) => {
+                let offset = i64::try_from(*offset).unwrap_or(i64::MAX);
+                binds.push(Value::Integer(offset));
+                binds.push(Value::Integer(i64::from(self.size) + 1));
+                format!(
+                    "{} OFFSET :{} ROWS FETCH NEXT :{} ROWS ONLY",
+                    sql,
+                    binds.len() - 1,
+                    binds.len()
+                )
+            }
+            Position::Keyset { column, after } => {
+                // The inline view hides the query's table aliases
+                if !is_identifier(column) || column.contains('.') {
+                    return Err(Error::SqlError(format!("invalid key column: {}", column)));
+                }
+                let filter = match after {
+                    Some(after) => {
+                        binds.push(after.clone());
+                        format!(" WHERE {} > :{}", column, binds.len())
+                    }
+                    None => String::new(),
+                };
+                binds.push(Value::Integer(i64::from(self.size) + 1));
+                format!(
+                    "SELECT * FROM ({}){} ORDER BY {} FETCH NEXT :{} ROWS ONLY",
+                    sql,
+                    filter,
+                    column,
+                    binds.len()
+                )
+            }
+        };
+        Ok((paged, binds))
+    }
+
+    /// Request for the page following `rows`
+    fn next(&self, rows: &[Row]) -> Option<PageRequest> {
+        let position = match &self.position {
+            Position::Offset(offset) => Position::Offset(offset + rows.len() as u64),
+            Position::Keyset { column, .. } => Position::Keyset {
+                column: column.clone(),
+                after: Some(rows.last()?.get_by_name(column)?.clone()),
+            },
+        };
+        Some(PageRequest {
+            position,
+            size: self.size,
+            with_total: self.with_total,
+        })
+    }
+}
+
+/// Only plain (optionally qualified) identifiers are spliced into SQL
+pub(crate) fn is_identifier(column: &str) -> bool {
+    !column.is_empty()
+        && column.split('.').all(|part| {
+            part.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '#'))
+        })
+}
+
+/// One page of query results
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// Rows of this page
+    pub items: Vec<T>,
+    /// Number of rows of the whole query, if requested with `with_total()`
+    pub total: Option<u64>,
+    /// Request for the next page, `None` on the last page
+    pub next: Option<PageRequest>,
+}
+
+impl<T> Page<T> {
+    /// Whether there are rows after this page
+    pub fn has_more(&self) -> bool {
+        self.next.is_some()
+    }
+}
+
+/// Run one page of a query
+pub(crate) async fn query_paged(
+    conn: &Object,
+    sql: &str,
+    params: &[Value],
+    request: &PageRequest,
+) -> Result<Page<Row>, Error> {
+    let (paged, binds) = request.to_sql(sql, params)?;
+    let mut items: Vec<Row> = query_as(conn, &paged, &binds).await?;
+
+    let next = if items.len() > request.size as usize {
+        items.truncate(request.size as usize);
+        request.next(&items)
+    } else {
+        None
+    };
+
+    let total = if request.with_total {
+        let count = format!("SELECT COUNT(*) FROM ({})", sql);
+        Some(query_scalar(conn, &count, params).await?)
+    } else {
+        None
+    };
+
+    Ok(Page { items, total, next })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_sql() {
+        let request = PageRequest::page(2, 10);
+        let (sql, binds) = request
+            .to_sql(
+                "SELECT * FROM emp WHERE dept = :1 ORDER BY id",
+                &[Value::Integer(20)],
+            )
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT * FROM emp WHERE dept = :1 ORDER BY id OFFSET :2 ROWS FETCH NEXT :3 ROWS ONLY"
+        );
+        assert_eq!(binds[1].as_i64(), Some(20));
+        assert_eq!(binds[2].as_i64(), Some(11));
+    }
+
+    #[test]
+    fn test_keyset_sql() {
+        let first = PageRequest::keyset("id", None, 10);
+        let (sql, binds) = first.to_sql("SELECT id, name FROM emp", &[]).unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM (SELECT id, name FROM emp) ORDER BY id FETCH NEXT :1 ROWS ONLY"
+        );
+        assert_eq!(binds.len(), 1);
+
+        let rows = vec![Row::with_names(
+            vec![Value::Integer(42), Value::String("SMITH".to_string())],
+            vec!["ID".to_string(), "NAME".to_string()],
+        )];
+        let next = first.next(&rows).unwrap();
+        let (sql, binds) = next.to_sql("SELECT id, name FROM emp", &[]).unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM (SELECT id, name FROM emp) WHERE id > :1 ORDER BY id FETCH NEXT :2 ROWS ONLY"
+        );
+        assert_eq!(binds[0].as_i64(), Some(42));
+
+        let bad = PageRequest::keyset("id; DROP TABLE emp", None, 10);
+        assert!(bad.to_sql("SELECT id FROM emp", &[]).is_err());
+        let qualified = PageRequest::keyset("e.id", None, 10);
+        assert!(qualified.to_sql("SELECT e.id FROM emp e", &[]).is_err());
+    }
+
+    #[test]
+    fn test_page_overflow() {
+        let request = PageRequest::page(u64::MAX / 2, 10);
+        assert!(matches!(request.position, Position::Offset(u64::MAX)));
+        let (_, binds) = request
+            .to_sql("SELECT id FROM emp ORDER BY id", &[])
+            .unwrap();
+        assert_eq!(binds[0].as_i64(), Some(i64::MAX));
+    }
+}
@@ -0,0 +1,83 @@
+//! Backoff of connection creation after repeated recycle failures
+//!
+//! A connection that fails its health check is evicted and replaced on the
+//! next `get()`. When the database keeps failing them, for example while it
+//! is in restricted mode, replacing them at once only hammers the listener.
+//! Every failure puts the pool in quarantine for an exponentially growing
+//! delay, during which new connections wait before connecting. A recycle
+//! that succeeds lifts it.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Quarantine state of a pool
+#[derive(Debug)]
+pub(crate) struct Quarantine {
+    base: Duration,
+    max: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    failures: u32,
+    until: Option<Instant>,
+}
+
+impl Quarantine {
+    pub(crate) fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max: max.max(base),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Record a failed recycle or create at `now`, returning the new backoff
+    pub(crate) fn record_failure(&self, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let backoff = self
+            .base
+            .saturating_mul(2u32.saturating_pow(state.failures))
+            .min(self.max);
+        state.failures = state.failures.saturating_add(1);
+        state.until = Some(now + backoff);
+        backoff
+    }
+
+    /// Lift the quarantine after a successful recycle
+    pub(crate) fn record_success(&self) {
+        *self.state.lock().unwrap() = State::default();
+    }
+
+    /// Time from `now` until new connections may be created, if quarantined
+    pub(crate) fn remaining(&self, now: Instant) -> Option<Duration> {
+        let until = self.state.lock().unwrap().until?;
+        let remaining = until.saturating_duration_since(now);
+        (!remaining.is_zero()).then_some(remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quarantine_backoff() {
+        let quarantine = Quarantine::new(Duration::from_secs(1), Duration::from_secs(5));
+        let now = Instant::now();
+        assert_eq!(quarantine.remaining(now), None);
+
+        assert_eq!(quarantine.record_failure(now), Duration::from_secs(1));
+        assert_eq!(quarantine.record_failure(now), Duration::from_secs(2));
+        assert_eq!(quarantine.record_failure(now), Duration::from_secs(4));
+        assert_eq!(quarantine.record_failure(now), Duration::from_secs(5));
+        assert_eq!(quarantine.remaining(now), Some(Duration::from_secs(5)));
+        let later = now + Duration::from_secs(5);
+        assert_eq!(quarantine.remaining(later), None);
+
+        quarantine.record_success();
+        assert_eq!(quarantine.remaining(now), None);
+        assert_eq!(quarantine.record_failure(now), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,358 @@
+//! Recording of the statements run through a pool
+//!
+//! A [`Recorder`] wraps an [`AnyPool`] and captures every statement run
+//! through its connections with its binds and outcome. Recordings are
+//! saved as JSON lines, one [`RecordedQuery`] per line, and replayed by
+//! `testing::MockManager::replay()`, so a trace taken in production becomes
+//! a regression test that needs no database.
+
+use crate::{AnyConnection, AnyError, AnyFuture, AnyPool, AnyRows, AnyValue};
+use deadpool::Status;
+use oracle_rs::Error;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, LineWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A statement run through a recorded connection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedQuery {
+    /// SQL text; commits and rollbacks are recorded as `COMMIT` and `ROLLBACK`
+    pub sql: String,
+    /// Bind values
+    pub params: Vec<AnyValue>,
+    /// What the statement returned
+    pub outcome: RecordedOutcome,
+}
+
+/// Outcome of a [`RecordedQuery`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecordedOutcome {
+    /// Rows of a query
+    Rows(AnyRows),
+    /// Number of rows affected by a statement
+    RowsAffected(u64),
+    /// A commit or rollback succeeded
+    Done,
+    /// The statement failed, with its ORA error code if it had one
+    Error { code: Option<u32>, message: String },
+}
+
+impl RecordedOutcome {
+    fn from_error(error: &AnyError) -> Self {
+        match error.downcast_ref::<Error>() {
+            Some(Error::OracleError { code, message } | Error::ServerError { code, message }) => {
+                RecordedOutcome::Error {
+                    code: Some(*code),
+                    message: message.clone(),
+                }
+            }
+            _ => RecordedOutcome::Error {
+                code: None,
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+/// Captures the statements run through the pools it wraps
+///
+/// Clones share the recording. A recorder created with
+/// [`new()`](Self::new) keeps the statements in memory until they are
+/// [saved](Self::save); one created with [`to_file()`](Self::to_file)
+/// appends each statement to the file as soon as it completes, so long
+/// production traces don't grow the process.
+///
+/// ```rust,no_run
+/// # async fn example(pool: deadpool_oracle::Pool) -> Result<(), deadpool_oracle::AnyError> {
+/// use deadpool_oracle::{AnyPool, Recorder};
+///
+/// let recorder = Recorder::to_file("orders.trace.jsonl")?;
+/// let pool = recorder.wrap(pool);
+///
+/// // Every statement run through `pool` is recorded
+/// let conn = pool.get().await?;
+/// conn.query("SELECT id FROM orders", &[]).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Recorder {
+    sink: Arc<Mutex<Sink>>,
+}
+
+enum Sink {
+    Memory(Vec<RecordedQuery>),
+    File(LineWriter<File>),
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    /// Create a recorder keeping the statements in memory
+    pub fn new() -> Self {
+        Self {
+            sink: Arc::new(Mutex::new(Sink::Memory(Vec::new()))),
+        }
+    }
+
+    /// Create a recorder appending the statements to the file at `path`
+    pub fn to_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            sink: Arc::new(Mutex::new(Sink::File(LineWriter::new(file)))),
+        })
+    }
+
+    /// Record the statements run through the connections of `pool`
+    pub fn wrap<P: AnyPool>(&self, pool: P) -> RecordingPool<P> {
+        RecordingPool {
+            pool,
+            recorder: self.clone(),
+        }
+    }
+
+    /// Statements recorded in memory so far, in order of completion
+    ///
+    /// Always empty for a recorder writing to a file.
+    pub fn queries(&self) -> Vec<RecordedQuery> {
+        match &*self.sink.lock().unwrap() {
+            Sink::Memory(queries) => queries.clone(),
+            Sink::File(_) => Vec::new(),
+        }
+    }
+
+    /// Write the statements recorded in memory to the file at `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        for query in self.queries() {
+            serde_json::to_writer(&mut file, &query)?;
+            file.write_all(b"\n")?;
+        }
+        file.flush()
+    }
+
+    /// Read a recording saved by [`save()`](Self::save) or [`to_file()`](Self::to_file)
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<RecordedQuery>> {
+        let file = BufReader::new(File::open(path)?);
+        let mut queries = Vec::new();
+        for line in file.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                queries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(queries)
+    }
+
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn record(&self, sql: &str, params: &[AnyValue], outcome: RecordedOutcome) {
+        let query = RecordedQuery {
+            sql: sql.to_string(),
+            params: params.to_vec(),
+            outcome,
+        };
+        match &mut *self.sink.lock().unwrap() {
+            Sink::Memory(queries) => queries.push(query),
+            Sink::File(file) => {
+                // A trace missing a line beats failing the statement
+                let written = serde_json::to_writer(&mut *file, &query)
+                    .map_err(io::Error::from)
+                    .and_then(|()| file.write_all(b"\n"));
+                if let Err(e) = written {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(error = %e, "failed to record a statement");
+                }
+            }
+        }
+    }
+
+    fn record_result<T>(
+        &self,
+        sql: &str,
+        params: &[AnyValue],
+        result: &Result<T, AnyError>,
+        outcome: impl FnOnce(&T) -> RecordedOutcome,
+    ) {
+        let outcome = match result {
+            Ok(value) => outcome(value),
+            Err(e) => RecordedOutcome::from_error(e),
+        };
+        self.record(sql, params, outcome);
+    }
+}
+
+/// A pool whose connections are recorded by a [`Recorder`]
+pub struct RecordingPool<P> {
+    pool: P,
+    recorder: Recorder,
+}
+
+impl<P> RecordingPool<P> {
+    /// The wrapped pool
+    pub fn inner(&self) -> &P {
+        &self.pool
+    }
+
+    /// The recorder capturing the statements
+    pub fn recorder(&self) -> &Recorder {
+        &self.recorder
+    }
+}
+
+impl<P: AnyPool> AnyPool for RecordingPool<P> {
+    fn get(&self) -> AnyFuture<'_, Box<dyn AnyConnection>> {
+        Box::pin(async move {
+            let conn = self.pool.get().await?;
+            let recorder = self.recorder.clone();
+            Ok(Box::new(RecordingConnection { conn, recorder }) as Box<dyn AnyConnection>)
+        })
+    }
+
+    fn status(&self) -> Status {
+        self.pool.status()
+    }
+
+    fn close(&self) {
+        self.pool.close()
+    }
+}
+
+/// Connection of a [`RecordingPool`]
+struct RecordingConnection {
+    conn: Box<dyn AnyConnection>,
+    recorder: Recorder,
+}
+
+impl AnyConnection for RecordingConnection {
+    // The connection is only `Send`, so its futures are created up front
+    // instead of borrowing it across an await
+    fn execute<'a>(&'a self, sql: &'a str, params: &'a [AnyValue]) -> AnyFuture<'a, u64> {
+        let execute = self.conn.execute(sql, params);
+        let recorder = &self.recorder;
+        Box::pin(async move {
+            let result = execute.await;
+            recorder.record_result(sql, params, &result, |n| RecordedOutcome::RowsAffected(*n));
+            result
+        })
+    }
+
+    fn query<'a>(&'a self, sql: &'a str, params: &'a [AnyValue]) -> AnyFuture<'a, AnyRows> {
+        let query = self.conn.query(sql, params);
+        let recorder = &self.recorder;
+        Box::pin(async move {
+            let result = query.await;
+            recorder.record_result(sql, params, &result, |rows| {
+                RecordedOutcome::Rows(rows.clone())
+            });
+            result
+        })
+    }
+
+    fn commit(&self) -> AnyFuture<'_, ()> {
+        let commit = self.conn.commit();
+        let recorder = &self.recorder;
+        Box::pin(async move {
+            let result = commit.await;
+            recorder.record_result("COMMIT", &[], &result, |()| RecordedOutcome::Done);
+            result
+        })
+    }
+
+    fn rollback(&self) -> AnyFuture<'_, ()> {
+        let rollback = self.conn.rollback();
+        let recorder = &self.recorder;
+        Box::pin(async move {
+            let result = rollback.await;
+            recorder.record_result("ROLLBACK", &[], &result, |()| RecordedOutcome::Done);
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let file = format!("deadpool-oracle-{}-{}.jsonl", std::process::id(), name);
+        std::env::temp_dir().join(file)
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let recorder = Recorder::new();
+        let rows = AnyRows {
+            columns: vec!["ID".to_string(), "NAME".to_string()],
+            rows: vec![vec![AnyValue::Int(1), AnyValue::Text("alice".to_string())]],
+        };
+        let params = [AnyValue::Bytes(vec![1, 2]), AnyValue::Null];
+        recorder.record(
+            "SELECT id, name FROM users",
+            &params,
+            RecordedOutcome::Rows(rows),
+        );
+        recorder.record("COMMIT", &[], RecordedOutcome::Done);
+        let error: AnyError = Box::new(Error::oracle(942, "table or view does not exist"));
+        recorder.record("DROP TABLE users", &[], RecordedOutcome::from_error(&error));
+
+        let path = temp_path("save");
+        recorder.save(&path).unwrap();
+        let loaded = Recorder::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, recorder.queries());
+        assert_eq!(
+            loaded[2].outcome,
+            RecordedOutcome::Error {
+                code: Some(942),
+                message: "table or view does not exist".to_string()
+            }
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        use crate::testing::MockManager;
+
+        let count = |n| AnyRows {
+            columns: vec!["COUNT(*)".to_string()],
+            rows: vec![vec![AnyValue::Int(n)]],
+        };
+        let path = temp_path("replay");
+        let recorder = Recorder::to_file(&path).unwrap();
+        let pool = recorder.wrap(
+            MockManager::new()
+                .with_query_result("SELECT COUNT(*) FROM users", count(2))
+                .with_error("DROP TABLE users", 942, "table or view does not exist")
+                .into_pool(1),
+        );
+        let conn = pool.get().await.unwrap();
+        let params = [AnyValue::Int(7)];
+        conn.query("SELECT COUNT(*) FROM users", &[]).await.unwrap();
+        conn.execute("DELETE FROM users WHERE id = :1", &params)
+            .await
+            .unwrap();
+        assert!(conn.execute("DROP TABLE users", &[]).await.is_err());
+        conn.commit().await.unwrap();
+        drop(conn);
+        assert!(recorder.queries().is_empty());
+
+        let replayed = MockManager::replay(&path).unwrap().into_pool(1);
+        std::fs::remove_file(&path).unwrap();
+        let conn = AnyPool::get(&replayed).await.unwrap();
+        assert_eq!(
+            conn.query("SELECT COUNT(*) FROM users", &[]).await.unwrap(),
+            count(2)
+        );
+        let error = conn.execute("DROP TABLE users", &[]).await.unwrap_err();
+        assert!(error.to_string().contains("ORA-00942"));
+        let statements = replayed.manager().statements();
+        assert_eq!(statements.len(), 2);
+    }
+}
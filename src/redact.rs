@@ -0,0 +1,192 @@
+//! Keeping credentials out of logs
+//!
+//! The `Debug` output of the manager and the pool builder shows each
+//! endpoint through [`ConfigDebug`], which leaves out the password and the
+//! wallet password the driver's own `Debug` would print. Errors of
+//! connection attempts go through [`scrub()`] before they are returned, so
+//! a password echoed by the server or the driver doesn't end up in a log
+//! line or a panic message.
+
+use oracle_rs::{Config, Error, TlsConfig};
+use std::fmt;
+use std::io;
+
+/// Shown in place of a secret
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// `Debug` of a connection configuration without its secrets
+pub(crate) struct ConfigDebug<'a>(pub(crate) &'a Config);
+
+impl fmt::Debug for ConfigDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let config = self.0;
+        let tls_config = config.tls_config.as_ref().map(TlsConfigDebug);
+        f.debug_struct("Config")
+            .field("host", &config.host)
+            .field("port", &config.port)
+            .field("service", &config.service)
+            .field("username", &config.username)
+            .field("password", &REDACTED)
+            .field("tls_mode", &config.tls_mode)
+            .field("tls_config", &tls_config)
+            .field("connect_timeout", &config.connect_timeout)
+            .field("stmtcachesize", &config.stmtcachesize)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `Debug` of TLS settings without the wallet password
+struct TlsConfigDebug<'a>(&'a TlsConfig);
+
+impl fmt::Debug for TlsConfigDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tls = self.0;
+        let wallet_password = tls.wallet_password.as_ref().map(|_| REDACTED);
+        f.debug_struct("TlsConfig")
+            .field("server_name", &tls.server_name)
+            .field("ca_cert_path", &tls.ca_cert_path)
+            .field("client_cert_path", &tls.client_cert_path)
+            .field("client_key_path", &tls.client_key_path)
+            .field("wallet_path", &tls.wallet_path)
+            .field("wallet_password", &wallet_password)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Remove credentials from the message of a connection error
+///
+/// Every occurrence of one of `secrets` is replaced, as is the password of
+/// an EZConnect string like `username/password@host`, which can show a
+/// password the pool doesn't know, such as the one of a `Config`.
+pub(crate) fn scrub(err: Error, username: &str, secrets: &[&str]) -> Error {
+    let clean = |text: String| scrub_text(text, username, secrets);
+    let clean_opt = |text: Option<String>| text.map(clean);
+    match err {
+        Error::Protocol(msg) => Error::Protocol(clean(msg)),
+        Error::ProtocolError(msg) => Error::ProtocolError(clean(msg)),
+        Error::ConnectionRefused {
+            error_code,
+            message,
+        } => Error::ConnectionRefused {
+            error_code,
+            message: clean_opt(message),
+        },
+        Error::ConnectionRedirected { address } => Error::ConnectionRedirected {
+            address: clean(address),
+        },
+        Error::ConnectionRedirect(msg) => Error::ConnectionRedirect(clean(msg)),
+        Error::ConnectionClosedByServer(msg) => Error::ConnectionClosedByServer(clean(msg)),
+        Error::InvalidConnectionString(msg) => Error::InvalidConnectionString(clean(msg)),
+        Error::InvalidServiceName {
+            service_name,
+            message,
+        } => Error::InvalidServiceName {
+            service_name: clean_opt(service_name),
+            message: clean_opt(message),
+        },
+        Error::InvalidSid { sid, message } => Error::InvalidSid {
+            sid: clean_opt(sid),
+            message: clean_opt(message),
+        },
+        Error::AuthenticationFailed(msg) => Error::AuthenticationFailed(clean(msg)),
+        Error::OracleError { code, message } => Error::OracleError {
+            code,
+            message: clean(message),
+        },
+        Error::ServerError { code, message } => Error::ServerError {
+            code,
+            message: clean(message),
+        },
+        Error::SqlError(msg) => Error::SqlError(clean(msg)),
+        Error::FeatureNotSupported(msg) => Error::FeatureNotSupported(clean(msg)),
+        Error::Internal(msg) => Error::Internal(clean(msg)),
+        Error::Io(e) => {
+            let text = e.to_string();
+            let scrubbed = clean(text.clone());
+            if scrubbed == text {
+                Error::Io(e)
+            } else {
+                Error::Io(io::Error::new(e.kind(), scrubbed))
+            }
+        }
+        other => other,
+    }
+}
+
+fn scrub_text(mut text: String, username: &str, secrets: &[&str]) -> String {
+    for secret in secrets.iter().filter(|secret| !secret.is_empty()) {
+        if text.contains(secret) {
+            text = text.replace(secret, REDACTED);
+        }
+    }
+    if username.is_empty() {
+        return text;
+    }
+    let prefix = format!("{}/", username);
+    let mut scrubbed = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find(&prefix) {
+        let after = start + prefix.len();
+        scrubbed.push_str(&rest[..after]);
+        rest = &rest[after..];
+        // Only a password if an `@` follows within the same word
+        let word = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        if let Some(at) = rest[..word].find('@').filter(|at| *at > 0) {
+            scrubbed.push_str(REDACTED);
+            rest = &rest[at..];
+        }
+    }
+    scrubbed.push_str(rest);
+    scrubbed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_debug_redacts_passwords() {
+        let config = Config::new("db", 2484, "ORDERS", "app", "hunter2")
+            .tls_config(TlsConfig::new().with_wallet("/etc/oracle/wallet", Some("w4llet".into())));
+        assert!(format!("{:?}", config).contains("hunter2"));
+
+        let debug = format!("{:?}", ConfigDebug(&config));
+        assert!(debug.contains("app"));
+        assert!(debug.contains("/etc/oracle/wallet"));
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("w4llet"));
+    }
+
+    #[test]
+    fn test_scrub() {
+        let err = Error::AuthenticationFailed("token s3cr3t-token expired".to_string());
+        let err = scrub(err, "app", &["s3cr3t-token", ""]);
+        assert_eq!(
+            err.to_string(),
+            "authentication failed: token <redacted> expired"
+        );
+
+        let err = Error::InvalidConnectionString("bad string app/hunter2@db:1521/ORDERS".into());
+        let err = scrub(err, "app", &[]);
+        assert_eq!(
+            err.to_string(),
+            "invalid connection string: bad string app/<redacted>@db:1521/ORDERS"
+        );
+
+        // A path isn't a password
+        let err = Error::Internal("no wallet file found in /home/app/wallet".to_string());
+        let err = scrub(err, "app", &[]);
+        assert_eq!(
+            err.to_string(),
+            "internal error: no wallet file found in /home/app/wallet"
+        );
+
+        let err = Error::Io(io::Error::new(io::ErrorKind::Other, "hunter2"));
+        let err = scrub(err, "app", &["hunter2"]);
+        assert!(matches!(&err, Error::Io(e) if e.kind() == io::ErrorKind::Other));
+        assert!(!err.to_string().contains("hunter2"));
+
+        let err = scrub(Error::oracle(1017, "invalid username/password"), "app", &[]);
+        assert_eq!(err.to_string(), "ORA-01017: invalid username/password");
+    }
+}
@@ -0,0 +1,219 @@
+//! Named pools of an application
+//!
+//! Applications talking to several schemas or databases, such as an
+//! "orders" schema and a "reporting" replica, keep one pool per target.
+//! [`PoolRegistry`] holds them under their names, so they can be looked up,
+//! monitored and closed together.
+
+#[cfg(feature = "serde")]
+use crate::{BuildError, PoolsConfig};
+use crate::{Pool, PoolStats, Status};
+use std::collections::BTreeMap;
+
+/// Pools of an application by name, see [`PoolRegistry`]
+///
+/// Reads naturally where the pools come from configuration:
+/// `Pools::from_config(&config)`.
+pub type Pools = PoolRegistry;
+
+/// Pools looked up by name
+///
+/// Cloning is cheap; clones share the pools.
+///
+/// ```rust,no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use deadpool_oracle::{PoolBuilder, PoolRegistry};
+/// use oracle_rs::Config;
+///
+/// let mut registry = PoolRegistry::new();
+/// registry.insert(
+///     "orders",
+///     PoolBuilder::new(Config::new("db", 1521, "ORDERS", "app", "secret")).build()?,
+/// );
+/// registry.insert(
+///     "reporting",
+///     PoolBuilder::new(Config::new("replica", 1521, "DWH", "report", "secret")).build()?,
+/// );
+///
+/// let conn = registry.get("orders").expect("configured").get().await?;
+/// println!("{} connections open", registry.status().size);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct PoolRegistry {
+    pools: BTreeMap<String, Pool>,
+}
+
+impl PoolRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a pool for every entry of `config`, named by its key
+    ///
+    /// Fails on the first pool that cannot be built. With the `serde`
+    /// feature the settings can be read straight from the application's
+    /// configuration file, in any format serde supports:
+    ///
+    /// ```rust
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use deadpool_oracle::{Pools, PoolsConfig};
+    ///
+    /// let config: PoolsConfig = toml::from_str(
+    ///     r#"
+    ///     [pools.orders]
+    ///     host = "db"
+    ///     service_name = "ORDERS"
+    ///     username = "app"
+    ///     password = "secret"
+    ///     max_size = 20
+    ///
+    ///     [pools.reporting]
+    ///     host = "replica"
+    ///     service_name = "DWH"
+    ///     username = "report"
+    ///     password = "secret"
+    ///     "#,
+    /// )?;
+    /// let pools = Pools::from_config(&config)?;
+    /// assert_eq!(pools.get("orders").unwrap().status().max_size, 20);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_config(config: &PoolsConfig) -> Result<Self, BuildError> {
+        let mut registry = Self::new();
+        for (name, config) in &config.pools {
+            let pool = config.builder().name(name.as_str()).build()?;
+            registry.insert(name.as_str(), pool);
+        }
+        Ok(registry)
+    }
+
+    /// Add a pool under `name`, returning the pool it replaces
+    ///
+    /// The replaced pool is not closed, since it may still be in use.
+    pub fn insert(&mut self, name: impl Into<String>, pool: Pool) -> Option<Pool> {
+        self.pools.insert(name.into(), pool)
+    }
+
+    /// Remove the pool registered under `name`
+    pub fn remove(&mut self, name: &str) -> Option<Pool> {
+        self.pools.remove(name)
+    }
+
+    /// Get the pool registered under `name`
+    pub fn get(&self, name: &str) -> Option<&Pool> {
+        self.pools.get(name)
+    }
+
+    /// Names of the registered pools, in order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.pools.keys().map(String::as_str)
+    }
+
+    /// Registered pools with their names, in order of name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Pool)> {
+        self.pools.iter().map(|(name, pool)| (name.as_str(), pool))
+    }
+
+    /// Number of registered pools
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Check whether no pools are registered
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    /// Status of all pools summed up
+    pub fn status(&self) -> Status {
+        let mut total = Status {
+            max_size: 0,
+            size: 0,
+            available: 0,
+            waiting: 0,
+        };
+        for pool in self.pools.values() {
+            let status = pool.status();
+            total.max_size += status.max_size;
+            total.size += status.size;
+            total.available += status.available;
+            total.waiting += status.waiting;
+        }
+        total
+    }
+
+    /// Statistics of every pool, by name
+    pub fn stats(&self) -> BTreeMap<String, PoolStats> {
+        let stats = self
+            .pools
+            .iter()
+            .map(|(name, pool)| (name.clone(), pool.stats()));
+        stats.collect()
+    }
+
+    /// Close all pools
+    pub fn close(&self) {
+        for pool in self.pools.values() {
+            pool.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PoolBuilder;
+    use oracle_rs::Config;
+
+    fn pool(service: &str, max_size: usize) -> Pool {
+        let config = Config::new("localhost", 1521, service, "user", "pass");
+        PoolBuilder::new(config).max_size(max_size).build().unwrap()
+    }
+
+    #[test]
+    fn test_pool_registry() {
+        let mut registry = PoolRegistry::new();
+        assert!(registry.is_empty());
+        registry.insert("reporting", pool("DWH", 2));
+        registry.insert("orders", pool("ORDERS", 5));
+        assert!(registry.insert("orders", pool("ORDERS", 3)).is_some());
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            ["orders", "reporting"]
+        );
+        assert_eq!(registry.get("orders").unwrap().status().max_size, 3);
+        assert!(registry.get("billing").is_none());
+        assert_eq!(registry.status().max_size, 5);
+        assert_eq!(registry.stats().len(), 2);
+
+        registry.close();
+        assert!(registry.iter().all(|(_, pool)| pool.is_closed()));
+        assert!(registry.remove("reporting").is_some());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_pool_registry_from_config() {
+        let config: PoolsConfig = serde_json::from_str(
+            r#"{"pools": {
+                "orders": {"host": "db", "service_name": "ORDERS",
+                           "username": "app", "password": "p", "max_size": 4},
+                "reporting": {"host": "replica", "port": 1522, "service_name": "DWH",
+                              "username": "report", "password": "p"}
+            }}"#,
+        )
+        .unwrap();
+        let registry = PoolRegistry::from_config(&config).unwrap();
+        assert_eq!(registry.get("orders").unwrap().name(), "orders");
+        assert_eq!(registry.get("orders").unwrap().status().max_size, 4);
+        assert_eq!(registry.get("reporting").unwrap().name(), "reporting");
+    }
+}
@@ -0,0 +1,85 @@
+//! Server result cache
+//!
+//! Oracle can keep query results in the result cache of the server and
+//! answer repeated executions from there until a table they depend on
+//! changes. Queries opt in with the `RESULT_CACHE` hint, added by
+//! [`result_cache_hint()`], or every query of a session uses the cache with
+//! [`ResultCacheMode::Force`].
+
+/// Result cache mode of pooled sessions
+///
+/// See [`PoolBuilder::result_cache_mode()`](crate::PoolBuilder::result_cache_mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResultCacheMode {
+    /// Only queries with the `RESULT_CACHE` hint are cached (Oracle's default)
+    Manual,
+    /// All queries are cached unless they have the `NO_RESULT_CACHE` hint
+    Force,
+}
+
+impl ResultCacheMode {
+    /// Statement setting this mode for a session
+    pub(crate) fn alter_session_sql(self) -> &'static str {
+        match self {
+            ResultCacheMode::Manual => "ALTER SESSION SET RESULT_CACHE_MODE = MANUAL",
+            ResultCacheMode::Force => "ALTER SESSION SET RESULT_CACHE_MODE = FORCE",
+        }
+    }
+}
+
+/// Add the `RESULT_CACHE` hint to a query
+///
+/// The hint goes right after the leading `SELECT`, merged into a hint
+/// comment already there. Other queries, such as those starting with
+/// `WITH`, are wrapped in `SELECT /*+ RESULT_CACHE */ * FROM (...)`.
+///
+/// ```rust
+/// use deadpool_oracle::result_cache_hint;
+///
+/// assert_eq!(
+///     result_cache_hint("SELECT code, name FROM countries"),
+///     "SELECT /*+ RESULT_CACHE */ code, name FROM countries"
+/// );
+/// ```
+pub fn result_cache_hint(sql: &str) -> String {
+    let trimmed = sql.trim_start();
+    let starts_with_select = trimmed
+        .get(..6)
+        .is_some_and(|keyword| keyword.eq_ignore_ascii_case("SELECT"))
+        && !trimmed[6..].starts_with(|c: char| c.is_alphanumeric() || c == '_');
+    if !starts_with_select {
+        return format!("SELECT /*+ RESULT_CACHE */ * FROM ({})", sql);
+    }
+
+    let select_end = sql.len() - trimmed.len() + 6;
+    let (head, rest) = sql.split_at(select_end);
+    match rest.trim_start().strip_prefix("/*+") {
+        Some(hints) => format!("{} /*+ RESULT_CACHE{}", head, hints),
+        None => format!("{} /*+ RESULT_CACHE */{}", head, rest),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_result_cache_hint() {
+        assert_eq!(
+            result_cache_hint("  select * FROM dual"),
+            "  select /*+ RESULT_CACHE */ * FROM dual"
+        );
+        assert_eq!(
+            result_cache_hint("SELECT /*+ INDEX(c c_pk) */ name FROM countries c"),
+            "SELECT /*+ RESULT_CACHE INDEX(c c_pk) */ name FROM countries c"
+        );
+        assert_eq!(
+            result_cache_hint("WITH t AS (SELECT 1 x FROM dual) SELECT x FROM t"),
+            "SELECT /*+ RESULT_CACHE */ * FROM (WITH t AS (SELECT 1 x FROM dual) SELECT x FROM t)"
+        );
+        assert_eq!(
+            result_cache_hint("SELECTED_ROWS"),
+            "SELECT /*+ RESULT_CACHE */ * FROM (SELECTED_ROWS)"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! DML with `RETURNING ... INTO`
+//!
+//! The driver has no support for DML returning binds, so the statement is
+//! wrapped in an anonymous PL/SQL block where the `INTO` targets are ordinary
+//! OUT binds. For array DML the statement is repeated in the block once per
+//! row, [`CHUNK_ROWS`] rows per round trip. Every placeholder occurrence gets
+//! its own bind, so the binds line up with the placeholders however the
+//! statement numbers them.
+
+use crate::named::rewrite_placeholders;
+use oracle_rs::{BindParam, Connection, Error, OracleType, Row, Value};
+
+/// Statements per PL/SQL block
+const CHUNK_ROWS: usize = 100;
+
+/// OUT buffer size for a returned column of `oracle_type`
+fn buffer_size(oracle_type: OracleType) -> u32 {
+    match oracle_type {
+        OracleType::Number | OracleType::BinaryInteger => 22,
+        OracleType::Date => 7,
+        OracleType::Timestamp | OracleType::TimestampTz | OracleType::TimestampLtz => 13,
+        OracleType::BinaryFloat => 4,
+        OracleType::BinaryDouble => 8,
+        OracleType::Raw => 2000,
+        _ => 4000,
+    }
+}
+
+/// A PL/SQL block running the statement for each row
+struct Block {
+    sql: String,
+    binds: Vec<BindParam>,
+    /// Row and returned column of each OUT bind, in bind order
+    outputs: Vec<(usize, usize)>,
+}
+
+impl Block {
+    fn new(sql: &str, rows: &[&[Value]], returning: &[OracleType]) -> Result<Self, Error> {
+        let statement = sql.trim_end().trim_end_matches(';');
+        let mut block = Block {
+            sql: String::from("BEGIN "),
+            binds: Vec::new(),
+            outputs: Vec::new(),
+        };
+
+        for (row_index, row) in rows.iter().enumerate() {
+            let rewritten = rewrite_placeholders(statement, |name| {
+                let n: usize = name.parse().map_err(|_| {
+                    Error::SqlError(format!(
+                        "RETURNING statements take positional binds, found :{}",
+                        name
+                    ))
+                })?;
+                let bind = match n.checked_sub(1) {
+                    Some(i) if i < row.len() => BindParam::input(row[i].clone()),
+                    Some(i) if i < row.len() + returning.len() => {
+                        let column = i - row.len();
+                        block.outputs.push((row_index, column));
+                        let oracle_type = returning[column];
+                        BindParam::output(oracle_type, buffer_size(oracle_type))
+                    }
+                    _ => {
+                        return Err(Error::SqlError(format!(
+                            "no bind value or RETURNING type for :{}",
+                            n
+                        )))
+                    }
+                };
+                block.binds.push(bind);
+                Ok(format!(":{}", block.binds.len()))
+            })?;
+            block.sql.push_str(&rewritten);
+            block.sql.push_str("; ");
+        }
+
+        block.sql.push_str("END;");
+        Ok(block)
+    }
+}
+
+/// Execute `sql` once per row and collect the `RETURNING` values of each
+pub(crate) async fn execute_returning(
+    conn: &Connection,
+    sql: &str,
+    rows: &[&[Value]],
+    returning: &[OracleType],
+) -> Result<Vec<Row>, Error> {
+    let mut returned = Vec::with_capacity(rows.len());
+
+    for chunk in rows.chunks(CHUNK_ROWS) {
+        let block = Block::new(sql, chunk, returning)?;
+        let result = conn.execute_plsql(&block.sql, &block.binds).await?;
+
+        let mut values = vec![vec![Value::Null; returning.len()]; chunk.len()];
+        for ((row, column), value) in block.outputs.into_iter().zip(result.out_values) {
+            values[row][column] = value;
+        }
+        returned.extend(values.into_iter().map(Row::new));
+    }
+
+    Ok(returned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_returning_block() {
+        let rows: Vec<Vec<Value>> = vec![
+            vec![Value::String("a".to_string())],
+            vec![Value::String("b".to_string())],
+        ];
+        let rows: Vec<&[Value]> = rows.iter().map(Vec::as_slice).collect();
+
+        let block = Block::new(
+            "INSERT INTO t (name) VALUES (:1) RETURNING id, created INTO :2, :3;",
+            &rows,
+            &[OracleType::Number, OracleType::Date],
+        )
+        .unwrap();
+
+        assert_eq!(
+            block.sql,
+            "BEGIN INSERT INTO t (name) VALUES (:1) RETURNING id, created INTO :2, :3; \
+             INSERT INTO t (name) VALUES (:4) RETURNING id, created INTO :5, :6; END;"
+        );
+        assert_eq!(block.binds.len(), 6);
+        assert_eq!(block.outputs, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+
+        assert!(Block::new("UPDATE t SET x = :1 RETURNING id INTO :3", &rows, &[]).is_err());
+        assert!(Block::new("UPDATE t SET x = :x RETURNING id INTO :id", &rows, &[]).is_err());
+    }
+}
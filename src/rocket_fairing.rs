@@ -0,0 +1,135 @@
+//! Fairing and request guards for Rocket
+//!
+//! [`Pool::fairing()`] puts the pool into Rocket's managed state and
+//! closes it on shutdown. [`DbConn`] and [`DbTransaction`] are request
+//! guards checking a connection out of the managed pool, which may also
+//! have been added with `Rocket::manage()`.
+
+use crate::{DbConn, DbTransaction, Pool, PoolRejection};
+use rocket::fairing::{self, Fairing, Info, Kind};
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::{Build, Orbit, Rocket};
+
+impl Pool {
+    /// Fairing managing this pool in Rocket's state
+    ///
+    /// ```rust,no_run
+    /// use deadpool_oracle::{DbConn, Pool};
+    ///
+    /// #[rocket::get("/users/count")]
+    /// async fn user_count(conn: DbConn) -> String {
+    ///     match conn.query("SELECT COUNT(*) FROM users", &[]).await {
+    ///         Ok(result) => format!("{:?}", result.rows[0].get_i64(0)),
+    ///         Err(e) => e.to_string(),
+    ///     }
+    /// }
+    ///
+    /// # async fn example(pool: Pool) -> Result<(), rocket::Error> {
+    /// rocket::build()
+    ///     .attach(pool.fairing())
+    ///     .mount("/", rocket::routes![user_count])
+    ///     .launch()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fairing(&self) -> PoolFairing {
+        PoolFairing { pool: self.clone() }
+    }
+}
+
+/// Fairing adding a [`Pool`] to Rocket's managed state, see [`Pool::fairing()`]
+///
+/// The pool is closed when Rocket shuts down, so idle connections are
+/// logged off rather than dropped with the process.
+pub struct PoolFairing {
+    pool: Pool,
+}
+
+#[rocket::async_trait]
+impl Fairing for PoolFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "deadpool_oracle::Pool",
+            kind: Kind::Ignite | Kind::Shutdown,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        Ok(rocket.manage(self.pool.clone()))
+    }
+
+    async fn on_shutdown(&self, _rocket: &Rocket<Orbit>) {
+        self.pool.close();
+    }
+}
+
+/// Outcome of a guard: 503 if the checkout failed, 500 without a managed pool
+///
+/// The error is `None` if no pool is managed.
+fn outcome<T>(result: Option<Result<T, PoolRejection>>) -> Outcome<T, Option<PoolRejection>> {
+    match result {
+        Some(Ok(value)) => Outcome::Success(value),
+        Some(Err(rejection)) => Outcome::Error((Status::ServiceUnavailable, Some(rejection))),
+        None => Outcome::Error((Status::InternalServerError, None)),
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for DbConn {
+    type Error = Option<PoolRejection>;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let result = match req.rocket().state::<Pool>() {
+            Some(pool) => Some(DbConn::checkout(pool).await),
+            None => None,
+        };
+        outcome(result)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for DbTransaction {
+    type Error = Option<PoolRejection>;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let result = match req.rocket().state::<Pool>() {
+            Some(pool) => Some(DbTransaction::begin(pool).await),
+            None => None,
+        };
+        outcome(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::tests::unreachable_pool;
+    use rocket::local::asynchronous::Client;
+
+    #[rocket::get("/")]
+    fn index(_conn: DbConn) -> &'static str {
+        "called"
+    }
+
+    async fn client(rocket: Rocket<Build>) -> Client {
+        let rocket = rocket.mount("/", rocket::routes![index]);
+        Client::untracked(rocket).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_pool_rejected_with_503() {
+        let rocket = rocket::custom(rocket::Config::debug_default());
+        let client = client(rocket.attach(unreachable_pool().fairing())).await;
+        let response = client.get("/").dispatch().await;
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+    }
+
+    #[tokio::test]
+    async fn test_missing_pool_rejected_with_500() {
+        let client = client(rocket::custom(rocket::Config::debug_default())).await;
+        let response = client.get("/").dispatch().await;
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
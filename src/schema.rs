@@ -0,0 +1,89 @@
+//! Switching the current schema of pooled sessions
+//!
+//! `ALTER SESSION SET CURRENT_SCHEMA` changes the schema unqualified names
+//! resolve to without changing the user, so one pool of a technical user
+//! with grants on several schemas can serve all of them.
+//! [`Pool::get_with_schema()`](crate::Pool::get_with_schema) switches the
+//! connection it hands out; the manager remembers the sessions it switched
+//! and moves them back to the user's own schema when they are recycled.
+
+use crate::page::is_identifier;
+use oracle_rs::{Connection, Error};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Block moving a session back to the schema of its user
+const RESET_SQL: &str = "BEGIN EXECUTE IMMEDIATE 'ALTER SESSION SET CURRENT_SCHEMA = ' \
+                         || SYS_CONTEXT('USERENV', 'SESSION_USER'); END;";
+
+/// Current schemas of the connections that were switched, by connection id
+#[derive(Debug, Default)]
+pub(crate) struct SessionSchemas {
+    switched: Mutex<HashMap<u32, String>>,
+}
+
+impl SessionSchemas {
+    /// Schema a connection was switched to, if it was
+    pub(crate) fn current(&self, connection_id: u32) -> Option<String> {
+        self.switched.lock().unwrap().get(&connection_id).cloned()
+    }
+
+    pub(crate) fn set(&self, connection_id: u32, schema: String) {
+        self.switched.lock().unwrap().insert(connection_id, schema);
+    }
+
+    /// Forget a connection that is back in its own schema or left the pool
+    pub(crate) fn remove(&self, connection_id: u32) {
+        self.switched.lock().unwrap().remove(&connection_id);
+    }
+}
+
+/// Normalize a schema name, rejecting anything but a plain identifier
+///
+/// The name ends up in DDL, where it cannot be bound.
+pub(crate) fn schema_name(name: &str) -> Result<String, Error> {
+    if !is_identifier(name) || name.contains('.') {
+        return Err(Error::SqlError(format!("invalid schema name: {}", name)));
+    }
+    Ok(name.to_ascii_uppercase())
+}
+
+/// Statement switching a session to `schema`, a name from `schema_name()`
+pub(crate) fn alter_session_sql(schema: &str) -> String {
+    format!("ALTER SESSION SET CURRENT_SCHEMA = {}", schema)
+}
+
+/// Move a session back to the schema of its user
+pub(crate) async fn reset(conn: &Connection) -> Result<(), Error> {
+    conn.execute(RESET_SQL, &[]).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_name() {
+        assert_eq!(schema_name("hr").unwrap(), "HR");
+        assert_eq!(schema_name("APP_DATA$1").unwrap(), "APP_DATA$1");
+        assert!(schema_name("").is_err());
+        assert!(schema_name("1HR").is_err());
+        assert!(schema_name("HR; DROP USER app").is_err());
+        assert!(schema_name("HR.EMPLOYEES").is_err());
+        assert_eq!(
+            alter_session_sql("HR"),
+            "ALTER SESSION SET CURRENT_SCHEMA = HR"
+        );
+    }
+
+    #[test]
+    fn test_session_schemas() {
+        let schemas = SessionSchemas::default();
+        assert_eq!(schemas.current(1), None);
+        schemas.set(1, "HR".to_string());
+        assert_eq!(schemas.current(1).as_deref(), Some("HR"));
+        schemas.remove(1);
+        assert_eq!(schemas.current(1), None);
+    }
+}
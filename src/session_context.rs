@@ -0,0 +1,67 @@
+//! Application contexts of pooled sessions
+//!
+//! Virtual Private Database policies filter rows by application context
+//! attributes, such as the tenant or role of the end user, which a trusted
+//! package sets with `DBMS_SESSION.SET_CONTEXT`. With pooled sessions, an
+//! attribute left behind by one request would apply to the next one. A
+//! hook registered with
+//! [`PoolBuilder::session_context()`](crate::PoolBuilder::session_context)
+//! sets the context on every checkout, and the manager runs the clearing
+//! statement given with it when the connection is recycled, before anyone
+//! else gets the session.
+
+use crate::Object;
+use oracle_rs::{Connection, Error};
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// Future returned by a session context hook
+pub type SessionContextFuture<'c> = Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'c>>;
+
+/// Callback setting the application context of a connection being checked out
+pub(crate) type SessionContextHook =
+    dyn for<'c> Fn(&'c Object) -> SessionContextFuture<'c> + Send + Sync;
+
+/// The hook of a pool, its clearing statement and the sessions it ran on
+pub(crate) struct SessionContext {
+    hook: Arc<SessionContextHook>,
+    clear_sql: String,
+    /// Connections whose context may be set, by connection id
+    sessions: Mutex<HashSet<u32>>,
+}
+
+impl SessionContext {
+    pub(crate) fn new(hook: Arc<SessionContextHook>, clear_sql: String) -> Self {
+        Self {
+            hook,
+            clear_sql,
+            sessions: Mutex::default(),
+        }
+    }
+
+    /// Run the hook on a connection being checked out
+    pub(crate) async fn set(&self, conn: &Object) -> Result<(), Error> {
+        // Marked first, so a session left half set up is still cleared
+        self.sessions.lock().unwrap().insert(conn.id());
+        (self.hook)(conn).await
+    }
+
+    /// Whether a connection has to be cleared before it is reused
+    pub(crate) fn is_set(&self, connection_id: u32) -> bool {
+        self.sessions.lock().unwrap().contains(&connection_id)
+    }
+
+    /// Run the clearing statement on a connection being recycled
+    pub(crate) async fn clear(&self, conn: &Connection) -> Result<(), Error> {
+        conn.execute(&self.clear_sql, &[]).await?;
+        self.remove(conn.id());
+        Ok(())
+    }
+
+    /// Forget a connection that was cleared or left the pool
+    pub(crate) fn remove(&self, connection_id: u32) {
+        self.sessions.lock().unwrap().remove(&connection_id);
+    }
+}
@@ -0,0 +1,239 @@
+//! Session parameters of pooled sessions
+//!
+//! [`PoolBuilder::session_params()`](crate::PoolBuilder::session_params)
+//! takes name and value pairs, such as `optimizer_mode` and
+//! `FIRST_ROWS_10`, and sets them all with one `ALTER SESSION` when a
+//! connection is created.
+//!
+//! [`Object::with_session_setting()`](crate::Object::with_session_setting)
+//! changes a parameter for a single checkout, for example to gather row
+//! source statistics or enable SQL trace on a production connection. The
+//! value it replaces is read first, from `NLS_SESSION_PARAMETERS` for NLS
+//! parameters and from `V$PARAMETER` for the others, and the manager sets
+//! it back when the connection is recycled, before anyone else gets the
+//! session.
+
+use crate::page::is_identifier;
+use oracle_rs::{Connection, Error, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const CURRENT_VALUE_SQL: &str = "SELECT value FROM v$parameter WHERE name = :1";
+
+/// `V$PARAMETER` has the instance's NLS settings, not the session's
+const CURRENT_NLS_VALUE_SQL: &str =
+    "SELECT value FROM nls_session_parameters WHERE parameter = UPPER(:1)";
+
+/// A changed parameter and the value it replaced, `None` if it had none
+type Changed = (String, Option<String>);
+
+/// Parameters changed for a checkout and the values they replaced, by
+/// connection id
+#[derive(Debug, Default)]
+pub(crate) struct SessionSettings {
+    changed: Mutex<HashMap<u32, Vec<Changed>>>,
+}
+
+impl SessionSettings {
+    /// Change a parameter of a session, remembering the value to restore
+    ///
+    /// Only the first change of a parameter in a checkout reads its value,
+    /// so later ones still restore the value from before the checkout.
+    pub(crate) async fn set(
+        &self,
+        conn: &Connection,
+        name: &str,
+        value: &str,
+    ) -> Result<(), Error> {
+        let name = name.to_ascii_lowercase();
+        let sql = setting_sql(&name, value)?;
+        if !self.is_changed(conn.id(), &name) {
+            let previous = current_value(conn, &name).await?;
+            self.record(conn.id(), name, previous);
+        }
+        conn.execute(&sql, &[]).await?;
+        Ok(())
+    }
+
+    /// Statement setting the changed parameters of a session back to their
+    /// previous values, forgetting them
+    pub(crate) fn take_restore(&self, connection_id: u32) -> Result<Option<String>, Error> {
+        let changed = self.changed.lock().unwrap().remove(&connection_id);
+        restore_sql(&changed.unwrap_or_default())
+    }
+
+    /// Forget a connection that left the pool
+    pub(crate) fn remove(&self, connection_id: u32) {
+        self.changed.lock().unwrap().remove(&connection_id);
+    }
+
+    fn is_changed(&self, connection_id: u32, name: &str) -> bool {
+        let changed = self.changed.lock().unwrap();
+        let Some(settings) = changed.get(&connection_id) else {
+            return false;
+        };
+        settings.iter().any(|(changed, _)| changed == name)
+    }
+
+    fn record(&self, connection_id: u32, name: String, previous: Option<String>) {
+        let mut changed = self.changed.lock().unwrap();
+        changed
+            .entry(connection_id)
+            .or_default()
+            .push((name, previous));
+    }
+}
+
+/// Value of a parameter in a session, `None` if it has none
+async fn current_value(conn: &Connection, name: &str) -> Result<Option<String>, Error> {
+    let sql = match name.starts_with("nls_") {
+        true => CURRENT_NLS_VALUE_SQL,
+        false => CURRENT_VALUE_SQL,
+    };
+    let result = conn.query(sql, &[Value::from(name)]).await?;
+    let Some(row) = result.rows.first() else {
+        return Err(Error::SqlError(format!(
+            "unknown session parameter: {}",
+            name
+        )));
+    };
+    Ok(row.get_string(0).map(str::to_string))
+}
+
+/// Statement setting changed parameters back to the values they replaced
+///
+/// Fails if one of them had no value, as `ALTER SESSION` can't unset it.
+fn restore_sql(changed: &[Changed]) -> Result<Option<String>, Error> {
+    let mut params = Vec::with_capacity(changed.len());
+    for (name, previous) in changed {
+        let Some(previous) = previous else {
+            return Err(Error::SqlError(format!(
+                "session parameter {} had no value to restore",
+                name
+            )));
+        };
+        params.push((name.clone(), previous.clone()));
+    }
+    alter_session_sql(&params)
+}
+
+/// Statement setting `params`, if there are any
+///
+/// Names must be plain identifiers. Values that are plain words or integers,
+/// like `FIRST_ROWS_10`, `TRUE` or `100`, are used as they are; anything
+/// else, such as `19.1.0`, is quoted as a string.
+pub(crate) fn alter_session_sql(params: &[(String, String)]) -> Result<Option<String>, Error> {
+    if params.is_empty() {
+        return Ok(None);
+    }
+    let mut settings = Vec::with_capacity(params.len());
+    for (name, value) in params {
+        settings.push(setting(name, value)?);
+    }
+    Ok(Some(format!("ALTER SESSION SET {}", settings.join(" "))))
+}
+
+/// Statement setting a single parameter
+fn setting_sql(name: &str, value: &str) -> Result<String, Error> {
+    Ok(format!("ALTER SESSION SET {}", setting(name, value)?))
+}
+
+/// `name = value` of an `ALTER SESSION`
+fn setting(name: &str, value: &str) -> Result<String, Error> {
+    if !is_identifier(name) || name.contains('.') {
+        return Err(Error::SqlError(format!(
+            "invalid session parameter name: {}",
+            name
+        )));
+    }
+    let word = value.starts_with(|c: char| c.is_ascii_alphabetic())
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let integer = !value.is_empty() && value.chars().all(|c| c.is_ascii_digit());
+    let bare = word || integer;
+    if bare {
+        Ok(format!("{} = {}", name, value))
+    } else {
+        Ok(format!("{} = '{}'", name, value.replace('\'', "''")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_alter_session_sql() {
+        assert_eq!(alter_session_sql(&[]).unwrap(), None);
+
+        let sql = alter_session_sql(&params(&[
+            ("optimizer_mode", "FIRST_ROWS_10"),
+            ("optimizer_index_cost_adj", "50"),
+            ("plsql_code_type", "NATIVE"),
+            ("nls_sort", "xgerman ci"),
+            ("nls_date_format", "DD'MM"),
+            ("optimizer_features_enable", "19.1.0"),
+            ("nls_numeric_characters", ".,"),
+        ]));
+        assert_eq!(
+            sql.unwrap().unwrap(),
+            "ALTER SESSION SET optimizer_mode = FIRST_ROWS_10 optimizer_index_cost_adj = 50 \
+             plsql_code_type = NATIVE nls_sort = 'xgerman ci' nls_date_format = 'DD''MM' \
+             optimizer_features_enable = '19.1.0' nls_numeric_characters = '.,'"
+        );
+
+        assert!(alter_session_sql(&params(&[("", "1")])).is_err());
+        assert!(alter_session_sql(&params(&[("sql_trace = true --", "1")])).is_err());
+        assert!(alter_session_sql(&params(&[("_hidden", "1")])).is_err());
+    }
+
+    #[test]
+    fn test_session_settings() {
+        let settings = SessionSettings::default();
+        assert_eq!(settings.take_restore(1).unwrap(), None);
+
+        let previous = Some("TYPICAL".to_string());
+        settings.record(1, "statistics_level".to_string(), previous);
+        assert!(settings.is_changed(1, "statistics_level"));
+        assert!(!settings.is_changed(1, "sql_trace"));
+        assert!(!settings.is_changed(2, "statistics_level"));
+
+        let restore = settings.take_restore(1).unwrap();
+        assert_eq!(
+            restore.as_deref(),
+            Some("ALTER SESSION SET statistics_level = TYPICAL")
+        );
+        assert_eq!(settings.take_restore(1).unwrap(), None);
+
+        settings.record(2, "sql_trace".to_string(), None);
+        settings.remove(2);
+        assert!(!settings.is_changed(2, "sql_trace"));
+    }
+
+    #[test]
+    fn test_restore_sql() {
+        assert_eq!(restore_sql(&[]).unwrap(), None);
+
+        let changed = [
+            ("statistics_level".to_string(), Some("TYPICAL".to_string())),
+            ("nls_date_format".to_string(), Some("DD-MON-RR".to_string())),
+        ];
+        assert_eq!(
+            restore_sql(&changed).unwrap().unwrap(),
+            "ALTER SESSION SET statistics_level = TYPICAL nls_date_format = 'DD-MON-RR'"
+        );
+        assert_eq!(
+            setting_sql("sql_trace", "TRUE").unwrap(),
+            "ALTER SESSION SET sql_trace = TRUE"
+        );
+
+        // A parameter without a value can't be set back
+        assert!(restore_sql(&[("nls_sort".to_string(), None)]).is_err());
+    }
+}
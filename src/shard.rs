@@ -0,0 +1,107 @@
+//! Routing checkouts to the shard holding a sharding key
+//!
+//! With Oracle Sharding, statements for a single sharding key run fastest
+//! on a connection to the shard that holds the key. A pool configured with
+//! [`PoolBuilder::shards()`](crate::PoolBuilder::shards) keeps a sub-pool
+//! per shard and a [`ShardResolver`] that maps keys to shards, so
+//! [`Pool::get_for_shard()`](crate::Pool::get_for_shard) hands out a
+//! connection to the right one. The pool itself stays connected to the
+//! shard catalog for cross-shard queries.
+
+use crate::Pool;
+use oracle_rs::Value;
+use std::sync::Arc;
+
+/// Maps sharding keys to shards
+///
+/// Implemented for closures taking the key and the number of shards.
+pub trait ShardResolver: Send + Sync + 'static {
+    /// Index of the shard holding `key`, in the order the shards were
+    /// configured, or `None` if no shard holds it
+    fn resolve(&self, key: &Value, shards: usize) -> Option<usize>;
+}
+
+impl<F> ShardResolver for F
+where
+    F: Fn(&Value, usize) -> Option<usize> + Send + Sync + 'static,
+{
+    fn resolve(&self, key: &Value, shards: usize) -> Option<usize> {
+        self(key, shards)
+    }
+}
+
+/// Spreads keys evenly over the shards by a stable hash
+///
+/// Suits shards populated by the application with the same hash, not
+/// Oracle's system-managed consistent hash, whose chunk placement is only
+/// known to the shard directors. Integer, string and raw keys are
+/// supported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HashShardResolver;
+
+impl ShardResolver for HashShardResolver {
+    fn resolve(&self, key: &Value, shards: usize) -> Option<usize> {
+        let hash = match key {
+            Value::Integer(n) => fnv1a(&n.to_be_bytes()),
+            Value::String(s) => fnv1a(s.as_bytes()),
+            Value::Bytes(b) => fnv1a(b),
+            _ => return None,
+        };
+        (shards > 0).then(|| (hash % shards as u64) as usize)
+    }
+}
+
+/// 64-bit FNV-1a, stable across processes and releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Sub-pools of the shards and the resolver choosing between them
+pub(crate) struct Shards {
+    pub(crate) pools: Vec<Pool>,
+    pub(crate) resolver: Arc<dyn ShardResolver>,
+}
+
+impl Shards {
+    /// Sub-pool of the shard holding `key`
+    pub(crate) fn pool_for(&self, key: &Value) -> Option<&Pool> {
+        let index = self.resolver.resolve(key, self.pools.len())?;
+        self.pools.get(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_shard_resolver() {
+        let resolver = HashShardResolver;
+        let key = Value::String("customer-42".to_string());
+        let shard = resolver.resolve(&key, 4).unwrap();
+        assert!(shard < 4);
+        assert_eq!(resolver.resolve(&key, 4), Some(shard));
+        assert_eq!(resolver.resolve(&key, 0), None);
+        assert_eq!(resolver.resolve(&Value::Null, 4), None);
+
+        // Keys spread over all shards
+        let mut used = [false; 4];
+        for id in 0..100 {
+            used[resolver.resolve(&Value::Integer(id), 4).unwrap()] = true;
+        }
+        assert!(used.iter().all(|&used| used));
+    }
+
+    #[test]
+    fn test_closure_shard_resolver() {
+        let by_region = |key: &Value, _shards: usize| match key {
+            Value::String(region) if region == "EU" => Some(0),
+            Value::String(region) if region == "US" => Some(1),
+            _ => None,
+        };
+        assert_eq!(by_region.resolve(&Value::String("US".into()), 2), Some(1));
+        assert_eq!(by_region.resolve(&Value::String("APAC".into()), 2), None);
+    }
+}
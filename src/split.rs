@@ -0,0 +1,213 @@
+//! Read/write splitting across a primary and its standbys
+//!
+//! Active Data Guard standbys can serve queries while the primary takes the
+//! writes. [`SplitPool`] keeps one pool for the primary and one per replica
+//! and routes checkouts accordingly. Reads go round-robin to the replicas,
+//! skipping those whose circuit breaker is open and those that failed a
+//! checkout recently, and fall back to the primary when no replica can
+//! serve them.
+
+use crate::{Object, Pool, PoolError};
+use deadpool::managed::{self, TimeoutType};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Primary pool for writes with replica pools for reads
+///
+/// Cloning is cheap; clones share the pools and the replica health.
+///
+/// ```rust,no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use deadpool_oracle::{PoolBuilder, SplitPool};
+/// use oracle_rs::Config;
+///
+/// let primary = PoolBuilder::new(Config::new("db-1", 1521, "ORDERS", "app", "secret")).build()?;
+/// let standby = PoolBuilder::new(Config::new("db-2", 1521, "ORDERS_RO", "app", "secret")).build()?;
+/// let pools = SplitPool::new(primary, vec![standby]);
+///
+/// let conn = pools.get_read().await?;
+/// let rows = conn.query("SELECT id, total FROM orders", &[]).await?;
+///
+/// let conn = pools.get_write().await?;
+/// conn.execute("UPDATE orders SET status = 'SHIPPED' WHERE id = 1", &[]).await?;
+/// conn.commit().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SplitPool {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    primary: Pool,
+    replicas: Vec<Replica>,
+    next: AtomicUsize,
+    cool_down: Duration,
+    primary_fallback: bool,
+}
+
+struct Replica {
+    pool: Pool,
+    /// Until when the replica is skipped after a failed checkout
+    down_until: Mutex<Option<Instant>>,
+}
+
+impl Replica {
+    fn is_available(&self) -> bool {
+        if self.pool.is_circuit_open() {
+            return false;
+        }
+        let down_until = *self.down_until.lock().unwrap();
+        down_until.map_or(true, |until| Instant::now() >= until)
+    }
+}
+
+impl SplitPool {
+    /// Route writes to `primary` and reads to `replicas`
+    ///
+    /// Without replicas, reads go to the primary as well.
+    pub fn new(primary: Pool, replicas: Vec<Pool>) -> Self {
+        let replicas = replicas
+            .into_iter()
+            .map(|pool| Replica {
+                pool,
+                down_until: Mutex::new(None),
+            })
+            .collect();
+        Self {
+            inner: Arc::new(Inner {
+                primary,
+                replicas,
+                next: AtomicUsize::new(0),
+                cool_down: Duration::from_secs(5),
+                primary_fallback: true,
+            }),
+        }
+    }
+
+    /// Set how long a replica is skipped after a failed checkout
+    ///
+    /// Default is 5 seconds.
+    pub fn with_replica_cool_down(mut self, cool_down: Duration) -> Self {
+        self.inner_mut().cool_down = cool_down;
+        self
+    }
+
+    /// Set whether reads go to the primary when no replica can serve them
+    ///
+    /// When disabled, `get_read()` returns the error of the last replica
+    /// tried instead. Default is enabled.
+    pub fn with_primary_fallback(mut self, enabled: bool) -> Self {
+        self.inner_mut().primary_fallback = enabled;
+        self
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner {
+        Arc::get_mut(&mut self.inner).expect("SplitPool configured after cloning")
+    }
+
+    /// Get a connection to the primary
+    pub async fn get_write(&self) -> Result<Object, PoolError> {
+        self.inner.primary.get().await
+    }
+
+    /// Get a connection to a replica, or the primary if none is available
+    ///
+    /// Replicas are tried round-robin. One that fails to hand out a
+    /// connection for any other reason than being busy is skipped by later
+    /// reads for the cool-down period. Connections from a replica are
+    /// read-only; writes on them fail with ORA-16000.
+    pub async fn get_read(&self) -> Result<Object, PoolError> {
+        let replicas = &self.inner.replicas;
+        let start = self.inner.next.fetch_add(1, Ordering::Relaxed);
+        let mut last_error = None;
+        for offset in 0..replicas.len() {
+            let replica = &replicas[(start + offset) % replicas.len()];
+            if !replica.is_available() {
+                continue;
+            }
+            match replica.pool.get().await {
+                Ok(conn) => {
+                    *replica.down_until.lock().unwrap() = None;
+                    return Ok(conn);
+                }
+                Err(err) => {
+                    if !matches!(err, managed::PoolError::Timeout(TimeoutType::Wait)) {
+                        let until = Instant::now() + self.inner.cool_down;
+                        *replica.down_until.lock().unwrap() = Some(until);
+                    }
+                    last_error = Some(err);
+                }
+            }
+        }
+        match last_error {
+            Some(err) if !self.inner.primary_fallback => Err(err),
+            _ => self.inner.primary.get().await,
+        }
+    }
+
+    /// The primary pool
+    pub fn primary(&self) -> &Pool {
+        &self.inner.primary
+    }
+
+    /// The replica pools, in the order given
+    pub fn replicas(&self) -> impl Iterator<Item = &Pool> {
+        self.inner.replicas.iter().map(|replica| &replica.pool)
+    }
+
+    /// Number of replicas currently eligible for reads
+    pub fn available_replicas(&self) -> usize {
+        let replicas = self.inner.replicas.iter();
+        replicas.filter(|replica| replica.is_available()).count()
+    }
+
+    /// Close the primary and all replicas
+    pub fn close(&self) {
+        self.inner.primary.close();
+        for replica in &self.inner.replicas {
+            replica.pool.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PoolBuilder;
+    use oracle_rs::Config;
+
+    fn pool(port: u16) -> Pool {
+        let config = Config::new("127.0.0.1", port, "FREEPDB1", "user", "pass");
+        PoolBuilder::new(config)
+            .max_size(1)
+            .create_timeout(Some(Duration::from_secs(2)))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_split_pool_skips_failed_replicas() {
+        // Nothing listens on port 1, so every checkout is refused
+        let pools = SplitPool::new(pool(1), vec![pool(1), pool(1)])
+            .with_replica_cool_down(Duration::from_secs(60));
+        assert_eq!(pools.available_replicas(), 2);
+        assert_eq!(pools.replicas().count(), 2);
+
+        // Both replicas are tried, then the primary
+        assert!(pools.get_read().await.is_err());
+        assert_eq!(pools.available_replicas(), 0);
+        assert!(pools.get_read().await.is_err());
+        assert!(pools.get_write().await.is_err());
+        assert_eq!(pools.primary().stats().create_failures, 3);
+
+        let pools = SplitPool::new(pool(1), vec![pool(1)]).with_primary_fallback(false);
+        assert!(pools.get_read().await.is_err());
+        assert_eq!(pools.primary().stats().create_failures, 0);
+
+        pools.close();
+        assert!(pools.primary().is_closed());
+    }
+}
@@ -0,0 +1,154 @@
+//! Statement cache accounting
+//!
+//! The driver keeps an LRU cache of parsed statements on every connection,
+//! keyed by SQL text. Pooled connections are reused, so cached statements
+//! survive checkouts, but the driver does not report hits. The pool mirrors
+//! each connection's cache with an LRU of SQL texts of the same capacity,
+//! fed by the statements run through `Object`, and counts hits and misses
+//! against it. DDL is never cached by the driver and is not counted.
+//!
+//! Queries can also be put into the cache of a new connection up front, see
+//! [`prepare`].
+
+use oracle_rs::{Connection, Error, Statement, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Mirror of the statement caches of all connections of a pool
+#[derive(Debug, Default)]
+pub(crate) struct StatementCaches {
+    capacity: usize,
+    connections: Mutex<HashMap<u32, VecDeque<String>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl StatementCaches {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ..Self::default()
+        }
+    }
+
+    /// Record a statement run on a connection
+    pub(crate) fn record(&self, connection_id: u32, sql: &str) {
+        if self.capacity == 0 || Statement::new(sql).is_ddl() {
+            return;
+        }
+
+        let mut connections = self.connections.lock().unwrap();
+        let entries = connections.entry(connection_id).or_default();
+        let counter = match entries.iter().position(|cached| cached == sql) {
+            Some(index) => {
+                let cached = entries.remove(index).expect("index in range");
+                entries.push_back(cached);
+                &self.hits
+            }
+            None => {
+                if entries.len() == self.capacity {
+                    entries.pop_front();
+                }
+                entries.push_back(sql.to_string());
+                &self.misses
+            }
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Forget a connection that left the pool
+    pub(crate) fn remove(&self, connection_id: u32) {
+        self.connections.lock().unwrap().remove(&connection_id);
+    }
+
+    pub(crate) fn snapshot(&self) -> StatementCacheMetrics {
+        StatementCacheMetrics {
+            capacity: self.capacity,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Parse a query into the statement cache of `conn`
+///
+/// The driver has no parse-only call, so the query is executed with every
+/// bind set to NULL and the rows are discarded.
+pub(crate) async fn prepare(conn: &Connection, sql: &str) -> Result<(), Error> {
+    conn.query(sql, &prepare_params(sql)?).await?;
+    Ok(())
+}
+
+/// NULL binds for each placeholder of the query `sql`
+///
+/// Only queries are accepted, as executing anything else changes data.
+fn prepare_params(sql: &str) -> Result<Vec<Value>, Error> {
+    let statement = Statement::new(sql);
+    if !statement.is_query() {
+        return Err(Error::SqlError(format!(
+            "only queries can be prepared, got: {}",
+            sql
+        )));
+    }
+    Ok(vec![Value::Null; statement.bind_info().len()])
+}
+
+/// Statement cache hits and misses across all connections
+///
+/// Part of [`PoolStats`](crate::PoolStats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatementCacheMetrics {
+    /// Statements cached per connection; 0 if caching is disabled
+    pub capacity: usize,
+    /// Statements found in their connection's cache, skipping the parse
+    pub hits: u64,
+    /// Statements that had to be parsed
+    pub misses: u64,
+}
+
+impl StatementCacheMetrics {
+    /// Fraction of statements served from the cache, if any were run
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statement_cache_lru() {
+        let caches = StatementCaches::new(2);
+        caches.record(1, "SELECT 1 FROM DUAL");
+        caches.record(1, "SELECT 2 FROM DUAL");
+        caches.record(1, "SELECT 1 FROM DUAL");
+        // Evicts SELECT 2, the least recently used
+        caches.record(1, "SELECT 3 FROM DUAL");
+        caches.record(1, "SELECT 2 FROM DUAL");
+        // Caches are per connection
+        caches.record(2, "SELECT 1 FROM DUAL");
+        caches.record(1, "CREATE TABLE t (x NUMBER)");
+
+        let metrics = caches.snapshot();
+        assert_eq!((metrics.hits, metrics.misses), (1, 5));
+        assert_eq!(metrics.hit_ratio(), Some(1.0 / 6.0));
+
+        caches.remove(1);
+        caches.record(1, "SELECT 2 FROM DUAL");
+        assert_eq!(caches.snapshot().misses, 6);
+    }
+
+    #[test]
+    fn test_prepare_params() {
+        let params = prepare_params("SELECT name FROM emp WHERE id = :1 OR boss = :1").unwrap();
+        assert_eq!(params.len(), 2);
+        assert!(params.iter().all(Value::is_null));
+
+        assert!(prepare_params("WITH t AS (SELECT 1 x FROM DUAL) SELECT x FROM t").is_ok());
+        assert!(prepare_params("DELETE FROM emp WHERE id = :1").is_err());
+        assert!(prepare_params("BEGIN purge; END;").is_err());
+    }
+}
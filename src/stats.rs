@@ -0,0 +1,570 @@
+//! Statistics collected by the connection manager
+//!
+//! Counters are plain atomics updated on the hot path; snapshots are cheap
+//! copies that can be taken at any time from [`Pool`](crate::Pool).
+
+use crate::statement_cache::StatementCacheMetrics;
+use deadpool::managed::Metrics;
+use deadpool::Status;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds of the latency histogram buckets
+///
+/// Values above the last bound are counted in an extra overflow bucket.
+const BUCKET_BOUNDS: [Duration; 12] = [
+    Duration::from_millis(1),
+    Duration::from_millis(2),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+];
+
+/// Lock-free latency histogram with fixed buckets
+#[derive(Default)]
+pub(crate) struct Histogram {
+    buckets: [AtomicU64; BUCKET_BOUNDS.len() + 1],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    /// Record a single observation
+    pub(crate) fn record(&self, value: Duration) {
+        let index = BUCKET_BOUNDS
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(BUCKET_BOUNDS.len());
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(value.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Take a point-in-time copy of the histogram
+    pub(crate) fn snapshot(&self) -> LatencyHistogram {
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let bound = BUCKET_BOUNDS.get(i).copied().unwrap_or(Duration::MAX);
+                (bound, count.load(Ordering::Relaxed))
+            })
+            .collect();
+
+        LatencyHistogram {
+            count: self.count.load(Ordering::Relaxed),
+            sum: Duration::from_micros(self.sum_micros.load(Ordering::Relaxed)),
+            buckets,
+        }
+    }
+}
+
+/// Snapshot of a latency histogram
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Number of recorded observations
+    pub count: u64,
+    /// Sum of all recorded observations
+    pub sum: Duration,
+    /// Buckets as `(upper bound, count)` pairs
+    ///
+    /// The last bucket has an upper bound of `Duration::MAX` and holds
+    /// everything above the largest finite bound.
+    pub buckets: Vec<(Duration, u64)>,
+}
+
+impl LatencyHistogram {
+    /// Mean of all recorded observations, or `None` if nothing was recorded
+    pub fn mean(&self) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.sum / self.count as u32)
+    }
+
+    /// Approximate percentile (0.0 to 1.0) of the recorded observations
+    ///
+    /// Returns the upper bound of the bucket that contains the percentile,
+    /// or `None` if nothing was recorded.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let target = ((self.count as f64) * p.clamp(0.0, 1.0)).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bound, count) in &self.buckets {
+            seen += count;
+            if seen >= target {
+                return Some(*bound);
+            }
+        }
+        self.buckets.last().map(|(bound, _)| *bound)
+    }
+}
+
+/// Reason a connection was evicted from the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EvictionReason {
+    /// The connection was already marked closed
+    Closed,
+    /// The server-side session was killed or timed out
+    SessionKilled,
+    /// The connection failed its health check
+    PingFailed,
+    /// The connection was retired by `Pool::drain_and_refresh()`
+    Retired,
+    /// Session state changed by a borrower could not be reset
+    ResetFailed,
+    /// The connection was idle longer than the idle timeout, or closed by
+    /// `Pool::close_all_idle()`
+    Idle,
+    /// The session's user or roles differ from the expected identity
+    IdentityMismatch,
+}
+
+/// Counters for connection recycling
+#[derive(Default)]
+pub(crate) struct RecycleStats {
+    duration: Histogram,
+    rollback_failures: AtomicU64,
+    ping_failures: AtomicU64,
+    evicted_closed: AtomicU64,
+    evicted_session_killed: AtomicU64,
+    evicted_ping_failed: AtomicU64,
+    evicted_retired: AtomicU64,
+    evicted_reset_failed: AtomicU64,
+    evicted_idle: AtomicU64,
+    evicted_identity_mismatch: AtomicU64,
+}
+
+impl RecycleStats {
+    pub(crate) fn record_duration(&self, duration: Duration) {
+        self.duration.record(duration);
+    }
+
+    pub(crate) fn record_rollback_failure(&self) {
+        self.rollback_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_ping_failure(&self) {
+        self.ping_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_eviction(&self, reason: EvictionReason) {
+        let counter = match reason {
+            EvictionReason::Closed => &self.evicted_closed,
+            EvictionReason::SessionKilled => &self.evicted_session_killed,
+            EvictionReason::PingFailed => &self.evicted_ping_failed,
+            EvictionReason::Retired => &self.evicted_retired,
+            EvictionReason::ResetFailed => &self.evicted_reset_failed,
+            EvictionReason::Idle => &self.evicted_idle,
+            EvictionReason::IdentityMismatch => &self.evicted_identity_mismatch,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> RecycleMetrics {
+        RecycleMetrics {
+            duration: self.duration.snapshot(),
+            rollback_failures: self.rollback_failures.load(Ordering::Relaxed),
+            ping_failures: self.ping_failures.load(Ordering::Relaxed),
+            evicted_closed: self.evicted_closed.load(Ordering::Relaxed),
+            evicted_session_killed: self.evicted_session_killed.load(Ordering::Relaxed),
+            evicted_ping_failed: self.evicted_ping_failed.load(Ordering::Relaxed),
+            evicted_retired: self.evicted_retired.load(Ordering::Relaxed),
+            evicted_reset_failed: self.evicted_reset_failed.load(Ordering::Relaxed),
+            evicted_idle: self.evicted_idle.load(Ordering::Relaxed),
+            evicted_identity_mismatch: self.evicted_identity_mismatch.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Snapshot of recycle outcomes since the pool was created
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecycleMetrics {
+    /// Time spent in `recycle()`, a single rollback round trip
+    pub duration: LatencyHistogram,
+    /// Number of recycle rollbacks rejected by the server
+    pub rollback_failures: u64,
+    /// Number of recycles that could not reach the server
+    pub ping_failures: u64,
+    /// Connections evicted because they were already closed
+    pub evicted_closed: u64,
+    /// Connections evicted because their session was killed
+    pub evicted_session_killed: u64,
+    /// Connections evicted because the health check failed
+    pub evicted_ping_failed: u64,
+    /// Connections closed because the pool was drained
+    pub evicted_retired: u64,
+    /// Connections evicted because their session state could not be reset
+    pub evicted_reset_failed: u64,
+    /// Connections closed because they were idle
+    pub evicted_idle: u64,
+    /// Connections evicted because their user or roles were not the expected ones
+    pub evicted_identity_mismatch: u64,
+}
+
+impl RecycleMetrics {
+    /// Number of recycle attempts
+    pub fn recycles(&self) -> u64 {
+        self.duration.count
+    }
+
+    /// Total number of evicted connections
+    pub fn evictions(&self) -> u64 {
+        self.evicted_closed
+            + self.evicted_session_killed
+            + self.evicted_ping_failed
+            + self.evicted_retired
+            + self.evicted_reset_failed
+            + self.evicted_idle
+            + self.evicted_identity_mismatch
+    }
+}
+
+/// Counters for connection creation and acquisition
+#[derive(Default)]
+pub(crate) struct PoolCounters {
+    wait_time: Histogram,
+    acquire_failures: AtomicU64,
+    timeouts: AtomicU64,
+    created: AtomicU64,
+    create_failures: AtomicU64,
+}
+
+impl PoolCounters {
+    pub(crate) fn record_acquire(&self, wait: Duration, outcome: AcquireOutcome) {
+        self.wait_time.record(wait);
+        match outcome {
+            AcquireOutcome::Acquired => {}
+            AcquireOutcome::TimedOut => {
+                self.acquire_failures.fetch_add(1, Ordering::Relaxed);
+                self.timeouts.fetch_add(1, Ordering::Relaxed);
+            }
+            AcquireOutcome::Failed => {
+                self.acquire_failures.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub(crate) fn record_create(&self, success: bool) {
+        let counter = if success {
+            &self.created
+        } else {
+            &self.create_failures
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(
+        &self,
+        status: Status,
+        recycle: RecycleMetrics,
+        statement_cache: StatementCacheMetrics,
+        quarantine: Option<Duration>,
+    ) -> PoolStats {
+        PoolStats {
+            status,
+            wait_time: self.wait_time.snapshot(),
+            acquire_failures: self.acquire_failures.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            connections_created: self.created.load(Ordering::Relaxed),
+            create_failures: self.create_failures.load(Ordering::Relaxed),
+            recycle,
+            statement_cache,
+            quarantine,
+        }
+    }
+}
+
+/// Outcome of a single `Pool::get()` call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AcquireOutcome {
+    Acquired,
+    TimedOut,
+    Failed,
+}
+
+/// Statistics accumulated since the pool was created
+///
+/// Returned by [`Pool::stats()`](crate::Pool::stats).
+#[derive(Debug, Clone)]
+pub struct PoolStats {
+    /// Current pool status; `status.waiting` is the queue depth
+    pub status: Status,
+    /// Time callers spent in `get()`, including create or recycle
+    pub wait_time: LatencyHistogram,
+    /// Number of `get()` calls that returned an error
+    pub acquire_failures: u64,
+    /// Number of `get()` calls that timed out
+    pub timeouts: u64,
+    /// Number of connections created
+    pub connections_created: u64,
+    /// Number of failed connection attempts
+    pub create_failures: u64,
+    /// Recycle outcomes, including evictions and health check failures
+    pub recycle: RecycleMetrics,
+    /// Statement cache hits and misses
+    pub statement_cache: StatementCacheMetrics,
+    /// Time new connections are still held back after repeated recycle
+    /// failures, see `PoolBuilder::recycle_failure_backoff()`
+    pub quarantine: Option<Duration>,
+}
+
+impl PoolStats {
+    /// Number of `get()` calls, successful or not
+    pub fn acquisitions(&self) -> u64 {
+        self.wait_time.count
+    }
+
+    /// Number of callers currently waiting for a connection
+    pub fn queue_depth(&self) -> usize {
+        self.status.waiting
+    }
+
+    /// Total number of evicted connections
+    pub fn evictions(&self) -> u64 {
+        self.recycle.evictions()
+    }
+
+    /// Number of failed recycle health checks (rollback or ping)
+    pub fn recycle_failures(&self) -> u64 {
+        self.recycle.rollback_failures + self.recycle.ping_failures
+    }
+
+    /// Approximate wait-time percentile (0.0 to 1.0)
+    pub fn wait_percentile(&self, p: f64) -> Option<Duration> {
+        self.wait_time.percentile(p)
+    }
+}
+
+/// Point-in-time view of a pool for admin and debug endpoints
+///
+/// Returned by [`Pool::status_snapshot()`](crate::Pool::status_snapshot).
+/// With the `serde` feature it implements `Serialize`, with durations in
+/// milliseconds, so it can be returned as JSON as is. It holds no
+/// credentials besides the user name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PoolSnapshot {
+    /// Name identifying the pool in telemetry
+    pub name: String,
+    /// Connections in the pool, idle or checked out
+    pub size: usize,
+    /// Idle connections
+    pub available: usize,
+    /// Callers waiting for a connection
+    pub waiting: usize,
+    /// Maximum number of connections
+    pub max_size: usize,
+    /// Connections created since the pool was built
+    pub connections_created: u64,
+    /// Failed connection attempts since the pool was built
+    pub create_failures: u64,
+    /// Connections evicted since the pool was built
+    pub evictions: u64,
+    /// `get()` calls that returned an error, including timeouts
+    pub acquire_failures: u64,
+    /// `get()` calls that timed out
+    pub timeouts: u64,
+    /// Whether the circuit breaker is failing `get()` at once
+    pub circuit_open: bool,
+    /// Settings of the pool
+    pub config: ConfigSummary,
+}
+
+/// Settings of a pool, as included in a [`PoolSnapshot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ConfigSummary {
+    /// Database host of the primary endpoint
+    pub host: String,
+    /// Database port of the primary endpoint
+    pub port: u16,
+    /// Service name, or SID for SID connections
+    pub service: String,
+    /// User the connections log on as
+    pub username: String,
+    /// Time `get()` waits for a connection
+    pub wait_timeout_ms: Option<u64>,
+    /// Time a connection attempt may take
+    pub create_timeout_ms: Option<u64>,
+    /// Time a health check may take
+    pub recycle_timeout_ms: Option<u64>,
+    /// Time after which unused connections are closed
+    pub idle_timeout_ms: Option<u64>,
+    /// Parsed statements cached per connection
+    pub statement_cache_size: usize,
+}
+
+pub(crate) fn millis(duration: Option<Duration>) -> Option<u64> {
+    duration.map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Usage statistics of a single pooled connection
+///
+/// Returned by [`Object::stats()`](crate::Object::stats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// Id of the underlying connection
+    pub connection_id: u32,
+    /// Time since the connection was created
+    pub age: Duration,
+    /// Number of times the connection has been checked out, including the current checkout
+    pub checkouts: usize,
+    /// Number of times the connection has been recycled
+    pub recycle_count: usize,
+    /// Time since the connection was last handed out by the pool
+    pub last_used: Duration,
+}
+
+impl ConnectionStats {
+    pub(crate) fn new(connection_id: u32, metrics: &Metrics) -> Self {
+        Self {
+            connection_id,
+            age: metrics.age(),
+            checkouts: metrics.recycle_count + 1,
+            recycle_count: metrics.recycle_count,
+            last_used: metrics.last_used(),
+        }
+    }
+}
+
+/// Time taken by the checkout of a connection
+///
+/// Returned by [`Object::acquire_latency()`](crate::Object::acquire_latency),
+/// to tell slowness caused by pool contention or connection setup from
+/// slow queries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AcquireLatency {
+    /// Time from the call to `get()` until the connection was handed out
+    pub total: Duration,
+    /// Part of `total` not spent creating a connection: waiting in the
+    /// queue and health-checking idle connections
+    pub wait: Duration,
+    /// Part of `total` spent creating a connection, if the checkout did
+    pub create: Option<Duration>,
+}
+
+impl AcquireLatency {
+    pub(crate) fn new(total: Duration, create: Option<Duration>) -> Self {
+        Self {
+            total,
+            wait: total.saturating_sub(create.unwrap_or_default()),
+            create,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_latency() {
+        let create = Some(Duration::from_millis(30));
+        let latency = AcquireLatency::new(Duration::from_millis(50), create);
+        assert_eq!(latency.wait, Duration::from_millis(20));
+
+        let latency = AcquireLatency::new(Duration::from_millis(5), None);
+        assert_eq!(latency.wait, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let histogram = Histogram::default();
+        for ms in [1, 3, 3, 8, 40, 40, 40, 90, 300, 7000] {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 10);
+        assert_eq!(snapshot.percentile(0.5), Some(Duration::from_millis(50)));
+        assert_eq!(snapshot.percentile(0.9), Some(Duration::from_millis(500)));
+        assert_eq!(snapshot.percentile(1.0), Some(Duration::MAX));
+        assert_eq!(snapshot.mean(), Some(Duration::from_micros(752_500)));
+    }
+
+    #[test]
+    fn test_empty_histogram() {
+        let snapshot = Histogram::default().snapshot();
+        assert_eq!(snapshot.count, 0);
+        assert_eq!(snapshot.mean(), None);
+        assert_eq!(snapshot.percentile(0.99), None);
+    }
+
+    #[test]
+    fn test_recycle_stats_evictions() {
+        let stats = RecycleStats::default();
+        stats.record_eviction(EvictionReason::SessionKilled);
+        stats.record_eviction(EvictionReason::PingFailed);
+        stats.record_eviction(EvictionReason::Retired);
+        stats.record_eviction(EvictionReason::ResetFailed);
+        stats.record_eviction(EvictionReason::Idle);
+        stats.record_eviction(EvictionReason::IdentityMismatch);
+        stats.record_ping_failure();
+
+        let metrics = stats.snapshot();
+        assert_eq!(metrics.evicted_session_killed, 1);
+        assert_eq!(metrics.evicted_ping_failed, 1);
+        assert_eq!(metrics.evicted_retired, 1);
+        assert_eq!(metrics.evicted_reset_failed, 1);
+        assert_eq!(metrics.evicted_idle, 1);
+        assert_eq!(metrics.evicted_identity_mismatch, 1);
+        assert_eq!(metrics.ping_failures, 1);
+        assert_eq!(metrics.evictions(), 6);
+        assert_eq!(metrics.recycles(), 0);
+    }
+
+    #[test]
+    fn test_pool_counters() {
+        let counters = PoolCounters::default();
+        counters.record_create(true);
+        counters.record_create(false);
+        counters.record_acquire(Duration::from_millis(3), AcquireOutcome::Acquired);
+        counters.record_acquire(Duration::from_secs(30), AcquireOutcome::TimedOut);
+
+        let status = Status {
+            max_size: 4,
+            size: 1,
+            available: 0,
+            waiting: 2,
+        };
+        let stats = counters.snapshot(
+            status,
+            RecycleStats::default().snapshot(),
+            StatementCacheMetrics::default(),
+            None,
+        );
+        assert_eq!(stats.acquisitions(), 2);
+        assert_eq!(stats.acquire_failures, 1);
+        assert_eq!(stats.timeouts, 1);
+        assert_eq!(stats.connections_created, 1);
+        assert_eq!(stats.create_failures, 1);
+        assert_eq!(stats.queue_depth(), 2);
+        assert_eq!(stats.wait_percentile(0.5), Some(Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn test_connection_stats() {
+        let metrics = Metrics {
+            recycle_count: 4,
+            ..Metrics::default()
+        };
+
+        let stats = ConnectionStats::new(3, &metrics);
+        assert_eq!(stats.connection_id, 3);
+        assert_eq!(stats.checkouts, 5);
+        assert_eq!(stats.recycle_count, 4);
+        assert!(stats.age <= stats.last_used);
+    }
+}
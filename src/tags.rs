@@ -0,0 +1,78 @@
+//! Connection tags
+//!
+//! A tag names session state a borrower set up on a connection, such as
+//! temporary tables, cached statements or application contexts. Tags stay
+//! with the connection when it goes back to the pool, so
+//! [`Pool::get_tagged()`](crate::Pool::get_tagged) can prefer a connection
+//! that already has the state over rebuilding it on another one. A fixup
+//! callback registered with
+//! [`PoolBuilder::tag_fixup()`](crate::PoolBuilder::tag_fixup) sets up the
+//! state when no connection has it, like OCI session tagging.
+
+use crate::Object;
+use oracle_rs::Error;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+/// Future returned by a tag fixup callback
+pub type TagFixupFuture<'c> = Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'c>>;
+
+/// Callback bringing a connection from its current tag to the requested one
+pub(crate) type TagFixup =
+    dyn for<'c> Fn(&'c Object, &'c str, Option<&'c str>) -> TagFixupFuture<'c> + Send + Sync;
+
+/// Tags of the connections of a pool, by connection id
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionTags {
+    tags: Mutex<HashMap<u32, String>>,
+}
+
+impl ConnectionTags {
+    pub(crate) fn get(&self, connection_id: u32) -> Option<String> {
+        self.tags.lock().unwrap().get(&connection_id).cloned()
+    }
+
+    pub(crate) fn set(&self, connection_id: u32, tag: Option<&str>) {
+        let mut tags = self.tags.lock().unwrap();
+        match tag {
+            Some(tag) => tags.insert(connection_id, tag.to_string()),
+            None => tags.remove(&connection_id),
+        };
+    }
+
+    /// Forget a connection that left the pool
+    pub(crate) fn remove(&self, connection_id: u32) {
+        self.set(connection_id, None);
+    }
+
+    /// Whether any connection carries `tag`, checked out or idle
+    pub(crate) fn contains(&self, tag: &str) -> bool {
+        self.tags.lock().unwrap().values().any(|t| t == tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_tags() {
+        let tags = ConnectionTags::default();
+        tags.set(1, Some("reporting"));
+        tags.set(2, Some("reporting"));
+        tags.set(3, Some("batch"));
+
+        assert_eq!(tags.get(1).as_deref(), Some("reporting"));
+        assert!(tags.contains("reporting"));
+
+        tags.set(1, Some("batch"));
+        tags.remove(2);
+        assert!(!tags.contains("reporting"));
+        assert!(tags.contains("batch"));
+
+        tags.set(3, None);
+        assert_eq!(tags.get(3), None);
+    }
+}
@@ -0,0 +1,76 @@
+//! Spawning of the pool's background tasks
+//!
+//! The pool runs a few tasks of its own: the idle reaper, circuit breaker
+//! probes, leak and transaction duration watches, and the commits,
+//! rollbacks and closes finishing a dropped connection. With the
+//! `tokio-console` feature and the `tokio_unstable` cfg they are spawned
+//! with names starting with `oracle_pool.`, so tokio-console tells them
+//! apart from the application's tasks. Otherwise they are spawned unnamed.
+
+use std::future::Future;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+/// Spawn `future` on the current runtime as the task `name`
+///
+/// Panics outside a Tokio runtime, like `tokio::spawn()`.
+pub(crate) fn spawn<F>(name: &'static str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    spawn_on(name, future, &Handle::current())
+}
+
+/// Spawn `future` on `runtime` as the task `name`
+pub(crate) fn spawn_on<F>(name: &'static str, future: F, runtime: &Handle) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(all(tokio_unstable, feature = "tokio-console"))]
+    return tokio::task::Builder::new()
+        .name(name)
+        .spawn_on(future, runtime)
+        .expect("failed to spawn task");
+
+    #[cfg(not(all(tokio_unstable, feature = "tokio-console")))]
+    {
+        let _ = name;
+        runtime.spawn(future)
+    }
+}
+
+/// Run `f` on the blocking thread pool as the task `name`
+///
+/// Panics outside a Tokio runtime, like `tokio::task::spawn_blocking()`.
+pub(crate) fn spawn_blocking<F, R>(name: &'static str, f: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    #[cfg(all(tokio_unstable, feature = "tokio-console"))]
+    return tokio::task::Builder::new()
+        .name(name)
+        .spawn_blocking(f)
+        .expect("failed to spawn task");
+
+    #[cfg(not(all(tokio_unstable, feature = "tokio-console")))]
+    {
+        let _ = name;
+        tokio::task::spawn_blocking(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spawned_tasks_run() {
+        assert_eq!(spawn("oracle_pool.test", async { 1 }).await.unwrap(), 1);
+        let task = spawn_on("oracle_pool.test", async { 2 }, &Handle::current());
+        assert_eq!(task.await.unwrap(), 2);
+        assert_eq!(spawn_blocking("oracle_pool.test", || 3).await.unwrap(), 3);
+    }
+}
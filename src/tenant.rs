@@ -0,0 +1,190 @@
+//! Pools per tenant for multi-tenant backends
+//!
+//! Backends serving many tenants from separate schemas or credentials need
+//! a pool per tenant, but only a fraction of the tenants are active at any
+//! time. [`TenantPools`] creates a tenant's pool on its first checkout and
+//! keeps at most a fixed number of them, closing the least recently used
+//! one to make room for another.
+
+use crate::{BuildError, Object, Pool, PoolBuilder, PoolError};
+use deadpool::managed;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+type Factory = dyn Fn(&str) -> PoolBuilder + Send + Sync;
+
+/// Lazily created pools keyed by tenant, evicted least recently used first
+///
+/// Cloning is cheap; clones share the pools.
+///
+/// ```rust,no_run
+/// # async fn example() -> Result<(), deadpool_oracle::PoolError> {
+/// use deadpool_oracle::{PoolBuilder, TenantPools};
+/// use oracle_rs::Config;
+///
+/// let tenants = TenantPools::new(100, |tenant| {
+///     let config = Config::new("db.example.com", 1521, "SAAS", tenant, "secret");
+///     PoolBuilder::new(config).max_size(4)
+/// });
+///
+/// let conn = tenants.get("acme").await?;
+/// let rows = conn.query("SELECT id FROM invoices", &[]).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TenantPools {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    max_pools: usize,
+    factory: Box<Factory>,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    pools: HashMap<String, Entry>,
+    /// Incremented on every lookup, to order tenants by last use
+    clock: u64,
+}
+
+struct Entry {
+    pool: Pool,
+    last_used: u64,
+}
+
+impl TenantPools {
+    /// Keep at most `max_pools` pools, built by `factory` for each tenant
+    ///
+    /// The factory gets the tenant key and returns the builder for its
+    /// pool, typically with the tenant's credentials or schema. Pools are
+    /// named after their tenant.
+    pub fn new<F>(max_pools: usize, factory: F) -> Self
+    where
+        F: Fn(&str) -> PoolBuilder + Send + Sync + 'static,
+    {
+        Self {
+            inner: Arc::new(Inner {
+                max_pools: max_pools.max(1),
+                factory: Box::new(factory),
+                state: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Get a connection from the pool of `tenant`, creating the pool if needed
+    pub async fn get(&self, tenant: &str) -> Result<Object, PoolError> {
+        // A missing runtime is the only reason building a pool can fail
+        let pool = self
+            .pool(tenant)
+            .map_err(|_| managed::PoolError::NoRuntimeSpecified)?;
+        pool.get().await
+    }
+
+    /// Get the pool of `tenant`, creating it if needed
+    ///
+    /// Creating a pool beyond the maximum closes the least recently used
+    /// one. Its connections checked out at the time stay usable and are
+    /// closed when returned.
+    pub fn pool(&self, tenant: &str) -> Result<Pool, BuildError> {
+        let mut state = self.inner.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        if let Some(entry) = state.pools.get_mut(tenant) {
+            entry.last_used = clock;
+            return Ok(entry.pool.clone());
+        }
+
+        let pool = (self.inner.factory)(tenant).name(tenant).build()?;
+        if state.pools.len() >= self.inner.max_pools {
+            let lru = state
+                .pools
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(tenant, _)| tenant.clone());
+            if let Some(entry) = lru.and_then(|tenant| state.pools.remove(&tenant)) {
+                entry.pool.close();
+            }
+        }
+        let entry = Entry {
+            pool: pool.clone(),
+            last_used: clock,
+        };
+        state.pools.insert(tenant.to_string(), entry);
+        Ok(pool)
+    }
+
+    /// Close and forget the pool of `tenant`, e.g. after its credentials changed
+    pub fn remove(&self, tenant: &str) -> bool {
+        let entry = self.inner.state.lock().unwrap().pools.remove(tenant);
+        entry.map(|entry| entry.pool.close()).is_some()
+    }
+
+    /// Tenants that currently have a pool, in no particular order
+    pub fn tenants(&self) -> Vec<String> {
+        let state = self.inner.state.lock().unwrap();
+        state.pools.keys().cloned().collect()
+    }
+
+    /// Number of open tenant pools
+    pub fn len(&self) -> usize {
+        self.inner.state.lock().unwrap().pools.len()
+    }
+
+    /// Check whether no tenant has a pool
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Close all tenant pools
+    ///
+    /// Later checkouts create new pools.
+    pub fn close(&self) {
+        let pools = std::mem::take(&mut self.inner.state.lock().unwrap().pools);
+        for entry in pools.into_values() {
+            entry.pool.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_rs::Config;
+
+    fn tenant_pools(max_pools: usize) -> TenantPools {
+        TenantPools::new(max_pools, |tenant| {
+            let config = Config::new("localhost", 1521, "SAAS", tenant, "secret");
+            PoolBuilder::new(config).max_size(2)
+        })
+    }
+
+    #[test]
+    fn test_tenant_pools_evict_least_recently_used() {
+        let tenants = tenant_pools(2);
+        let acme = tenants.pool("acme").unwrap();
+        assert_eq!(acme.name(), "acme");
+        let globex = tenants.pool("globex").unwrap();
+        // Using acme again makes globex the least recently used
+        assert!(!tenants.pool("acme").unwrap().is_closed());
+
+        let initech = tenants.pool("initech").unwrap();
+        assert_eq!(tenants.len(), 2);
+        assert!(globex.is_closed());
+        assert!(!acme.is_closed());
+
+        let mut names = tenants.tenants();
+        names.sort();
+        assert_eq!(names, ["acme", "initech"]);
+
+        assert!(tenants.remove("initech"));
+        assert!(!tenants.remove("initech"));
+        assert!(initech.is_closed());
+
+        tenants.close();
+        assert!(tenants.is_empty());
+        assert!(acme.is_closed());
+    }
+}
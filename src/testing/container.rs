@@ -0,0 +1,124 @@
+//! Pools on a throwaway Oracle Free container
+
+use crate::{AnyError, Pool, PoolBuilder};
+use oracle_rs::Config;
+use std::ops::Deref;
+use std::time::Duration;
+use testcontainers::core::{IntoContainerPort, WaitFor};
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, GenericImage, ImageExt, TestcontainersError};
+
+/// Image started by [`TestPool::start()`]
+const IMAGE: &str = "gvenzl/oracle-free";
+const TAG: &str = "23-slim-faststart";
+/// Password of SYSTEM, the same as the default of the integration tests
+const PASSWORD: &str = "testpass";
+const SERVICE: &str = "FREEPDB1";
+
+/// A pool connected to an Oracle Free container started for a test
+///
+/// [`start()`](Self::start) launches the container and waits until the
+/// database accepts connections, so integration tests need neither a
+/// provisioned database nor environment variables, only Docker. The pool
+/// connects as SYSTEM to `FREEPDB1`. The first start pulls the image,
+/// which takes a while.
+///
+/// Dereferences to the [`Pool`]. The container is removed when the
+/// `TestPool` is dropped, or by [`stop()`](Self::stop), so keep it alive
+/// for as long as the test uses the pool.
+///
+/// ```rust,no_run
+/// use deadpool_oracle::testing::TestPool;
+///
+/// #[tokio::test]
+/// async fn test_orders() {
+///     let db = TestPool::start().await.expect("Oracle container");
+///     let conn = db.get().await.unwrap();
+///     conn.execute("CREATE TABLE orders (id NUMBER)", &[]).await.unwrap();
+///     // ...
+/// }
+/// ```
+pub struct TestPool {
+    pool: Pool,
+    config: Config,
+    container: ContainerAsync<GenericImage>,
+}
+
+impl TestPool {
+    /// Start a container and build a pool with default settings for it
+    pub async fn start() -> Result<Self, AnyError> {
+        Self::start_with(|builder| builder).await
+    }
+
+    /// Start a container and build a pool with the settings applied by `configure`
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), deadpool_oracle::AnyError> {
+    /// use deadpool_oracle::testing::TestPool;
+    ///
+    /// let db = TestPool::start_with(|builder| builder.max_size(2).commit_on_drop(true)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn start_with(
+        configure: impl FnOnce(PoolBuilder) -> PoolBuilder,
+    ) -> Result<Self, AnyError> {
+        let container = GenericImage::new(IMAGE, TAG)
+            .with_exposed_port(1521.tcp())
+            .with_wait_for(WaitFor::message_on_stdout("DATABASE IS READY TO USE!"))
+            .with_env_var("ORACLE_PASSWORD", PASSWORD)
+            .with_startup_timeout(Duration::from_secs(300))
+            .start()
+            .await?;
+        let host = container.get_host().await?.to_string();
+        let port = container.get_host_port_ipv4(1521).await?;
+
+        let config = Config::new(&host, port, SERVICE, "system", PASSWORD);
+        let pool = configure(PoolBuilder::new(config.clone())).build()?;
+        let db = Self {
+            pool,
+            config,
+            container,
+        };
+        db.wait_until_ready().await?;
+        Ok(db)
+    }
+
+    /// Wait for the listener to register the service, which may lag behind
+    /// the ready message
+    async fn wait_until_ready(&self) -> Result<(), AnyError> {
+        let mut attempts = 30;
+        loop {
+            match self.pool.get().await {
+                Ok(_) => return Ok(()),
+                Err(e) if attempts == 0 => return Err(e.into()),
+                Err(_) => attempts -= 1,
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    /// The pool connected to the container
+    pub fn pool(&self) -> &Pool {
+        &self.pool
+    }
+
+    /// Connection settings of the container, to build further pools for it
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Close the pool and remove the container
+    pub async fn stop(self) -> Result<(), TestcontainersError> {
+        self.pool.close();
+        self.container.rm().await
+    }
+}
+
+impl Deref for TestPool {
+    type Target = Pool;
+
+    fn deref(&self) -> &Pool {
+        &self.pool
+    }
+}
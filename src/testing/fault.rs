@@ -0,0 +1,259 @@
+//! Faults injected into a real pool
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Injects faults into a pool, to test how an application copes with them
+///
+/// Attached with `PoolBuilder::fault_injector()`. Each fault happens with
+/// its own probability, from 0 (never, the default) to 1 (always); values
+/// outside that range are clamped.
+///
+/// - Create latency delays connection attempts, counting against the
+///   create timeout.
+/// - Recycle failures disconnect an idle connection when it is about to be
+///   handed out again, so its health check fails and it is replaced.
+/// - Disconnects close a connection as it is handed out, so the
+///   application's next statement on it fails as if the session had died
+///   while checked out.
+/// - Idle kills are chaos for soak tests: a background task closes idle
+///   connections at a steady rate, as a RAC node reboot or a firewall
+///   dropping sessions would, and the pool has to replace them.
+///
+/// Clones share their counters, so a clone kept by the test tells how
+/// many faults were injected.
+///
+/// ```rust,no_run
+/// # fn example(config: oracle_rs::Config) -> Result<(), deadpool_oracle::BuildError> {
+/// use deadpool_oracle::testing::FaultInjector;
+/// use deadpool_oracle::PoolBuilder;
+/// use std::time::Duration;
+///
+/// let faults = FaultInjector::new()
+///     .create_latency(0.2, Duration::from_millis(500))
+///     .disconnects(0.05)
+///     .seed(42);
+/// let pool = PoolBuilder::new(config)
+///     .fault_injector(faults.clone())
+///     .build()?;
+///
+/// // ... run the code under test, then
+/// assert!(faults.disconnects_injected() > 0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct FaultInjector {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    create_latency: f64,
+    create_delay: Duration,
+    recycle_failures: f64,
+    disconnects: f64,
+    idle_kills: f64,
+    idle_kill_interval: Duration,
+    rng: AtomicU64,
+    delayed_creates: AtomicU64,
+    failed_recycles: AtomicU64,
+    disconnected: AtomicU64,
+    idle_killed: AtomicU64,
+}
+
+impl Default for FaultInjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FaultInjector {
+    /// Create an injector that injects nothing yet, seeded at random
+    pub fn new() -> Self {
+        let inner = Inner {
+            create_latency: 0.0,
+            create_delay: Duration::ZERO,
+            recycle_failures: 0.0,
+            disconnects: 0.0,
+            idle_kills: 0.0,
+            idle_kill_interval: Duration::ZERO,
+            rng: AtomicU64::new(0),
+            delayed_creates: AtomicU64::new(0),
+            failed_recycles: AtomicU64::new(0),
+            disconnected: AtomicU64::new(0),
+            idle_killed: AtomicU64::new(0),
+        };
+        let seed = RandomState::new().build_hasher().finish();
+        Self {
+            inner: Arc::new(inner),
+        }
+        .seed(seed)
+    }
+
+    fn configure(mut self, f: impl FnOnce(&mut Inner)) -> Self {
+        // Only configured before it is attached and cloned
+        f(Arc::get_mut(&mut self.inner).expect("configure a FaultInjector before cloning it"));
+        self
+    }
+
+    /// Delay connection attempts by `delay` with `probability`
+    pub fn create_latency(self, probability: f64, delay: Duration) -> Self {
+        self.configure(|inner| {
+            inner.create_latency = probability.clamp(0.0, 1.0);
+            inner.create_delay = delay;
+        })
+    }
+
+    /// Fail the health check of an idle connection with `probability`
+    pub fn recycle_failures(self, probability: f64) -> Self {
+        self.configure(|inner| inner.recycle_failures = probability.clamp(0.0, 1.0))
+    }
+
+    /// Disconnect a connection as it is handed out with `probability`
+    pub fn disconnects(self, probability: f64) -> Self {
+        self.configure(|inner| inner.disconnects = probability.clamp(0.0, 1.0))
+    }
+
+    /// Close each idle connection with `probability` every `interval`
+    ///
+    /// The connections stay in the pool, closed on the client side, and
+    /// are replaced when their health check finds them closed on the next
+    /// checkout. The task runs while the pool is open and not
+    /// deterministic, from `PoolBuilder::build()` within a Tokio runtime.
+    pub fn idle_kills(self, probability: f64, interval: Duration) -> Self {
+        self.configure(|inner| {
+            inner.idle_kills = probability.clamp(0.0, 1.0);
+            inner.idle_kill_interval = interval;
+        })
+    }
+
+    /// Seed the random choices, to inject the same sequence of faults on every run
+    pub fn seed(self, seed: u64) -> Self {
+        // xorshift gets stuck at 0
+        self.configure(|inner| inner.rng = AtomicU64::new(seed.max(1)))
+    }
+
+    /// Number of connection attempts delayed so far
+    pub fn creates_delayed(&self) -> u64 {
+        self.inner.delayed_creates.load(Ordering::Relaxed)
+    }
+
+    /// Number of health checks failed so far
+    pub fn recycles_failed(&self) -> u64 {
+        self.inner.failed_recycles.load(Ordering::Relaxed)
+    }
+
+    /// Number of connections disconnected at checkout so far
+    pub fn disconnects_injected(&self) -> u64 {
+        self.inner.disconnected.load(Ordering::Relaxed)
+    }
+
+    /// Number of idle connections closed so far
+    pub fn idle_connections_killed(&self) -> u64 {
+        self.inner.idle_killed.load(Ordering::Relaxed)
+    }
+
+    /// Delay to inject before a connection attempt, if any
+    pub(crate) fn create_delay(&self) -> Option<Duration> {
+        self.roll(self.inner.create_latency, &self.inner.delayed_creates)
+            .then_some(self.inner.create_delay)
+    }
+
+    /// Whether to fail the next health check
+    pub(crate) fn fail_recycle(&self) -> bool {
+        self.roll(self.inner.recycle_failures, &self.inner.failed_recycles)
+    }
+
+    /// Whether to disconnect the connection being handed out
+    pub(crate) fn disconnect(&self) -> bool {
+        self.roll(self.inner.disconnects, &self.inner.disconnected)
+    }
+
+    /// How often to kill idle connections, if at all
+    pub(crate) fn idle_kill_interval(&self) -> Option<Duration> {
+        let interval = self.inner.idle_kill_interval;
+        (self.inner.idle_kills > 0.0 && !interval.is_zero()).then_some(interval)
+    }
+
+    /// Whether to kill an idle connection
+    pub(crate) fn kill_idle(&self) -> bool {
+        self.roll(self.inner.idle_kills, &self.inner.idle_killed)
+    }
+
+    /// Draw whether a fault of `probability` happens, counting it in `counter`
+    fn roll(&self, probability: f64, counter: &AtomicU64) -> bool {
+        if probability <= 0.0 {
+            return false;
+        }
+        let happens = self.next_f64() < probability;
+        if happens {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+        happens
+    }
+
+    /// Next number of the xorshift64* sequence, scaled to [0, 1)
+    fn next_f64(&self) -> f64 {
+        let step = |mut x: u64| {
+            x ^= x >> 12;
+            x ^= x << 25;
+            x ^= x >> 27;
+            x
+        };
+        let previous = self
+            .inner
+            .rng
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| Some(step(x)))
+            .expect("always updated");
+        let x = step(previous).wrapping_mul(0x2545_f491_4f6c_dd1d);
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probabilities() {
+        let faults = FaultInjector::new()
+            .create_latency(1.0, Duration::from_millis(5))
+            .recycle_failures(0.5)
+            .disconnects(-1.0)
+            .seed(7);
+        for _ in 0..1000 {
+            assert_eq!(faults.create_delay(), Some(Duration::from_millis(5)));
+            faults.fail_recycle();
+            assert!(!faults.disconnect());
+        }
+        assert_eq!(faults.creates_delayed(), 1000);
+        assert!((400..600).contains(&faults.recycles_failed()));
+        assert_eq!(faults.disconnects_injected(), 0);
+        assert_eq!(faults.idle_kill_interval(), None);
+    }
+
+    #[test]
+    fn test_idle_kills() {
+        let interval = Duration::from_secs(10);
+        let faults = FaultInjector::new().idle_kills(1.0, interval);
+        assert_eq!(faults.idle_kill_interval(), Some(interval));
+        assert!(faults.kill_idle());
+        assert_eq!(faults.idle_connections_killed(), 1);
+
+        let never = FaultInjector::new().idle_kills(0.5, Duration::ZERO);
+        assert_eq!(never.idle_kill_interval(), None);
+    }
+
+    #[test]
+    fn test_seeded_sequence_repeats() {
+        let sequence = |seed| {
+            let faults = FaultInjector::new().disconnects(0.3).seed(seed);
+            (0..64).map(|_| faults.disconnect()).collect::<Vec<_>>()
+        };
+        assert_eq!(sequence(42), sequence(42));
+        assert_ne!(sequence(42), sequence(43));
+    }
+}
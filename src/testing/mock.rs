@@ -0,0 +1,421 @@
+//! Scripted stand-in for the connection manager
+
+use crate::{AnyConnection, AnyFuture, AnyPool, AnyRows, AnyValue};
+#[cfg(feature = "record")]
+use crate::{RecordedOutcome, RecordedQuery, Recorder};
+use deadpool::managed::{self, Metrics, RecycleError, RecycleResult};
+use deadpool::Status;
+use oracle_rs::Error;
+use std::collections::HashMap;
+use std::io;
+#[cfg(feature = "record")]
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Pool of [`MockConnection`]s
+pub type MockPool = managed::Pool<MockManager>;
+
+/// Manager of connections answering from a script instead of a database
+///
+/// Statements are matched by their SQL text. Those without a scripted
+/// response succeed without rows, so only the statements whose results
+/// matter to a test need to be scripted. Everything run through the
+/// connections is recorded and available from
+/// [`statements()`](Self::statements).
+///
+/// ```rust
+/// use deadpool_oracle::testing::MockManager;
+/// use deadpool_oracle::{AnyError, AnyPool, AnyRows, AnyValue};
+///
+/// // Application code, running on a `Pool` in production
+/// async fn active_users(pool: &dyn AnyPool) -> Result<i64, AnyError> {
+///     let conn = pool.get().await?;
+///     let result = conn.query("SELECT COUNT(*) FROM users WHERE active = 1", &[]).await?;
+///     match result.rows[0][0] {
+///         AnyValue::Int(n) => Ok(n),
+///         _ => Err("not a count".into()),
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let count = AnyRows {
+///     columns: vec!["COUNT(*)".to_string()],
+///     rows: vec![vec![AnyValue::Int(3)]],
+/// };
+/// let pool = MockManager::new()
+///     .with_query_result("SELECT COUNT(*) FROM users WHERE active = 1", count)
+///     .into_pool(2);
+/// assert_eq!(active_users(&pool).await.unwrap(), 3);
+///
+/// pool.manager().fail_next_creates(1);
+/// pool.resize(0);
+/// assert!(active_users(&pool).await.is_err());
+/// # }
+/// ```
+#[derive(Default)]
+pub struct MockManager {
+    script: Arc<Script>,
+}
+
+#[derive(Default)]
+struct Script {
+    responses: HashMap<String, Response>,
+    #[cfg(feature = "record")]
+    replays: Vec<Replay>,
+    statements: Mutex<Vec<MockStatement>>,
+    create_failures: AtomicUsize,
+    recycle_failures: AtomicUsize,
+    next_id: AtomicU32,
+}
+
+#[derive(Clone)]
+enum Response {
+    Rows(AnyRows),
+    RowsAffected(u64),
+    Error { code: Option<u32>, message: String },
+}
+
+/// Recorded outcomes of a statement with the same binds, answered in turn
+#[cfg(feature = "record")]
+struct Replay {
+    statement: MockStatement,
+    responses: Vec<Option<Response>>,
+    next: AtomicUsize,
+}
+
+/// A statement run through a [`MockConnection`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockStatement {
+    /// SQL text; commits and rollbacks are recorded as `COMMIT` and `ROLLBACK`
+    pub sql: String,
+    /// Bind values
+    pub params: Vec<AnyValue>,
+}
+
+impl MockManager {
+    /// Create a manager without scripted responses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer the query `sql` with `rows`
+    pub fn with_query_result(self, sql: impl Into<String>, rows: AnyRows) -> Self {
+        self.with_response(sql.into(), Response::Rows(rows))
+    }
+
+    /// Report `rows_affected` rows for the statement `sql`
+    pub fn with_rows_affected(self, sql: impl Into<String>, rows_affected: u64) -> Self {
+        self.with_response(sql.into(), Response::RowsAffected(rows_affected))
+    }
+
+    /// Fail the statement or query `sql` with the error ORA-`code`
+    pub fn with_error(self, sql: impl Into<String>, code: u32, message: impl Into<String>) -> Self {
+        let (code, message) = (Some(code), message.into());
+        self.with_response(sql.into(), Response::Error { code, message })
+    }
+
+    /// Answer the statements of a recording as they were answered when recorded
+    ///
+    /// Statements are matched by their SQL text and binds. One that ran
+    /// several times is answered with its recorded outcomes in order, the
+    /// last one repeating once they are used up. Statements without a match
+    /// fall back to the other scripted responses.
+    #[cfg(feature = "record")]
+    pub fn with_recording(mut self, queries: impl IntoIterator<Item = RecordedQuery>) -> Self {
+        let script = Arc::get_mut(&mut self.script).expect("script is not shared");
+        for query in queries {
+            let response = match query.outcome {
+                RecordedOutcome::Rows(rows) => Some(Response::Rows(rows)),
+                RecordedOutcome::RowsAffected(n) => Some(Response::RowsAffected(n)),
+                RecordedOutcome::Done => None,
+                RecordedOutcome::Error { code, message } => Some(Response::Error { code, message }),
+            };
+            let statement = MockStatement {
+                sql: query.sql,
+                params: query.params,
+            };
+            match script.replays.iter_mut().find(|r| r.statement == statement) {
+                Some(replay) => replay.responses.push(response),
+                None => script.replays.push(Replay {
+                    statement,
+                    responses: vec![response],
+                    next: AtomicUsize::new(0),
+                }),
+            }
+        }
+        self
+    }
+
+    /// Create a manager answering the statements of the recording at `path`
+    ///
+    /// See [`with_recording()`](Self::with_recording).
+    #[cfg(feature = "record")]
+    pub fn replay(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new().with_recording(Recorder::load(path)?))
+    }
+
+    fn with_response(mut self, sql: String, response: Response) -> Self {
+        // Not shared yet, connections are only created by a pool
+        let script = Arc::get_mut(&mut self.script).expect("script is not shared");
+        script.responses.insert(sql, response);
+        self
+    }
+
+    /// Fail the next `n` connection attempts as if the listener refused them
+    pub fn fail_next_creates(&self, n: usize) {
+        self.script.create_failures.store(n, Ordering::Relaxed);
+    }
+
+    /// Fail the next `n` recycles, so the connections are replaced
+    pub fn fail_next_recycles(&self, n: usize) {
+        self.script.recycle_failures.store(n, Ordering::Relaxed);
+    }
+
+    /// Statements run so far, in order
+    pub fn statements(&self) -> Vec<MockStatement> {
+        self.script.statements.lock().unwrap().clone()
+    }
+
+    /// Number of connections created so far
+    pub fn created(&self) -> u32 {
+        self.script.next_id.load(Ordering::Relaxed)
+    }
+
+    /// Build a pool of up to `max_size` connections
+    pub fn into_pool(self, max_size: usize) -> MockPool {
+        managed::Pool::builder(self)
+            .max_size(max_size)
+            .build()
+            .expect("a pool without timeouts needs no runtime")
+    }
+}
+
+/// Decrement `counter` unless it is zero, returning whether it was decremented
+fn take_one(counter: &AtomicUsize) -> bool {
+    counter
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+        .is_ok()
+}
+
+impl managed::Manager for MockManager {
+    type Type = MockConnection;
+    type Error = Error;
+
+    async fn create(&self) -> Result<MockConnection, Error> {
+        if take_one(&self.script.create_failures) {
+            return Err(Error::Io(io::ErrorKind::ConnectionRefused.into()));
+        }
+        Ok(MockConnection {
+            id: self.script.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            script: self.script.clone(),
+        })
+    }
+
+    async fn recycle(
+        &self,
+        _conn: &mut MockConnection,
+        _metrics: &Metrics,
+    ) -> RecycleResult<Error> {
+        if take_one(&self.script.recycle_failures) {
+            return Err(RecycleError::Backend(Error::ConnectionClosed));
+        }
+        Ok(())
+    }
+}
+
+/// Connection created by a [`MockManager`]
+pub struct MockConnection {
+    id: u32,
+    script: Arc<Script>,
+}
+
+impl MockConnection {
+    /// Number of this connection, counting from 1 in order of creation
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn run(&self, sql: &str, params: &[AnyValue]) -> Result<Option<Response>, Error> {
+        self.script.statements.lock().unwrap().push(MockStatement {
+            sql: sql.to_string(),
+            params: params.to_vec(),
+        });
+        match self.response(sql, params) {
+            Some(Response::Error {
+                code: Some(code),
+                message,
+            }) => Err(Error::oracle(code, message)),
+            Some(Response::Error {
+                code: None,
+                message,
+            }) => Err(Error::SqlError(message)),
+            response => Ok(response),
+        }
+    }
+
+    #[cfg_attr(not(feature = "record"), allow(unused_variables))]
+    fn response(&self, sql: &str, params: &[AnyValue]) -> Option<Response> {
+        #[cfg(feature = "record")]
+        {
+            let matches = |r: &&Replay| r.statement.sql == sql && r.statement.params == params;
+            if let Some(replay) = self.script.replays.iter().find(matches) {
+                let next = replay.next.fetch_add(1, Ordering::Relaxed);
+                return replay.responses[next.min(replay.responses.len() - 1)].clone();
+            }
+        }
+        self.script.responses.get(sql).cloned()
+    }
+
+    /// Execute a statement, returning the number of rows affected
+    pub async fn execute(&self, sql: &str, params: &[AnyValue]) -> Result<u64, Error> {
+        Ok(match self.run(sql, params)? {
+            Some(Response::RowsAffected(n)) => n,
+            Some(Response::Rows(rows)) => rows.rows.len() as u64,
+            _ => 0,
+        })
+    }
+
+    /// Run a query and fetch all of its rows
+    pub async fn query(&self, sql: &str, params: &[AnyValue]) -> Result<AnyRows, Error> {
+        Ok(match self.run(sql, params)? {
+            Some(Response::Rows(rows)) => rows,
+            _ => AnyRows::default(),
+        })
+    }
+
+    /// Commit the current transaction
+    pub async fn commit(&self) -> Result<(), Error> {
+        self.run("COMMIT", &[]).map(drop)
+    }
+
+    /// Roll back the current transaction
+    pub async fn rollback(&self) -> Result<(), Error> {
+        self.run("ROLLBACK", &[]).map(drop)
+    }
+}
+
+impl AnyPool for MockPool {
+    fn get(&self) -> AnyFuture<'_, Box<dyn AnyConnection>> {
+        Box::pin(async move {
+            let conn = managed::Pool::get(self).await?;
+            Ok(Box::new(conn) as Box<dyn AnyConnection>)
+        })
+    }
+
+    fn status(&self) -> Status {
+        managed::Pool::status(self)
+    }
+
+    fn close(&self) {
+        managed::Pool::close(self)
+    }
+}
+
+impl AnyConnection for managed::Object<MockManager> {
+    fn execute<'a>(&'a self, sql: &'a str, params: &'a [AnyValue]) -> AnyFuture<'a, u64> {
+        Box::pin(async move { Ok(MockConnection::execute(self, sql, params).await?) })
+    }
+
+    fn query<'a>(&'a self, sql: &'a str, params: &'a [AnyValue]) -> AnyFuture<'a, AnyRows> {
+        Box::pin(async move { Ok(MockConnection::query(self, sql, params).await?) })
+    }
+
+    fn commit(&self) -> AnyFuture<'_, ()> {
+        Box::pin(async move { Ok(MockConnection::commit(self).await?) })
+    }
+
+    fn rollback(&self) -> AnyFuture<'_, ()> {
+        Box::pin(async move { Ok(MockConnection::rollback(self).await?) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scripted_responses() {
+        let rows = AnyRows {
+            columns: vec!["NAME".to_string()],
+            rows: vec![vec![AnyValue::Text("alice".to_string())]],
+        };
+        let pool = MockManager::new()
+            .with_query_result("SELECT name FROM users", rows.clone())
+            .with_rows_affected("DELETE FROM users", 2)
+            .with_error("DROP TABLE users", 942, "table or view does not exist")
+            .into_pool(1);
+
+        let conn = AnyPool::get(&pool).await.unwrap();
+        assert_eq!(
+            conn.query("SELECT name FROM users", &[]).await.unwrap(),
+            rows
+        );
+        assert_eq!(conn.execute("DELETE FROM users", &[]).await.unwrap(), 2);
+        assert_eq!(
+            conn.execute("UPDATE users SET x = 1", &[]).await.unwrap(),
+            0
+        );
+        let error = conn.execute("DROP TABLE users", &[]).await.unwrap_err();
+        assert!(error.to_string().contains("ORA-00942"));
+        conn.commit().await.unwrap();
+
+        let params = [AnyValue::Int(1)];
+        conn.execute("INSERT INTO users (id) VALUES (:1)", &params)
+            .await
+            .unwrap();
+        let statements = pool.manager().statements();
+        assert_eq!(statements.len(), 6);
+        assert_eq!(statements[4].sql, "COMMIT");
+        assert_eq!(statements[5].params, params);
+    }
+
+    #[cfg(feature = "record")]
+    #[tokio::test]
+    async fn test_replay_in_order() {
+        let recorded = |n| RecordedQuery {
+            sql: "SELECT COUNT(*) FROM orders".to_string(),
+            params: vec![AnyValue::Int(1)],
+            outcome: RecordedOutcome::Rows(AnyRows {
+                columns: vec!["COUNT(*)".to_string()],
+                rows: vec![vec![AnyValue::Int(n)]],
+            }),
+        };
+        let pool = MockManager::new()
+            .with_recording([recorded(0), recorded(1)])
+            .into_pool(1);
+
+        let conn = AnyPool::get(&pool).await.unwrap();
+        let count = |rows: AnyRows| rows.rows[0][0].clone();
+        let params = [AnyValue::Int(1)];
+        for expected in [0, 1, 1] {
+            let rows = conn
+                .query("SELECT COUNT(*) FROM orders", &params)
+                .await
+                .unwrap();
+            assert_eq!(count(rows), AnyValue::Int(expected));
+        }
+        // Other binds don't match the recording
+        let rows = conn
+            .query("SELECT COUNT(*) FROM orders", &[])
+            .await
+            .unwrap();
+        assert!(rows.rows.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_and_recycle_failures() {
+        let pool = MockManager::new().into_pool(1);
+        pool.manager().fail_next_creates(1);
+        assert!(pool.get().await.is_err());
+        let conn = pool.get().await.unwrap();
+        assert_eq!(conn.id(), 1);
+        drop(conn);
+
+        pool.manager().fail_next_recycles(1);
+        let conn = pool.get().await.unwrap();
+        assert_eq!(conn.id(), 2);
+        assert_eq!(pool.manager().created(), 2);
+    }
+}
@@ -0,0 +1,31 @@
+//! Utilities for testing code that uses a pool
+//!
+//! Enabled by the `testing` feature, which is meant for dev-dependencies:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! deadpool-oracle = { version = "0.1", features = ["testing"] }
+//! ```
+//!
+//! [`MockManager`] stands in for an Oracle database in unit tests. Code
+//! written against [`AnyPool`](crate::AnyPool) and
+//! [`AnyConnection`](crate::AnyConnection) runs on a [`MockPool`] in tests
+//! and on a [`Pool`](crate::Pool) in production. With the `record`
+//! feature, it also replays statements captured by a `Recorder`.
+//! [`FaultInjector`] makes a real pool misbehave, to test how the
+//! application copes, and [`PoolAssert`] checks that it left the pool in
+//! order. With the
+//! `testcontainers` feature, [`TestPool`] runs integration tests on an
+//! Oracle Free container.
+
+#[cfg(feature = "testcontainers")]
+mod container;
+mod fault;
+mod mock;
+mod pool_assert;
+
+#[cfg(feature = "testcontainers")]
+pub use container::TestPool;
+pub use fault::FaultInjector;
+pub use mock::{MockConnection, MockManager, MockPool, MockStatement};
+pub use pool_assert::PoolAssert;
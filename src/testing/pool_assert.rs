@@ -0,0 +1,138 @@
+//! Assertions on the state of a pool
+
+use crate::Pool;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How often conditions are checked while waiting for them
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Assertions on a [`Pool`] for test suites
+///
+/// Connections are returned to the pool in the background when a commit
+/// on drop or forced rollback is pending, and the idle reaper closes
+/// connections on its own schedule. So instead of checking
+/// `Pool::status()` once, each assertion waits up to a timeout for the
+/// pool to get into the expected state and panics with the pool status if
+/// it does not.
+///
+/// ```rust,no_run
+/// # async fn example(pool: deadpool_oracle::Pool) {
+/// use deadpool_oracle::testing::PoolAssert;
+///
+/// // ... run the code under test, then
+/// let check = PoolAssert::new(&pool);
+/// check.assert_no_leaked_connections().await;
+/// check.assert_all_recycled_clean().await;
+/// # }
+/// ```
+pub struct PoolAssert<'a> {
+    pool: &'a Pool,
+    timeout: Duration,
+}
+
+impl<'a> PoolAssert<'a> {
+    /// Assert on `pool`, waiting up to 5 seconds for each condition
+    pub fn new(pool: &'a Pool) -> Self {
+        Self {
+            pool,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Set how long to wait for each condition
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Wait until `condition` holds, panicking with `what` after the timeout
+    async fn wait_until(&self, what: &str, condition: impl Fn(&Pool) -> bool) {
+        let deadline = Instant::now() + self.timeout;
+        while !condition(self.pool) {
+            if Instant::now() >= deadline {
+                panic!(
+                    "{} within {:?}, pool status: {:?}",
+                    what,
+                    self.timeout,
+                    self.pool.status()
+                );
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Assert that every connection is back in the pool
+    pub async fn assert_no_leaked_connections(&self) {
+        self.wait_until("connections still checked out", |pool| {
+            let status = pool.status();
+            status.available == status.size
+        })
+        .await;
+    }
+
+    /// Assert that every idle connection passes its health check
+    ///
+    /// The idle connections are checked out, which recycles them, and
+    /// returned. Panics if any recycle so far failed or found a connection
+    /// closed, killed or impossible to reset. Evictions of retired and
+    /// idle connections are intended and don't count.
+    pub async fn assert_all_recycled_clean(&self) {
+        let mut checked_out = Vec::new();
+        for _ in 0..self.pool.status().available {
+            match self.pool.try_get().await {
+                Ok(Some(conn)) => checked_out.push(conn),
+                Ok(None) => break,
+                Err(e) => panic!("recycling an idle connection failed: {}", e),
+            }
+        }
+        drop(checked_out);
+
+        let metrics = self.pool.recycle_metrics();
+        let unclean = metrics.rollback_failures
+            + metrics.ping_failures
+            + metrics.evicted_closed
+            + metrics.evicted_session_killed
+            + metrics.evicted_ping_failed
+            + metrics.evicted_reset_failed
+            + metrics.evicted_identity_mismatch;
+        assert!(unclean == 0, "connections failed to recycle: {:?}", metrics);
+    }
+
+    /// Wait until the pool holds `size` connections, idle or checked out
+    pub async fn wait_for_size(&self, size: usize) {
+        let what = format!("pool did not reach size {}", size);
+        self.wait_until(&what, |pool| pool.status().size == size)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PoolBuilder;
+    use oracle_rs::Config;
+
+    /// A pool that never gets a connection, as nothing listens on port 1
+    fn unreachable_pool() -> Pool {
+        let config = Config::new("127.0.0.1", 1, "FREEPDB1", "user", "password");
+        PoolBuilder::new(config).max_size(1).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_empty_pool_passes() {
+        let pool = unreachable_pool();
+        let check = PoolAssert::new(&pool);
+        check.assert_no_leaked_connections().await;
+        check.assert_all_recycled_clean().await;
+        check.wait_for_size(0).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    #[should_panic(expected = "pool did not reach size 1 within 1s")]
+    async fn test_wait_for_size_times_out() {
+        let pool = unreachable_pool();
+        let check = PoolAssert::new(&pool).timeout(Duration::from_secs(1));
+        check.wait_for_size(1).await;
+    }
+}
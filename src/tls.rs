@@ -0,0 +1,132 @@
+//! TLS settings shared by every endpoint of a pool
+//!
+//! [`PoolBuilder::tls_ca_cert()`](crate::PoolBuilder::tls_ca_cert),
+//! [`PoolBuilder::tls_server_name()`](crate::PoolBuilder::tls_server_name)
+//! and the client certificate of
+//! [`PoolBuilder::tls_client_cert()`](crate::PoolBuilder::tls_client_cert)
+//! or [`PoolBuilder::tls_wallet()`](crate::PoolBuilder::tls_wallet) are
+//! applied to the configuration of the primary, failover and shard
+//! endpoints alike whenever a connection is created, on top of any
+//! `TlsConfig` an endpoint has already, and switch it to TCPS.
+//!
+//! The driver always verifies the server certificate and its host name, and
+//! negotiates with the default TLS 1.2 and 1.3 cipher suites of rustls,
+//! neither of which it lets a client change. A lab database whose
+//! certificate doesn't name the host it is reached by is connected to by
+//! verifying against the name in the certificate with `tls_server_name()`,
+//! and a self-signed one by trusting it with `tls_ca_cert()`.
+
+use crate::redact::REDACTED;
+use oracle_rs::{Config, TlsMode};
+use std::fmt;
+
+/// TLS settings of a pool
+#[derive(Clone, Default)]
+pub(crate) struct TlsOptions {
+    /// PEM file of the certificates to trust instead of the system roots
+    pub(crate) ca_cert: Option<String>,
+    /// Name to expect in the server certificate instead of the host
+    pub(crate) server_name: Option<String>,
+    /// PEM files of the client certificate chain and its private key
+    pub(crate) client_cert: Option<(String, String)>,
+    /// Wallet directory and the password of its private key
+    pub(crate) wallet: Option<(String, Option<String>)>,
+}
+
+impl fmt::Debug for TlsOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let wallet = self.wallet.as_ref();
+        let wallet = wallet.map(|(path, password)| (path, password.as_ref().map(|_| REDACTED)));
+        f.debug_struct("TlsOptions")
+            .field("ca_cert", &self.ca_cert)
+            .field("server_name", &self.server_name)
+            .field("client_cert", &self.client_cert)
+            .field("wallet", &wallet)
+            .finish()
+    }
+}
+
+/// Make `config` connect with TLS and `options`
+pub(crate) fn apply(config: &mut Config, options: &TlsOptions) {
+    let mut tls = config.tls_config.take().unwrap_or_default();
+    if let Some(path) = &options.ca_cert {
+        tls.ca_cert_path = Some(path.clone());
+    }
+    if let Some(name) = &options.server_name {
+        tls.server_name = Some(name.clone());
+    }
+    if let Some((cert, key)) = &options.client_cert {
+        tls.client_cert_path = Some(cert.clone());
+        tls.client_key_path = Some(key.clone());
+    }
+    if let Some((path, password)) = &options.wallet {
+        tls.wallet_path = Some(path.clone());
+        tls.wallet_password = password.clone();
+    }
+    config.tls_config = Some(tls);
+    config.tls_mode = TlsMode::Require;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_rs::TlsConfig;
+
+    #[test]
+    fn test_apply_tls_options() {
+        let options = TlsOptions {
+            ca_cert: Some("/etc/oracle/lab-ca.pem".to_string()),
+            server_name: Some("db.lab.internal".to_string()),
+            ..TlsOptions::default()
+        };
+
+        let mut config = Config::new("10.0.0.5", 2484, "ORDERS", "app", "secret");
+        apply(&mut config, &options);
+        assert!(config.is_tls_enabled());
+        let tls = config.tls_config.unwrap();
+        assert_eq!(tls.ca_cert_path.as_deref(), Some("/etc/oracle/lab-ca.pem"));
+        assert_eq!(tls.server_name.as_deref(), Some("db.lab.internal"));
+        assert!(tls.verify_server);
+
+        // Settings of the endpoint that aren't overridden are kept
+        let mut config = Config::new("db", 2484, "ORDERS", "app", "secret")
+            .tls_config(TlsConfig::new().with_wallet("/etc/oracle/wallet", None));
+        let options = TlsOptions {
+            server_name: Some("db.example.com".to_string()),
+            ..TlsOptions::default()
+        };
+        apply(&mut config, &options);
+        let tls = config.tls_config.unwrap();
+        assert_eq!(tls.wallet_path.as_deref(), Some("/etc/oracle/wallet"));
+        assert_eq!(tls.ca_cert_path, None);
+        assert_eq!(tls.server_name.as_deref(), Some("db.example.com"));
+    }
+
+    #[test]
+    fn test_apply_client_cert() {
+        let options = TlsOptions {
+            client_cert: Some((
+                "/etc/oracle/app.crt".to_string(),
+                "/etc/oracle/app.key".to_string(),
+            )),
+            ..TlsOptions::default()
+        };
+        let mut config = Config::new("db", 2484, "ORDERS", "app", "secret");
+        apply(&mut config, &options);
+        assert!(config.is_tls_enabled());
+        let tls = config.tls_config.unwrap();
+        assert_eq!(tls.client_cert_path.as_deref(), Some("/etc/oracle/app.crt"));
+        assert_eq!(tls.client_key_path.as_deref(), Some("/etc/oracle/app.key"));
+        assert_eq!(tls.wallet_path, None);
+
+        let options = TlsOptions {
+            wallet: Some(("/etc/oracle/wallet".to_string(), Some("w4llet".to_string()))),
+            ..TlsOptions::default()
+        };
+        let mut config = Config::new("db", 2484, "ORDERS", "app", "secret");
+        apply(&mut config, &options);
+        let tls = config.tls_config.unwrap();
+        assert_eq!(tls.wallet_path.as_deref(), Some("/etc/oracle/wallet"));
+        assert_eq!(tls.wallet_password.as_deref(), Some("w4llet"));
+    }
+}
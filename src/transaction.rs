@@ -0,0 +1,753 @@
+//! Transaction guard for pooled connections
+//!
+//! Oracle starts a transaction implicitly with the first DML statement, so
+//! the guard does not issue anything on begin. It only makes sure the work
+//! is either committed or rolled back before the connection is used again.
+//!
+//! Nested scopes map to Oracle savepoints. Rolling back is async, so a guard
+//! dropped without being finished only records what has to be undone; the
+//! rollback is issued before the next statement run through a guard.
+
+use crate::audit::Binds;
+use crate::events::EventBus;
+use crate::task;
+use crate::{
+    AutonomousBlock, CallParams, CallResult, Clock, CursorRows, FromRow, FromValue, LobReader,
+    LobWriter, NamedBinds, Object, Page, PageRequest, PoolEvent,
+};
+use oracle_rs::{
+    BatchBinds, BatchResult, BindParam, Connection, Error, LobLocator, OracleType, PlsqlResult,
+    QueryResult, RefCursor, Row, Value,
+};
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Transaction isolation level applied to pooled sessions
+///
+/// See [`PoolBuilder::isolation_level()`](crate::PoolBuilder::isolation_level).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IsolationLevel {
+    /// Statements see data committed before they started (Oracle's default)
+    ReadCommitted,
+    /// Transactions see data committed before they started; conflicting
+    /// updates fail with ORA-08177
+    Serializable,
+}
+
+impl IsolationLevel {
+    /// Statement setting this level for all later transactions of a session
+    pub(crate) fn alter_session_sql(self) -> &'static str {
+        match self {
+            IsolationLevel::ReadCommitted => "ALTER SESSION SET ISOLATION_LEVEL = READ COMMITTED",
+            IsolationLevel::Serializable => "ALTER SESSION SET ISOLATION_LEVEL = SERIALIZABLE",
+        }
+    }
+}
+
+/// Block rolling back and then running `statements`, in one round trip
+///
+/// Used by the recycle to re-assert session settings a borrower may have
+/// changed.
+pub(crate) fn rollback_block_sql(statements: &[&str]) -> String {
+    let mut block = String::from("BEGIN ROLLBACK;");
+    for sql in statements {
+        let literal = sql.replace('\'', "''");
+        block.push_str(&format!(" EXECUTE IMMEDIATE '{}';", literal));
+    }
+    block.push_str(" END;");
+    block
+}
+
+/// Limit on how long a transaction may stay open
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HoldLimit {
+    pub(crate) limit: Duration,
+    pub(crate) force_rollback: bool,
+}
+
+impl HoldLimit {
+    /// Start timing a transaction that was just opened
+    ///
+    /// With `deterministic` set no timer is spawned; a transaction held past
+    /// the limit is reported when it ends instead.
+    pub(crate) fn watch(
+        self,
+        connection_id: u32,
+        events: EventBus,
+        clock: &Arc<dyn Clock>,
+        deterministic: bool,
+    ) -> HoldWatch {
+        let limit = self.limit;
+        let started = clock.now();
+        let timer = (!deterministic).then(|| {
+            let sleep = clock.sleep(limit);
+            let events = events.clone();
+            task::spawn("oracle_pool.hold_watch", async move {
+                sleep.await;
+                report_long_transaction(connection_id, limit, &events);
+            })
+        });
+
+        HoldWatch {
+            limit: self,
+            started,
+            clock: clock.clone(),
+            connection_id,
+            events,
+            timer,
+        }
+    }
+}
+
+fn report_long_transaction(connection_id: u32, held: Duration, events: &EventBus) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        connection_id,
+        held = ?held,
+        "transaction open longer than the maximum transaction duration"
+    );
+
+    events.emit(PoolEvent::LongTransaction {
+        connection_id,
+        held,
+    });
+}
+
+/// Timer for an open transaction, cancelled when dropped
+pub(crate) struct HoldWatch {
+    limit: HoldLimit,
+    started: Instant,
+    clock: Arc<dyn Clock>,
+    connection_id: u32,
+    events: EventBus,
+    /// `None` for deterministic pools, which report when the transaction ends
+    timer: Option<JoinHandle<()>>,
+}
+
+impl HoldWatch {
+    /// Whether the transaction has to be rolled back when the connection is returned
+    pub(crate) fn force_rollback(&self) -> bool {
+        let held = self.clock.now().saturating_duration_since(self.started);
+        self.limit.force_rollback && held >= self.limit.limit
+    }
+}
+
+impl Drop for HoldWatch {
+    fn drop(&mut self) {
+        match &self.timer {
+            Some(timer) => timer.abort(),
+            None => {
+                let held = self.clock.now().saturating_duration_since(self.started);
+                if held >= self.limit.limit {
+                    report_long_transaction(self.connection_id, self.limit.limit, &self.events);
+                }
+            }
+        }
+    }
+}
+
+/// Rollback still owed by a guard that was dropped without finishing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PendingRollback {
+    /// The whole transaction has to be rolled back
+    Transaction,
+    /// Work after the given savepoint has to be rolled back
+    Savepoint(String),
+}
+
+impl PendingRollback {
+    /// Combine with the rollback owed by a guard dropped later
+    ///
+    /// Guards are dropped innermost first, so a later savepoint rollback
+    /// always covers an earlier one.
+    fn merge(current: Option<Self>, dropped: Self) -> Self {
+        match current {
+            Some(PendingRollback::Transaction) => PendingRollback::Transaction,
+            _ => dropped,
+        }
+    }
+}
+
+/// Callback registered with `on_commit()` or `on_rollback()`
+pub(crate) struct Hook {
+    /// Nesting depth of the scope that registered the hook
+    depth: u32,
+    on_commit: bool,
+    run: Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>,
+}
+
+/// Run hooks of the given kind one after the other
+async fn run_hooks(hooks: Vec<Hook>, on_commit: bool) {
+    for hook in hooks.into_iter().filter(|hook| hook.on_commit == on_commit) {
+        (hook.run)().await;
+    }
+}
+
+/// A transaction on a pooled connection
+///
+/// Created by [`Object::begin()`]. Call [`commit()`](Self::commit) to make
+/// the work permanent. If the guard is dropped without committing, for
+/// example because a request handler returned early or was cancelled, the
+/// transaction is rolled back before the connection is reused: by the next
+/// [`Object::begin()`] or when the connection is returned to the pool.
+///
+/// Nested scopes are opened with [`savepoint()`](Self::savepoint). Statements
+/// should be run through the guard's own `execute()`, `query()`,
+/// `execute_plsql()` and `execute_batch()` so that work of a dropped nested
+/// scope is undone before they run.
+///
+/// Side effects that should only happen once the outcome is known, such as
+/// cache invalidation or outbox dispatch, can be registered with
+/// [`on_commit()`](Self::on_commit) and [`on_rollback()`](Self::on_rollback).
+pub struct Transaction<'a> {
+    conn: &'a mut Object,
+    depth: u32,
+    savepoint: Option<String>,
+    read_only: bool,
+    finished: bool,
+}
+
+impl<'a> Transaction<'a> {
+    pub(crate) fn new(conn: &'a mut Object) -> Self {
+        Self {
+            conn,
+            depth: 0,
+            savepoint: None,
+            read_only: false,
+            finished: false,
+        }
+    }
+
+    /// Open a nested scope backed by a savepoint
+    ///
+    /// Committing the nested guard keeps its work as part of this
+    /// transaction; rolling it back, or dropping it, undoes only the work
+    /// done since the savepoint. Helpers can therefore take a
+    /// `&mut Transaction` and open their own scope without knowing whether
+    /// they run at the top level or nested.
+    pub async fn savepoint(&mut self) -> Result<Transaction<'_>, Error> {
+        self.settle().await?;
+
+        self.conn.savepoint_seq += 1;
+        let name = format!("DEADPOOL_SP_{}", self.conn.savepoint_seq);
+        self.conn.savepoint(&name).await?;
+
+        Ok(Transaction {
+            conn: &mut *self.conn,
+            depth: self.depth + 1,
+            savepoint: Some(name),
+            read_only: self.read_only,
+            finished: false,
+        })
+    }
+
+    /// Make this a read-only transaction
+    ///
+    /// Must run before any other statement of the transaction.
+    pub(crate) async fn set_read_only(&mut self) -> Result<(), Error> {
+        self.conn.execute("SET TRANSACTION READ ONLY", &[]).await?;
+        self.read_only = true;
+        Ok(())
+    }
+
+    /// Whether this transaction was started with `Object::begin_read_only()`
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Whether this guard is a nested scope opened with `savepoint()`
+    pub fn is_nested(&self) -> bool {
+        self.savepoint.is_some()
+    }
+
+    /// Run `f` once the transaction has been committed
+    ///
+    /// Hooks run in registration order after the outermost guard commits,
+    /// and are awaited by its `commit()`. Hooks registered in a nested scope
+    /// are discarded if that scope is rolled back.
+    pub fn on_commit<F, Fut>(&self, f: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.add_hook(true, f);
+    }
+
+    /// Run `f` once the transaction, or this nested scope, has been rolled back
+    ///
+    /// Hooks are awaited by `rollback()`. If the guard is dropped without
+    /// finishing, they are spawned on the Tokio runtime instead.
+    pub fn on_rollback<F, Fut>(&self, f: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.add_hook(false, f);
+    }
+
+    fn add_hook<F, Fut>(&self, on_commit: bool, f: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.conn.hooks.lock().unwrap().push(Hook {
+            depth: self.depth,
+            on_commit,
+            run: Box::new(move || Box::pin(f())),
+        });
+    }
+
+    /// Remove the hooks registered in this scope and the scopes nested in it
+    fn take_hooks(&self) -> Vec<Hook> {
+        let mut hooks = self.conn.hooks.lock().unwrap();
+        let (taken, kept) = hooks.drain(..).partition(|hook| hook.depth >= self.depth);
+        *hooks = kept;
+        taken
+    }
+
+    /// Commit the transaction
+    ///
+    /// For a nested scope this only keeps its work; nothing is committed
+    /// until the outermost guard commits.
+    pub async fn commit(mut self) -> Result<(), Error> {
+        self.settle().await?;
+
+        if self.savepoint.is_some() {
+            // The enclosing scope now decides the outcome
+            for hook in self.conn.hooks.get_mut().unwrap().iter_mut() {
+                if hook.depth == self.depth {
+                    hook.depth -= 1;
+                }
+            }
+            self.finished = true;
+            return Ok(());
+        }
+
+        self.conn.commit().await?;
+        self.finished = true;
+        self.conn.hold_watch = None;
+        run_hooks(self.take_hooks(), true).await;
+        Ok(())
+    }
+
+    /// Roll back the transaction, or a nested scope up to its savepoint
+    pub async fn rollback(mut self) -> Result<(), Error> {
+        match &self.savepoint {
+            Some(name) => self.conn.rollback_to_savepoint(name).await?,
+            None => {
+                self.conn.rollback().await?;
+                self.conn.hold_watch = None;
+            }
+        }
+        // Anything owed by nested scopes is covered by this rollback
+        self.conn.rollback_pending.lock().unwrap().take();
+        self.finished = true;
+        run_hooks(self.take_hooks(), false).await;
+        Ok(())
+    }
+
+    /// Run statements in an autonomous transaction
+    ///
+    /// They are committed independently of this transaction; see
+    /// [`Object::autonomous()`].
+    pub async fn autonomous<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut AutonomousBlock),
+    {
+        crate::autonomous::run(self.conn, f).await
+    }
+
+    /// Execute a statement in this transaction
+    pub async fn execute(&self, sql: &str, params: &[Value]) -> Result<QueryResult, Error> {
+        self.settle().await?;
+        self.conn.execute(sql, params).await
+    }
+
+    /// Run a query in this transaction
+    pub async fn query(&self, sql: &str, params: &[Value]) -> Result<QueryResult, Error> {
+        self.settle().await?;
+        self.conn.query(sql, params).await
+    }
+
+    /// Run a query in this transaction and fetch all of its rows
+    ///
+    /// See [`Object::query_all()`].
+    pub async fn query_all(&self, sql: &str, params: &[Value]) -> Result<QueryResult, Error> {
+        self.settle().await?;
+        self.conn.query_all(sql, params).await
+    }
+
+    /// Run a query in this transaction, fetching `fetch_size` rows per round trip
+    ///
+    /// See [`Object::query_all_with_fetch_size()`].
+    pub async fn query_all_with_fetch_size(
+        &self,
+        sql: &str,
+        params: &[Value],
+        fetch_size: u32,
+    ) -> Result<QueryResult, Error> {
+        self.settle().await?;
+        self.conn
+            .query_all_with_fetch_size(sql, params, fetch_size)
+            .await
+    }
+
+    /// Execute a statement with named binds in this transaction
+    ///
+    /// See [`Object::execute_named()`].
+    pub async fn execute_named(&self, sql: &str, binds: &NamedBinds) -> Result<QueryResult, Error> {
+        let (sql, params) = binds.to_positional(sql)?;
+        self.execute(&sql, &params).await
+    }
+
+    /// Run a query with named binds in this transaction
+    ///
+    /// See [`Object::execute_named()`].
+    pub async fn query_named(&self, sql: &str, binds: &NamedBinds) -> Result<QueryResult, Error> {
+        let (sql, params) = binds.to_positional(sql)?;
+        self.query(&sql, &params).await
+    }
+
+    /// Execute a DML statement once per row of binds in this transaction
+    ///
+    /// See [`Object::execute_many()`].
+    pub async fn execute_many(&self, sql: &str, rows: &[&[Value]]) -> Result<BatchResult, Error> {
+        self.settle().await?;
+        self.conn.execute_many(sql, rows).await
+    }
+
+    /// Execute a DML statement with a `RETURNING ... INTO` clause in this
+    /// transaction
+    ///
+    /// See [`Object::execute_returning()`].
+    pub async fn execute_returning(
+        &self,
+        sql: &str,
+        params: &[Value],
+        returning: &[OracleType],
+    ) -> Result<Row, Error> {
+        self.settle().await?;
+        self.conn.execute_returning(sql, params, returning).await
+    }
+
+    /// Execute a DML statement with a `RETURNING ... INTO` clause once per
+    /// row of binds in this transaction
+    ///
+    /// See [`Object::execute_many_returning()`].
+    pub async fn execute_many_returning(
+        &self,
+        sql: &str,
+        rows: &[&[Value]],
+        returning: &[OracleType],
+    ) -> Result<Vec<Row>, Error> {
+        self.settle().await?;
+        self.conn.execute_many_returning(sql, rows, returning).await
+    }
+
+    /// Call a stored procedure in this transaction
+    ///
+    /// See [`Object::call()`].
+    pub async fn call(&self, name: &str, params: &CallParams) -> Result<CallResult, Error> {
+        self.settle().await?;
+        crate::call::call(self.conn, name, params).await
+    }
+
+    /// Iterate the rows of a REF CURSOR returned by a call in this transaction
+    ///
+    /// See [`Object::cursor_rows()`].
+    pub fn cursor_rows(&self, cursor: RefCursor) -> CursorRows<'_> {
+        self.conn.cursor_rows(cursor)
+    }
+
+    /// Stream the contents of a CLOB or BLOB in this transaction
+    ///
+    /// See [`Object::lob_reader()`].
+    pub fn lob_reader(&self, locator: LobLocator) -> LobReader<'_> {
+        self.conn.lob_reader(locator)
+    }
+
+    /// Stream data into a CLOB or BLOB in this transaction
+    ///
+    /// See [`Object::lob_writer()`].
+    pub fn lob_writer(&self, locator: LobLocator) -> LobWriter<'_> {
+        self.conn.lob_writer(locator)
+    }
+
+    /// Run a query in this transaction and map every row to `T`
+    ///
+    /// See [`Object::query_as()`].
+    pub async fn query_as<T: FromRow>(&self, sql: &str, params: &[Value]) -> Result<Vec<T>, Error> {
+        self.settle().await?;
+        crate::from_row::query_as(self.conn, sql, params).await
+    }
+
+    /// Run a query in this transaction that must return exactly one row
+    ///
+    /// See [`Object::query_one()`].
+    pub async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, Error> {
+        self.settle().await?;
+        crate::from_row::query_one(self.conn, sql, params).await
+    }
+
+    /// Run a query in this transaction that returns at most one row
+    ///
+    /// See [`Object::query_opt()`].
+    pub async fn query_opt(&self, sql: &str, params: &[Value]) -> Result<Option<Row>, Error> {
+        self.settle().await?;
+        crate::from_row::query_opt(self.conn, sql, params).await
+    }
+
+    /// Run a query in this transaction returning a single value
+    ///
+    /// See [`Object::query_scalar()`].
+    pub async fn query_scalar<T: FromValue>(
+        &self,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<T, Error> {
+        self.settle().await?;
+        crate::from_row::query_scalar(self.conn, sql, params).await
+    }
+
+    /// Run one page of a query in this transaction
+    ///
+    /// See [`Object::query_paged()`].
+    pub async fn query_paged(
+        &self,
+        sql: &str,
+        params: &[Value],
+        request: PageRequest,
+    ) -> Result<Page<Row>, Error> {
+        self.settle().await?;
+        crate::page::query_paged(self.conn, sql, params, &request).await
+    }
+
+    /// Execute a PL/SQL block in this transaction
+    pub async fn execute_plsql(
+        &self,
+        sql: &str,
+        params: &[BindParam],
+    ) -> Result<PlsqlResult, Error> {
+        self.settle().await?;
+        self.conn
+            .observe(
+                sql,
+                Binds::Params(params),
+                |conn| conn.execute_plsql(sql, params),
+                |_| None,
+            )
+            .await
+    }
+
+    /// Execute a batch of DML in this transaction
+    pub async fn execute_batch(&self, batch: &BatchBinds) -> Result<BatchResult, Error> {
+        self.settle().await?;
+        let binds = Binds::Count(batch.row_count() * batch.column_count());
+        self.conn
+            .observe(
+                batch.sql(),
+                binds,
+                |conn| conn.execute_batch(batch),
+                |result| Some(result.total_rows_affected),
+            )
+            .await
+    }
+
+    /// Issue the rollback owed by a dropped nested scope
+    async fn settle(&self) -> Result<(), Error> {
+        let pending = self.conn.rollback_pending.lock().unwrap().take();
+        if let Some(PendingRollback::Savepoint(name)) = pending {
+            if let Err(e) = self.conn.rollback_to_savepoint(&name).await {
+                *self.conn.rollback_pending.lock().unwrap() =
+                    Some(PendingRollback::Savepoint(name));
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let dropped = match self.savepoint.take() {
+                Some(name) => PendingRollback::Savepoint(name),
+                None => PendingRollback::Transaction,
+            };
+            let mut pending = self.conn.rollback_pending.lock().unwrap();
+            *pending = Some(PendingRollback::merge(pending.take(), dropped));
+            drop(pending);
+
+            let hooks = self.take_hooks();
+            if hooks.iter().any(|hook| !hook.on_commit) {
+                if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                    let hooks = run_hooks(hooks, false);
+                    task::spawn_on("oracle_pool.rollback_hooks", hooks, &runtime);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokioClock;
+    use std::sync::Mutex;
+
+    fn clock() -> Arc<dyn Clock> {
+        Arc::new(TokioClock)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_long_transaction_reported() {
+        let events = EventBus::default();
+        let mut receiver = events.subscribe();
+        let limit = HoldLimit {
+            limit: Duration::from_secs(30),
+            force_rollback: true,
+        };
+
+        let watch = limit.watch(7, events.clone(), &clock(), false);
+        assert!(!watch.force_rollback());
+        tokio::time::sleep(Duration::from_secs(31)).await;
+
+        assert!(watch.force_rollback());
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            PoolEvent::LongTransaction {
+                connection_id: 7,
+                held: Duration::from_secs(30),
+            }
+        );
+    }
+
+    /// A clock that only moves when told to, with timers that never fire
+    struct ManualClock(Mutex<Instant>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+
+        fn sleep(&self, _duration: Duration) -> crate::ClockSleep {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hold_time_on_pool_clock() {
+        let limit = HoldLimit {
+            limit: Duration::from_secs(30),
+            force_rollback: true,
+        };
+        let manual = Arc::new(ManualClock(Mutex::new(Instant::now())));
+        let clock: Arc<dyn Clock> = manual.clone();
+
+        let watch = limit.watch(7, EventBus::default(), &clock, false);
+        assert!(!watch.force_rollback());
+        *manual.0.lock().unwrap() += Duration::from_secs(31);
+        assert!(watch.force_rollback());
+    }
+
+    #[test]
+    fn test_deterministic_hold_reported_when_finished() {
+        let events = EventBus::default();
+        let mut receiver = events.subscribe();
+        let limit = HoldLimit {
+            limit: Duration::from_secs(30),
+            force_rollback: true,
+        };
+        let manual = Arc::new(ManualClock(Mutex::new(Instant::now())));
+        let clock: Arc<dyn Clock> = manual.clone();
+
+        // Runs outside a runtime, so nothing can have been spawned
+        drop(limit.watch(7, events.clone(), &clock, true));
+        assert!(receiver.try_recv().is_err());
+
+        let watch = limit.watch(7, events.clone(), &clock, true);
+        *manual.0.lock().unwrap() += Duration::from_secs(31);
+        assert!(watch.force_rollback());
+        assert!(receiver.try_recv().is_err());
+        drop(watch);
+
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            PoolEvent::LongTransaction {
+                connection_id: 7,
+                held: Duration::from_secs(30),
+            }
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_finished_transaction_not_reported() {
+        let events = EventBus::default();
+        let mut receiver = events.subscribe();
+        let limit = HoldLimit {
+            limit: Duration::from_secs(30),
+            force_rollback: false,
+        };
+
+        let watch = limit.watch(7, events.clone(), &clock(), false);
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        drop(watch);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_hooks_of_one_kind_in_order() {
+        let ran = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let hook = |name: &'static str, on_commit: bool| {
+            let ran = ran.clone();
+            Hook {
+                depth: 0,
+                on_commit,
+                run: Box::new(move || Box::pin(async move { ran.lock().unwrap().push(name) })),
+            }
+        };
+
+        let hooks = vec![
+            hook("invalidate", true),
+            hook("compensate", false),
+            hook("dispatch", true),
+        ];
+        run_hooks(hooks, true).await;
+
+        assert_eq!(*ran.lock().unwrap(), vec!["invalidate", "dispatch"]);
+    }
+
+    #[test]
+    fn test_pending_rollback_merge() {
+        let inner = PendingRollback::Savepoint("DEADPOOL_SP_2".to_string());
+        let outer = PendingRollback::Savepoint("DEADPOOL_SP_1".to_string());
+
+        assert_eq!(PendingRollback::merge(None, inner.clone()), inner);
+        assert_eq!(PendingRollback::merge(Some(inner), outer.clone()), outer);
+        assert_eq!(
+            PendingRollback::merge(Some(outer), PendingRollback::Transaction),
+            PendingRollback::Transaction
+        );
+        assert_eq!(
+            PendingRollback::merge(
+                Some(PendingRollback::Transaction),
+                PendingRollback::Savepoint("X".to_string())
+            ),
+            PendingRollback::Transaction
+        );
+    }
+}
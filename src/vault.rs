@@ -0,0 +1,161 @@
+//! Credentials from HashiCorp Vault
+//!
+//! [`VaultProvider`] reads the user name and password of a pool from a KV
+//! version 2 secret, or has the database secrets engine issue them, using a
+//! [`vaultrs`] client the application authenticated already.
+
+use crate::credentials::{CredentialProvider, Credentials, CredentialsFuture};
+use oracle_rs::Error;
+use std::collections::HashMap;
+use vaultrs::client::VaultClient;
+
+/// Where in Vault the credentials come from
+enum Source {
+    Kv2 {
+        mount: String,
+        path: String,
+        username_key: String,
+        password_key: String,
+    },
+    DatabaseRole {
+        mount: String,
+        role: String,
+    },
+    DatabaseStaticRole {
+        mount: String,
+        role: String,
+    },
+}
+
+/// Credential provider reading from HashiCorp Vault
+///
+/// Dynamic credentials of the database secrets engine expire with their
+/// lease, so pair [`database_role()`](Self::database_role) with
+/// `PoolBuilder::credential_refresh()` at an interval well within the
+/// lease duration.
+///
+/// ```rust,no_run
+/// # fn example(config: oracle_rs::Config) -> Result<(), Box<dyn std::error::Error>> {
+/// use deadpool_oracle::{PoolBuilder, VaultProvider};
+/// use vaultrs::client::{VaultClient, VaultClientSettingsBuilder};
+///
+/// let client = VaultClient::new(
+///     VaultClientSettingsBuilder::default()
+///         .address("https://vault.example.com:8200")
+///         .token(std::env::var("VAULT_TOKEN")?)
+///         .build()?,
+/// )?;
+/// let pool = PoolBuilder::new(config)
+///     .credential_provider(VaultProvider::kv2(client, "secret", "orders/oracle"))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct VaultProvider {
+    client: VaultClient,
+    source: Source,
+}
+
+impl VaultProvider {
+    /// Read the `username` and `password` fields of a KV version 2 secret
+    pub fn kv2(client: VaultClient, mount: &str, path: &str) -> Self {
+        Self {
+            client,
+            source: Source::Kv2 {
+                mount: mount.to_string(),
+                path: path.to_string(),
+                username_key: "username".to_string(),
+                password_key: "password".to_string(),
+            },
+        }
+    }
+
+    /// Have the database secrets engine create a user for a role
+    ///
+    /// Every refresh creates another user; connections of the previous one
+    /// keep working until Vault revokes its lease.
+    pub fn database_role(client: VaultClient, mount: &str, role: &str) -> Self {
+        Self {
+            client,
+            source: Source::DatabaseRole {
+                mount: mount.to_string(),
+                role: role.to_string(),
+            },
+        }
+    }
+
+    /// Read the current password of a static role of the database secrets
+    /// engine, which Vault rotates
+    pub fn database_static_role(client: VaultClient, mount: &str, role: &str) -> Self {
+        Self {
+            client,
+            source: Source::DatabaseStaticRole {
+                mount: mount.to_string(),
+                role: role.to_string(),
+            },
+        }
+    }
+
+    /// Set the fields of a KV secret holding the user name and password
+    ///
+    /// Default is `username` and `password`. Has no effect on the database
+    /// secrets engine.
+    pub fn keys(mut self, username: &str, password: &str) -> Self {
+        if let Source::Kv2 {
+            username_key,
+            password_key,
+            ..
+        } = &mut self.source
+        {
+            *username_key = username.to_string();
+            *password_key = password.to_string();
+        }
+        self
+    }
+
+    async fn read(&self) -> Result<Credentials, Error> {
+        match &self.source {
+            Source::Kv2 {
+                mount,
+                path,
+                username_key,
+                password_key,
+            } => {
+                let read = vaultrs::kv2::read(&self.client, mount, path);
+                let secret: HashMap<String, String> =
+                    read.await.map_err(|e| vault_error(path, e))?;
+                let field = |key: &str| {
+                    secret.get(key).cloned().ok_or_else(|| {
+                        Error::AuthenticationFailed(format!(
+                            "Vault secret {} has no field {}",
+                            path, key
+                        ))
+                    })
+                };
+                Ok(Credentials::new(field(username_key)?, field(password_key)?))
+            }
+            Source::DatabaseRole { mount, role } => {
+                let creds = vaultrs::database::role::creds(&self.client, mount, role)
+                    .await
+                    .map_err(|e| vault_error(role, e))?;
+                Ok(Credentials::new(creds.username, creds.password))
+            }
+            Source::DatabaseStaticRole { mount, role } => {
+                let creds = vaultrs::database::static_role::creds(&self.client, mount, role)
+                    .await
+                    .map_err(|e| vault_error(role, e))?;
+                Ok(Credentials::new(creds.username, creds.password))
+            }
+        }
+    }
+}
+
+impl CredentialProvider for VaultProvider {
+    fn credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(self.read())
+    }
+}
+
+fn vault_error(name: &str, err: vaultrs::error::ClientError) -> Error {
+    Error::AuthenticationFailed(format!("reading {} from Vault: {}", name, err))
+}
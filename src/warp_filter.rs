@@ -0,0 +1,98 @@
+//! Filters for warp handlers
+//!
+//! warp has no application state; the filters returned by
+//! [`Pool::filter()`], [`Pool::conn_filter()`] and
+//! [`Pool::transaction_filter()`] hold a handle to the pool instead. A
+//! failed checkout rejects the request with a [`PoolRejection`], which
+//! [`PoolRejection::recover()`] turns into 503 Service Unavailable.
+
+use crate::{DbConn, DbTransaction, Pool, PoolRejection};
+use std::convert::Infallible;
+use warp::http::StatusCode;
+use warp::reject::Reject;
+use warp::reply::{Reply, Response};
+use warp::{Filter, Rejection};
+
+impl Pool {
+    /// Filter extracting this pool, for handlers that need the pool itself
+    pub fn filter(&self) -> impl Filter<Extract = (Pool,), Error = Infallible> + Clone {
+        let pool = self.clone();
+        warp::any().map(move || pool.clone())
+    }
+
+    /// Filter checking out a connection for the handler
+    ///
+    /// ```rust,no_run
+    /// use deadpool_oracle::{DbConn, Pool, PoolRejection};
+    /// use warp::Filter;
+    ///
+    /// async fn user_count(conn: DbConn) -> Result<String, warp::Rejection> {
+    ///     match conn.query("SELECT COUNT(*) FROM users", &[]).await {
+    ///         Ok(result) => Ok(format!("{:?}", result.rows[0].get_i64(0))),
+    ///         Err(e) => Ok(e.to_string()),
+    ///     }
+    /// }
+    ///
+    /// # fn example(pool: Pool) {
+    /// let routes = warp::path!("users" / "count")
+    ///     .and(pool.conn_filter())
+    ///     .and_then(user_count)
+    ///     .recover(PoolRejection::recover);
+    /// # }
+    /// ```
+    pub fn conn_filter(&self) -> impl Filter<Extract = (DbConn,), Error = Rejection> + Clone {
+        self.filter().and_then(|pool: Pool| async move {
+            let result = DbConn::checkout(&pool).await;
+            result.map_err(warp::reject::custom)
+        })
+    }
+
+    /// Filter checking out a connection with a transaction open for the handler
+    pub fn transaction_filter(
+        &self,
+    ) -> impl Filter<Extract = (DbTransaction,), Error = Rejection> + Clone {
+        self.filter().and_then(|pool: Pool| async move {
+            let result = DbTransaction::begin(&pool).await;
+            result.map_err(warp::reject::custom)
+        })
+    }
+}
+
+impl Reject for PoolRejection {}
+
+impl PoolRejection {
+    /// Respond to a failed checkout with 503 Service Unavailable
+    ///
+    /// For `Filter::recover()`; other rejections are passed on.
+    pub async fn recover(rejection: Rejection) -> Result<Response, Rejection> {
+        match rejection.find::<PoolRejection>() {
+            Some(e) => {
+                let status = StatusCode::SERVICE_UNAVAILABLE;
+                Ok(warp::reply::with_status(e.to_string(), status).into_response())
+            }
+            None => Err(rejection),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::tests::unreachable_pool;
+
+    #[tokio::test]
+    async fn test_unavailable_pool_rejected_with_503() {
+        let pool = unreachable_pool();
+        let routes = pool
+            .conn_filter()
+            .map(|_conn: DbConn| "called")
+            .recover(PoolRejection::recover);
+
+        let response = warp::test::request().reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let filter = pool.transaction_filter();
+        let rejection = warp::test::request().filter(&filter).await.err().unwrap();
+        assert!(rejection.find::<PoolRejection>().is_some());
+    }
+}
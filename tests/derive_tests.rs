@@ -0,0 +1,45 @@
+//! Tests for `#[derive(FromRow)]`
+
+#![cfg(feature = "derive")]
+
+use deadpool_oracle::oracle_rs::{Row, Value};
+use deadpool_oracle::FromRow;
+
+#[derive(Debug, PartialEq, FromRow)]
+struct Employee {
+    id: i64,
+    #[oracle(rename = "LAST_NAME")]
+    name: String,
+    manager_id: Option<i64>,
+}
+
+#[test]
+fn test_derive_from_row() {
+    let row = Row::with_names(
+        vec![
+            Value::Integer(7),
+            Value::String("SMITH".to_string()),
+            Value::Null,
+        ],
+        vec![
+            "ID".to_string(),
+            "LAST_NAME".to_string(),
+            "MANAGER_ID".to_string(),
+        ],
+    );
+
+    assert_eq!(
+        Employee::from_row(&row).unwrap(),
+        Employee {
+            id: 7,
+            name: "SMITH".to_string(),
+            manager_id: None,
+        }
+    );
+}
+
+#[test]
+fn test_derive_from_row_missing_column() {
+    let row = Row::with_names(vec![Value::Integer(7)], vec!["ID".to_string()]);
+    assert!(Employee::from_row(&row).is_err());
+}
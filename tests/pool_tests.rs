@@ -3,8 +3,9 @@
 //! These tests require a running Oracle database. Set the ORACLE_TEST_URL
 //! environment variable to run them.
 
-use deadpool_oracle::{ConfigExt, PoolBuilder};
-use oracle_rs::Config;
+use deadpool_oracle::{AnyPool, AnyValue, ConfigExt, NlsConfig, Object, PoolBuilder};
+use oracle_rs::{Config, Value};
+use std::sync::Arc;
 use std::time::Duration;
 
 fn get_test_config() -> Option<Config> {
@@ -92,6 +93,71 @@ async fn test_pool_reuse() {
     assert_eq!(status.available, 1);
 }
 
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_pool_connection_stats() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    {
+        let conn = pool.get().await.expect("Failed to get connection");
+        let stats = Object::stats(&conn);
+        assert_eq!(stats.checkouts, 1);
+        assert_eq!(stats.recycle_count, 0);
+    }
+
+    // Same connection again, recycled once
+    let conn = pool.get().await.expect("Failed to get connection");
+    let stats = Object::stats(&conn);
+    assert_eq!(stats.checkouts, 2);
+    assert_eq!(stats.recycle_count, 1);
+    assert_eq!(stats.connection_id, conn.id());
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_acquire_latency() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    {
+        let conn = pool.get().await.expect("Failed to get connection");
+        let latency = conn.acquire_latency();
+        let create = latency.create.expect("first checkout creates a connection");
+        assert_eq!(latency.wait + create, latency.total);
+    }
+
+    // Reused, so nothing is created
+    let conn = pool.get().await.expect("Failed to get connection");
+    let latency = conn.acquire_latency();
+    assert_eq!(latency.create, None);
+    assert_eq!(latency.wait, latency.total);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_pool_health_check() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let report = pool.health_check(Duration::from_secs(10)).await;
+    assert!(report.healthy, "health check failed: {:?}", report.error);
+    assert_eq!(report.status.size, 1);
+    assert_eq!(report.status.available, 1);
+}
+
 #[tokio::test]
 #[ignore = "requires Oracle database"]
 async fn test_pool_concurrent() {
@@ -186,6 +252,1735 @@ async fn test_pool_transaction_rollback() {
     }
 }
 
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_transaction_guard() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let mut conn = pool.get().await.expect("Failed to get connection");
+    conn.execute(
+        "BEGIN EXECUTE IMMEDIATE 'CREATE TABLE tx_guard_test (id NUMBER)'; EXCEPTION WHEN OTHERS THEN IF SQLCODE != -955 THEN RAISE; END IF; END;",
+        &[]
+    ).await.expect("Failed to create table");
+
+    // Dropped without commit - rolled back by the next begin()
+    {
+        let tx = conn.begin().await.expect("Failed to begin");
+        tx.execute("INSERT INTO tx_guard_test (id) VALUES (1)", &[])
+            .await
+            .expect("Insert failed");
+    }
+
+    let tx = conn.begin().await.expect("Failed to begin");
+    tx.execute("INSERT INTO tx_guard_test (id) VALUES (2)", &[])
+        .await
+        .expect("Insert failed");
+    tx.commit().await.expect("Failed to commit");
+
+    let result = conn
+        .query("SELECT COUNT(*) FROM tx_guard_test", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(result.rows[0].get_i64(0), Some(1));
+
+    conn.execute("DROP TABLE tx_guard_test", &[]).await.ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_transaction_savepoints() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let mut conn = pool.get().await.expect("Failed to get connection");
+    conn.execute(
+        "BEGIN EXECUTE IMMEDIATE 'CREATE TABLE tx_savepoint_test (id NUMBER)'; EXCEPTION WHEN OTHERS THEN IF SQLCODE != -955 THEN RAISE; END IF; END;",
+        &[]
+    ).await.expect("Failed to create table");
+
+    let mut tx = conn.begin().await.expect("Failed to begin");
+    tx.execute("INSERT INTO tx_savepoint_test (id) VALUES (1)", &[])
+        .await
+        .expect("Insert failed");
+
+    // Kept as part of the outer transaction
+    let inner = tx.savepoint().await.expect("Failed to create savepoint");
+    assert!(inner.is_nested());
+    inner
+        .execute("INSERT INTO tx_savepoint_test (id) VALUES (2)", &[])
+        .await
+        .expect("Insert failed");
+    inner.commit().await.expect("Failed to release savepoint");
+
+    // Dropped - undone before the next statement
+    {
+        let inner = tx.savepoint().await.expect("Failed to create savepoint");
+        inner
+            .execute("INSERT INTO tx_savepoint_test (id) VALUES (3)", &[])
+            .await
+            .expect("Insert failed");
+    }
+
+    let result = tx
+        .query("SELECT COUNT(*) FROM tx_savepoint_test", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(result.rows[0].get_i64(0), Some(2));
+    tx.rollback().await.expect("Failed to roll back");
+
+    conn.execute("DROP TABLE tx_savepoint_test", &[]).await.ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_read_only_transaction() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let mut conn = pool.get().await.expect("Failed to get connection");
+    conn.execute(
+        "BEGIN EXECUTE IMMEDIATE 'CREATE TABLE tx_read_only_test (id NUMBER)'; EXCEPTION WHEN OTHERS THEN IF SQLCODE != -955 THEN RAISE; END IF; END;",
+        &[]
+    ).await.expect("Failed to create table");
+
+    let tx = conn.begin_read_only().await.expect("Failed to begin");
+    assert!(tx.is_read_only());
+
+    tx.query("SELECT COUNT(*) FROM tx_read_only_test", &[])
+        .await
+        .expect("Query failed");
+    let result = tx
+        .execute("INSERT INTO tx_read_only_test (id) VALUES (1)", &[])
+        .await;
+    assert!(
+        result.is_err(),
+        "DML should fail in a read-only transaction"
+    );
+    tx.rollback().await.expect("Failed to roll back");
+
+    conn.execute("DROP TABLE tx_read_only_test", &[]).await.ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_commit_on_drop() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    {
+        let conn = pool.get().await.expect("Failed to get connection");
+        conn.execute(
+            "BEGIN EXECUTE IMMEDIATE 'CREATE TABLE commit_on_drop_test (id NUMBER)'; EXCEPTION WHEN OTHERS THEN IF SQLCODE != -955 THEN RAISE; END IF; END;",
+            &[]
+        ).await.expect("Failed to create table");
+    }
+
+    {
+        let mut conn = pool.get().await.expect("Failed to get connection");
+        conn.commit_on_drop(true);
+        conn.execute("INSERT INTO commit_on_drop_test (id) VALUES (1)", &[])
+            .await
+            .expect("Insert failed");
+    }
+
+    // Waits for the commit to finish, as the pool has a single connection
+    let conn = pool.get().await.expect("Failed to get connection");
+    let result = conn
+        .query("SELECT COUNT(*) FROM commit_on_drop_test", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(result.rows[0].get_i64(0), Some(1));
+
+    conn.execute("DROP TABLE commit_on_drop_test", &[])
+        .await
+        .ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_credential_provider() {
+    use deadpool_oracle::{CredentialProvider, Credentials, CredentialsFuture};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Returns a stale password first, as if the secret was rotated since
+    struct Rotated {
+        calls: AtomicUsize,
+        username: String,
+        password: String,
+    }
+
+    impl CredentialProvider for Rotated {
+        fn credentials(&self) -> CredentialsFuture<'_> {
+            let password = match self.calls.fetch_add(1, Ordering::SeqCst) {
+                0 => "stale".to_string(),
+                _ => self.password.clone(),
+            };
+            Box::pin(async move { Ok(Credentials::new(&self.username, password)) })
+        }
+    }
+
+    let username = std::env::var("ORACLE_USER").unwrap_or_else(|_| "system".to_string());
+    let password = std::env::var("ORACLE_PASSWORD").unwrap_or_else(|_| "testpass".to_string());
+    let mut config = get_test_config().expect("ORACLE_TEST_URL not set");
+    config.set_password("not the password");
+
+    let provider = Rotated {
+        calls: AtomicUsize::new(0),
+        username,
+        password,
+    };
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .credential_provider(provider)
+        .build()
+        .expect("Failed to build pool");
+
+    // The refused login asks the provider again
+    let conn = pool.get().await.expect("Failed to get connection");
+    let one: i64 = conn
+        .query_scalar("SELECT 1 FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(one, 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_call_timeout() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .call_timeout(Some(Duration::from_secs(1)))
+        .build()
+        .expect("Failed to build pool");
+    let sleep = "BEGIN DBMS_SESSION.SLEEP(3); END;";
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let err = conn
+        .execute(sleep, &[])
+        .await
+        .expect_err("Call should time out");
+    assert!(matches!(
+        err,
+        oracle_rs::Error::OracleError { code: 3156, .. }
+    ));
+    drop(conn);
+
+    // The connection is discarded, and the override lifts the limit
+    let mut conn = pool.get().await.expect("Failed to get connection");
+    assert_eq!(pool.stats().connections_created, 2);
+    conn.call_timeout(None);
+    conn.execute(sleep, &[]).await.expect("Call failed");
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_transaction_hooks() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let committed = Arc::new(AtomicUsize::new(0));
+    let rolled_back = Arc::new(AtomicUsize::new(0));
+
+    let mut conn = pool.get().await.expect("Failed to get connection");
+    let mut tx = conn.begin().await.expect("Failed to begin");
+
+    let counter = committed.clone();
+    tx.on_commit(move || async move {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    // Hooks of a rolled back scope: commit hook discarded, rollback hook run
+    let inner = tx.savepoint().await.expect("Failed to create savepoint");
+    let counter = committed.clone();
+    inner.on_commit(move || async move {
+        counter.fetch_add(10, Ordering::SeqCst);
+    });
+    let counter = rolled_back.clone();
+    inner.on_rollback(move || async move {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    inner
+        .rollback()
+        .await
+        .expect("Failed to roll back savepoint");
+    assert_eq!(rolled_back.load(Ordering::SeqCst), 1);
+
+    tx.commit().await.expect("Failed to commit");
+    assert_eq!(committed.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_autonomous_transaction() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let mut conn = pool.get().await.expect("Failed to get connection");
+    conn.execute(
+        "BEGIN EXECUTE IMMEDIATE 'CREATE TABLE autonomous_test (msg VARCHAR2(100))'; EXCEPTION WHEN OTHERS THEN IF SQLCODE != -955 THEN RAISE; END IF; END;",
+        &[]
+    ).await.expect("Failed to create table");
+
+    let tx = conn.begin().await.expect("Failed to begin");
+    tx.autonomous(|block| {
+        block.execute(
+            "INSERT INTO autonomous_test (msg) VALUES (:1)",
+            &[oracle_rs::Value::String("kept".to_string())],
+        );
+    })
+    .await
+    .expect("Autonomous block failed");
+    tx.rollback().await.expect("Failed to roll back");
+
+    // Survived the rollback of the surrounding transaction
+    let result = conn
+        .query("SELECT COUNT(*) FROM autonomous_test", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(result.rows[0].get_i64(0), Some(1));
+
+    conn.execute("DROP TABLE autonomous_test", &[]).await.ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_query_helpers() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+
+    let count: i64 = conn
+        .query_scalar("SELECT COUNT(*) FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(count, 1);
+
+    let row = conn
+        .query_one("SELECT 1 AS ONE FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(deadpool_oracle::column::<i64>(&row, "one").unwrap(), 1);
+
+    let none = conn
+        .query_opt("SELECT 1 FROM DUAL WHERE 1 = 0", &[])
+        .await
+        .expect("Query failed");
+    assert!(none.is_none());
+
+    let many = conn
+        .query_opt("SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 2", &[])
+        .await;
+    assert!(many.is_err());
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_named_binds() {
+    use deadpool_oracle::binds;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+
+    let result = conn
+        .query_named(
+            "SELECT :a + :b + :a FROM DUAL",
+            &binds! { "a" => 1, "b" => 2 },
+        )
+        .await
+        .expect("Query failed");
+    assert_eq!(result.rows[0].get_i64(0), Some(4));
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_execute_many() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.execute("DROP TABLE execute_many_test", &[]).await.ok();
+    conn.execute(
+        "CREATE TABLE execute_many_test (id NUMBER PRIMARY KEY)",
+        &[],
+    )
+    .await
+    .expect("Failed to create table");
+
+    // 2500 rows span three chunks; row 2000 duplicates row 0
+    let rows: Vec<Vec<Value>> = (0..2500)
+        .map(|i| vec![Value::Integer(if i == 2000 { 0 } else { i })])
+        .collect();
+    let rows: Vec<&[Value]> = rows.iter().map(Vec::as_slice).collect();
+
+    let result = conn
+        .execute_many("INSERT INTO execute_many_test (id) VALUES (:1)", &rows)
+        .await
+        .expect("Batch failed");
+    assert_eq!(result.success_count, 2499);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].row_index, 2000);
+
+    conn.execute("DROP TABLE execute_many_test", &[]).await.ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_execute_returning() {
+    use oracle_rs::OracleType;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.execute("DROP TABLE returning_test", &[]).await.ok();
+    conn.execute(
+        "CREATE TABLE returning_test (id NUMBER GENERATED ALWAYS AS IDENTITY, name VARCHAR2(50))",
+        &[],
+    )
+    .await
+    .expect("Failed to create table");
+
+    let sql = "INSERT INTO returning_test (name) VALUES (:1) RETURNING id, name INTO :2, :3";
+    let returning = [OracleType::Number, OracleType::Varchar];
+
+    let row = conn
+        .execute_returning(sql, &[Value::String("first".to_string())], &returning)
+        .await
+        .expect("Insert failed");
+    assert_eq!(row.get_i64(0), Some(1));
+
+    let rows: Vec<Vec<Value>> = (0..150)
+        .map(|i| vec![Value::String(format!("row {}", i))])
+        .collect();
+    let rows: Vec<&[Value]> = rows.iter().map(Vec::as_slice).collect();
+    let returned = conn
+        .execute_many_returning(sql, &rows, &returning)
+        .await
+        .expect("Insert failed");
+    assert_eq!(returned.len(), 150);
+    assert_eq!(returned[149].get_i64(0), Some(151));
+    assert_eq!(returned[149].get_string(1), Some("row 149"));
+
+    conn.rollback().await.ok();
+    conn.execute("DROP TABLE returning_test", &[]).await.ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_call_procedure() {
+    use deadpool_oracle::CallParams;
+    use oracle_rs::OracleType;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.execute(
+        "CREATE OR REPLACE PROCEDURE call_test_proc(a IN NUMBER, b IN OUT NUMBER, c OUT VARCHAR2) AS \
+         BEGIN b := a + b; c := 'sum ' || b; END;",
+        &[],
+    )
+    .await
+    .expect("Failed to create procedure");
+
+    let result = conn
+        .call(
+            "call_test_proc",
+            &CallParams::new()
+                .input(2)
+                .input_output(3, 22)
+                .output(OracleType::Varchar, 100),
+        )
+        .await
+        .expect("Call failed");
+    assert_eq!(result.get::<i64>(1).unwrap(), 5);
+    assert_eq!(result.get::<String>(2).unwrap(), "sum 5");
+
+    conn.execute("DROP PROCEDURE call_test_proc", &[])
+        .await
+        .ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_ref_cursor_rows() {
+    use deadpool_oracle::CallParams;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.execute(
+        "CREATE OR REPLACE PROCEDURE cursor_test_proc(n IN NUMBER, c OUT SYS_REFCURSOR) AS \
+         BEGIN OPEN c FOR SELECT LEVEL AS ID FROM DUAL CONNECT BY LEVEL <= n; END;",
+        &[],
+    )
+    .await
+    .expect("Failed to create procedure");
+
+    // More rows than one fetch returns
+    let result = conn
+        .call(
+            "cursor_test_proc",
+            &CallParams::new().input(250).output_cursor(),
+        )
+        .await
+        .expect("Call failed");
+    let rows = conn.cursor_rows(result.cursor(1).expect("No cursor"));
+    let ids: Vec<(i64,)> = rows.collect_as().await.expect("Fetch failed");
+    assert_eq!(ids.len(), 250);
+    assert_eq!(ids[249], (250,));
+
+    conn.execute("DROP PROCEDURE cursor_test_proc", &[])
+        .await
+        .ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_lob_streaming() {
+    use oracle_rs::LobValue;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+
+    // Several chunks, with multi-byte characters straddling chunk boundaries
+    let text = "blåbærsyltetøy ".repeat(20_000);
+
+    conn.execute("DROP TABLE lob_stream_test", &[]).await.ok();
+    conn.execute("CREATE TABLE lob_stream_test (doc CLOB)", &[])
+        .await
+        .expect("Failed to create table");
+    conn.execute("INSERT INTO lob_stream_test VALUES (EMPTY_CLOB())", &[])
+        .await
+        .expect("Insert failed");
+
+    let row = conn
+        .query_one("SELECT doc FROM lob_stream_test FOR UPDATE", &[])
+        .await
+        .expect("Query failed");
+    let Some(Value::Lob(LobValue::Locator(locator))) = row.get(0) else {
+        panic!("expected a LOB locator");
+    };
+    let mut writer = conn.lob_writer(locator.clone());
+    writer
+        .write_all(text.as_bytes())
+        .await
+        .expect("Write failed");
+    writer.shutdown().await.expect("Write failed");
+
+    let row = conn
+        .query_one("SELECT doc FROM lob_stream_test", &[])
+        .await
+        .expect("Query failed");
+    let Some(Value::Lob(LobValue::Locator(locator))) = row.get(0) else {
+        panic!("expected a LOB locator");
+    };
+    let mut read = String::new();
+    conn.lob_reader(locator.clone())
+        .read_to_string(&mut read)
+        .await
+        .expect("Read failed");
+    assert_eq!(read, text);
+
+    conn.execute("DROP TABLE lob_stream_test", &[]).await.ok();
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_statement_cache_stats() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    for _ in 0..3 {
+        let conn = pool.get().await.expect("Failed to get connection");
+        conn.query("SELECT 42 FROM DUAL", &[])
+            .await
+            .expect("Query failed");
+    }
+
+    // Cached on the first checkout, reused on the next two
+    let cache = pool.stats().statement_cache;
+    assert_eq!((cache.hits, cache.misses), (2, 1));
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_prepare_on_create() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .prepare_on_create(&["SELECT :1 FROM DUAL", "DELETE FROM DUAL"])
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let result = conn
+        .query("SELECT :1 FROM DUAL", &[Value::Integer(7)])
+        .await
+        .expect("Query failed");
+    assert_eq!(result.rows[0].get_i64(0), Some(7));
+
+    // Parsed when the connection was created; the DELETE was refused
+    let cache = pool.stats().statement_cache;
+    assert_eq!((cache.hits, cache.misses), (1, 1));
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_query_all() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .fetch_array_size(250)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let sql = "SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 1000";
+
+    let first = conn.query(sql, &[]).await.expect("Query failed");
+    assert!(first.has_more_rows);
+
+    let all = conn.query_all(sql, &[]).await.expect("Query failed");
+    assert_eq!(all.rows.len(), 1000);
+    assert!(!all.has_more_rows);
+    assert_eq!(all.rows[999].get_i64(0), Some(1000));
+
+    let all = conn
+        .query_all_with_fetch_size(sql, &[], 7)
+        .await
+        .expect("Query failed");
+    assert_eq!(all.rows.len(), 1000);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_get_tagged() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(3)
+        .build()
+        .expect("Failed to build pool");
+
+    let conns = vec![
+        pool.get().await.expect("Failed to get connection"),
+        pool.get().await.expect("Failed to get connection"),
+        pool.get().await.expect("Failed to get connection"),
+    ];
+    conns[2].set_tag(Some("reporting"));
+    let tagged_id = conns[2].id();
+    drop(conns);
+    let recycles = pool.recycle_metrics().recycles();
+
+    let conn = pool
+        .get_tagged("reporting")
+        .await
+        .expect("Failed to get connection");
+    assert_eq!(conn.id(), tagged_id);
+    assert_eq!(conn.tag().as_deref(), Some("reporting"));
+    // The untagged connection passed over on the way isn't health-checked
+    assert_eq!(pool.recycle_metrics().recycles(), recycles + 2);
+
+    // Nothing tagged: any connection, untagged
+    let other = pool
+        .get_tagged("batch")
+        .await
+        .expect("Failed to get connection");
+    assert_eq!(other.tag(), None);
+    assert_eq!(pool.status().size, 3);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_get_tagged_fixup() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .tag_fixup(|conn, tag, _current| {
+            let sql = format!("ALTER SESSION SET NLS_LANGUAGE = {}", tag);
+            Box::pin(async move { conn.execute(&sql, &[]).await.map(drop) })
+        })
+        .build()
+        .expect("Failed to build pool");
+    let language = "SELECT value FROM nls_session_parameters WHERE parameter = 'NLS_LANGUAGE'";
+
+    let conn = pool
+        .get_tagged("GERMAN")
+        .await
+        .expect("Failed to get connection");
+    assert_eq!(conn.tag().as_deref(), Some("GERMAN"));
+    let current: String = conn
+        .query_scalar(language, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current, "GERMAN");
+    drop(conn);
+
+    let conn = pool
+        .get_tagged("AMERICAN")
+        .await
+        .expect("Failed to get connection");
+    let current: String = conn
+        .query_scalar(language, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current, "AMERICAN");
+    drop(conn);
+
+    // A failed fixup closes the half set-up connection
+    assert!(pool.get_tagged("NO_SUCH_LANGUAGE").await.is_err());
+    let conn = pool.get().await.expect("Failed to get connection");
+    assert_eq!(conn.tag(), None);
+    assert_eq!(pool.stats().connections_created, 2);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_query_observer() {
+    use deadpool_oracle::QueryEvent;
+    use std::sync::{Arc, Mutex};
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let observed = seen.clone();
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .query_observer(move |event: &QueryEvent<'_>| {
+            observed.lock().unwrap().push((
+                event.sql.to_string(),
+                event.bind_count,
+                event.rows_affected,
+                event.error.is_some(),
+            ));
+        })
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.query("SELECT :1 FROM DUAL", &[Value::Integer(1)])
+        .await
+        .expect("Query failed");
+    conn.query("SELECT * FROM no_such_table", &[])
+        .await
+        .expect_err("Query should fail");
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert_eq!(
+        seen[0],
+        ("SELECT :1 FROM DUAL".to_string(), 1, Some(1), false)
+    );
+    assert!(seen[1].3);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_audit_sink() {
+    use deadpool_oracle::AuditEvent;
+    use std::sync::{Arc, Mutex};
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let audited = seen.clone();
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .audit_sink(move |event: &AuditEvent<'_>| {
+            audited.lock().unwrap().push((
+                event.connection_id,
+                event.client_identifier.map(str::to_string),
+                event.sql.to_string(),
+                event.binds.to_vec(),
+                event.error.is_some(),
+            ));
+        })
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.set_client_identifier("alice")
+        .await
+        .expect("Failed to set client identifier");
+    conn.query("SELECT :1 FROM DUAL", &[Value::String("secret".into())])
+        .await
+        .expect("Query failed");
+    conn.query("SELECT * FROM no_such_table", &[])
+        .await
+        .expect_err("Query should fail");
+    let id = conn.id();
+    drop(conn);
+
+    // The identifier is cleared with the checkout
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.query("SELECT 1 FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 3);
+    assert_eq!(
+        seen[0],
+        (
+            id,
+            Some("alice".to_string()),
+            "SELECT :1 FROM DUAL".to_string(),
+            vec!["VARCHAR2"],
+            false
+        )
+    );
+    assert!(seen[1].4);
+    assert_eq!(seen[2].1, None);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_get_readonly() {
+    use deadpool_oracle::Credentials;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+    let admin = PoolBuilder::new(config.clone())
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+    let conn = admin.get().await.expect("Failed to get connection");
+    let _ = conn.execute("DROP USER deadpool_reporting", &[]).await;
+    let create = "CREATE USER deadpool_reporting IDENTIFIED BY reporting_pw";
+    conn.execute(create, &[])
+        .await
+        .expect("Failed to create user");
+    conn.execute("GRANT CREATE SESSION TO deadpool_reporting", &[])
+        .await
+        .expect("Failed to grant");
+    drop(conn);
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .readonly_credentials(Credentials::new("deadpool_reporting", "reporting_pw"))
+        .build()
+        .expect("Failed to build pool");
+    let conn = pool.get_readonly().await.expect("Failed to get connection");
+    let result = conn
+        .query("SELECT USER FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(result.rows[0].get_string(0), Some("DEADPOOL_REPORTING"));
+    let err = conn
+        .execute("CREATE TABLE deadpool_reporting_t (id NUMBER)", &[])
+        .await
+        .expect_err("Write should be refused");
+    assert!(err.to_string().contains("ORA-01031"), "{}", err);
+    drop(conn);
+    pool.close();
+
+    let conn = admin.get().await.expect("Failed to get connection");
+    conn.execute("DROP USER deadpool_reporting", &[])
+        .await
+        .expect("Failed to drop user");
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_expected_identity() {
+    use deadpool_oracle::SessionIdentity;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+    let pool = PoolBuilder::new(config.clone())
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+    let conn = pool.get().await.expect("Failed to get connection");
+    let result = conn
+        .query("SELECT role FROM session_roles", &[])
+        .await
+        .expect("Query failed");
+    let roles: Vec<String> = result
+        .rows
+        .iter()
+        .filter_map(|row| row.get_string(0).map(str::to_string))
+        .collect();
+    assert!(!roles.is_empty(), "test user has no roles to disable");
+    drop(conn);
+
+    let pool = PoolBuilder::new(config.clone())
+        .max_size(1)
+        .expected_identity(SessionIdentity::new().roles(roles))
+        .build()
+        .expect("Failed to build pool");
+    let conn = pool.get().await.expect("Failed to get connection");
+    let first_id = conn.id();
+    conn.execute("SET ROLE NONE", &[])
+        .await
+        .expect("Failed to disable roles");
+    drop(conn);
+
+    // The session without its roles is replaced on the next checkout
+    let conn = pool.get().await.expect("Failed to get connection");
+    assert_ne!(conn.id(), first_id);
+    assert_eq!(pool.recycle_metrics().evicted_identity_mismatch, 1);
+    drop(conn);
+
+    // A new session that doesn't match fails the checkout
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .expected_identity(SessionIdentity::new().user("NO_SUCH_USER"))
+        .build()
+        .expect("Failed to build pool");
+    let Err(err) = pool.get().await else {
+        panic!("checkout should fail");
+    };
+    assert!(err.to_string().contains("NO_SUCH_USER"), "{}", err);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_try_get() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool
+        .try_get()
+        .await
+        .expect("Failed to get connection")
+        .expect("Pool should have room");
+    assert!(pool.try_get().await.expect("try_get failed").is_none());
+
+    drop(conn);
+    assert!(pool.try_get().await.expect("try_get failed").is_some());
+    assert_eq!(pool.stats().timeouts, 0);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_with_connection_reconnects() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let attempts = Arc::new(AtomicU32::new(0));
+    let result = pool
+        .with_connection(|conn| {
+            let attempts = attempts.clone();
+            Box::pin(async move {
+                // Simulate a connection lost before its first use
+                if attempts.fetch_add(1, Ordering::Relaxed) == 0 {
+                    conn.mark_closed();
+                }
+                conn.query("SELECT 1 FROM DUAL", &[]).await
+            })
+        })
+        .await
+        .expect("with_connection failed");
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(attempts.load(Ordering::Relaxed), 2);
+    assert_eq!(pool.stats().recycle.evicted_closed, 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_with_connection_counts_direct_use() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(2)
+        .build()
+        .expect("Failed to build pool");
+
+    let attempts = Arc::new(AtomicU32::new(0));
+    let result = pool
+        .with_connection(|conn| {
+            let attempts = attempts.clone();
+            Box::pin(async move {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                // Runs on the Connection itself, out of the pool's sight
+                conn.ping().await?;
+                conn.mark_closed();
+                conn.query("SELECT 1 FROM DUAL", &[]).await
+            })
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(attempts.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_get_replaces_dead_connection() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(2)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let dead = conn.id();
+    // Simulate a connection lost before its first use
+    conn.mark_closed();
+    let result = conn
+        .query("SELECT 1 FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(result.rows.len(), 1);
+    assert_ne!(conn.id(), dead);
+    assert_eq!(pool.stats().recycle.evicted_closed, 1);
+    // The dead connection left the pool, its replacement stays
+    drop(conn);
+    assert_eq!(pool.status().size, 1);
+
+    // Later statements are not retried
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.query("SELECT 1 FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+    conn.mark_closed();
+    assert!(conn.query("SELECT 1 FROM DUAL", &[]).await.is_err());
+
+    let pool = PoolBuilder::new(get_test_config().expect("ORACLE_TEST_URL not set"))
+        .max_size(2)
+        .reconnect_retries(0)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.mark_closed();
+    assert!(conn.query("SELECT 1 FROM DUAL", &[]).await.is_err());
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_drain_and_refresh() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(2)
+        .build()
+        .expect("Failed to build pool");
+
+    let busy = pool.get().await.expect("Failed to get connection");
+    drop(pool.get().await.expect("Failed to get connection"));
+    assert_eq!(pool.status().size, 2);
+
+    // The idle connection is closed at once, the busy one once returned
+    pool.drain_and_refresh(None).await;
+    assert_eq!(pool.status().size, 1);
+    busy.query("SELECT 1 FROM DUAL", &[])
+        .await
+        .expect("In-flight work should be undisturbed");
+    drop(busy);
+    assert_eq!(pool.status().size, 0);
+    assert_eq!(pool.stats().recycle.evicted_retired, 2);
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.query("SELECT 1 FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_get_for_container() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+    let con_name = "SELECT SYS_CONTEXT('USERENV', 'CON_NAME') FROM DUAL";
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let home: String = conn
+        .query_scalar(con_name, &[])
+        .await
+        .expect("Query failed");
+    drop(conn);
+
+    let conn = pool
+        .get_for_container("cdb$root")
+        .await
+        .expect("Failed to switch container");
+    let current: String = conn
+        .query_scalar(con_name, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current, "CDB$ROOT");
+    drop(conn);
+
+    // Recycling moves the session back
+    let conn = pool.get().await.expect("Failed to get connection");
+    let current: String = conn
+        .query_scalar(con_name, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current, home);
+    assert_eq!(pool.stats().connections_created, 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_get_with_schema() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+    let schema = "SELECT SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA') FROM DUAL";
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let own: String = conn.query_scalar(schema, &[]).await.expect("Query failed");
+    drop(conn);
+
+    let conn = pool
+        .get_with_schema("sys")
+        .await
+        .expect("Failed to switch schema");
+    let current: String = conn.query_scalar(schema, &[]).await.expect("Query failed");
+    assert_eq!(current, "SYS");
+    drop(conn);
+
+    // Recycling switches the session back
+    let conn = pool.get().await.expect("Failed to get connection");
+    let current: String = conn.query_scalar(schema, &[]).await.expect("Query failed");
+    assert_eq!(current, own);
+    assert!(pool.get_with_schema("SYS; DROP USER x").await.is_err());
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_session_context() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    // A context namespace and the package trusted to set it
+    {
+        let setup = PoolBuilder::new(config.clone())
+            .max_size(1)
+            .build()
+            .expect("Failed to build pool");
+        let conn = setup.get().await.expect("Failed to get connection");
+        conn.execute(
+            "CREATE OR REPLACE PACKAGE pool_test_ctx_pkg AS \
+             PROCEDURE set_tenant(tenant NUMBER); PROCEDURE clear; END;",
+            &[],
+        )
+        .await
+        .expect("Failed to create package");
+        conn.execute(
+            "CREATE OR REPLACE PACKAGE BODY pool_test_ctx_pkg AS \
+             PROCEDURE set_tenant(tenant NUMBER) IS BEGIN \
+             DBMS_SESSION.SET_CONTEXT('POOL_TEST_CTX', 'TENANT', tenant); END; \
+             PROCEDURE clear IS BEGIN DBMS_SESSION.CLEAR_ALL_CONTEXT('POOL_TEST_CTX'); END; \
+             END;",
+            &[],
+        )
+        .await
+        .expect("Failed to create package body");
+        conn.execute(
+            "CREATE OR REPLACE CONTEXT pool_test_ctx USING pool_test_ctx_pkg",
+            &[],
+        )
+        .await
+        .expect("Failed to create context");
+    }
+
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let observed = seen.clone();
+    let pool = PoolBuilder::new(config)
+        .max_size(2)
+        .session_context(
+            |conn| {
+                Box::pin(async move {
+                    let sql = "BEGIN pool_test_ctx_pkg.set_tenant(42); END;";
+                    conn.execute(sql, &[]).await.map(drop)
+                })
+            },
+            "BEGIN pool_test_ctx_pkg.clear; END;",
+        )
+        .query_observer(move |event: &deadpool_oracle::QueryEvent<'_>| {
+            observed.lock().unwrap().push(event.sql.to_string());
+        })
+        .build()
+        .expect("Failed to build pool");
+    let tenant = "SELECT SYS_CONTEXT('POOL_TEST_CTX', 'TENANT') FROM DUAL";
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let current: Option<String> = conn.query_scalar(tenant, &[]).await.expect("Query failed");
+    assert_eq!(current.as_deref(), Some("42"));
+    drop(conn);
+
+    // The hook's statements are the pool's, not the caller's
+    assert_eq!(*seen.lock().unwrap(), [tenant]);
+
+    // Cleared on recycle; try_get() doesn't run the hook
+    let conn = pool.try_get().await.expect("Failed to get connection");
+    let conn = conn.expect("connection available");
+    let current: Option<String> = conn.query_scalar(tenant, &[]).await.expect("Query failed");
+    assert_eq!(current, None);
+
+    // get_tagged() runs the hook on the tagged connection it finds, behind
+    // an untagged one
+    let untagged = pool.get().await.expect("Failed to get connection");
+    conn.set_tag(Some("reporting"));
+    drop(untagged);
+    drop(conn);
+    let conn = pool
+        .get_tagged("reporting")
+        .await
+        .expect("Failed to get connection");
+    assert_eq!(conn.tag().as_deref(), Some("reporting"));
+    let current: Option<String> = conn.query_scalar(tenant, &[]).await.expect("Query failed");
+    assert_eq!(current.as_deref(), Some("42"));
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_set_context() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+    let module_action = "SELECT SYS_CONTEXT('USERENV', 'MODULE') || '/' || \
+                         SYS_CONTEXT('USERENV', 'ACTION') FROM DUAL";
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.set_context("orders", "create_order")
+        .await
+        .expect("Failed to set context");
+    let current: String = conn
+        .query_scalar(module_action, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current, "orders/create_order");
+    drop(conn);
+
+    // The labels stay with the session
+    let conn = pool.get().await.expect("Failed to get connection");
+    let current: String = conn
+        .query_scalar(module_action, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current, "orders/create_order");
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_client_identifier() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let monitor = PoolBuilder::new(config.clone())
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+    let client_id = "SELECT SYS_CONTEXT('USERENV', 'CLIENT_IDENTIFIER') FROM DUAL";
+
+    let conn = pool
+        .get_with_client_identifier("alice")
+        .await
+        .expect("Failed to get connection");
+    let current: Option<String> = conn
+        .query_scalar(client_id, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current.as_deref(), Some("alice"));
+    drop(conn);
+
+    // Cleared once the connection is back, before anyone checks it out
+    while pool.status().available == 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let sessions = "SELECT COUNT(*) FROM v$session WHERE client_identifier = 'alice'";
+    let monitor = monitor.get().await.expect("Failed to get connection");
+    let count: i64 = monitor
+        .query_scalar(sessions, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(count, 0);
+
+    // Cleared for the next borrower
+    let conn = pool.get().await.expect("Failed to get connection");
+    let current: Option<String> = conn
+        .query_scalar(client_id, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current, None);
+    assert_eq!(pool.stats().connections_created, 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_nls() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .nls(
+            NlsConfig::new()
+                .date_format("YYYY-MM-DD")
+                .numeric_characters(",."),
+        )
+        .build()
+        .expect("Failed to build pool");
+    let formatted = "SELECT TO_CHAR(DATE '2024-01-31') || ' ' || TO_CHAR(1.5) FROM DUAL";
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let current: String = conn
+        .query_scalar(formatted, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current, "2024-01-31 1,5");
+    conn.execute("ALTER SESSION SET NLS_DATE_FORMAT = 'DD.MM.YYYY'", &[])
+        .await
+        .expect("Failed to alter session");
+    drop(conn);
+
+    // Recycling restores the settings
+    let conn = pool.get().await.expect("Failed to get connection");
+    let current: String = conn
+        .query_scalar(formatted, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(current, "2024-01-31 1,5");
+    assert_eq!(pool.stats().connections_created, 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_time_zone() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .time_zone("Europe/Oslo")
+        .build()
+        .expect("Failed to build pool");
+    let current = "SELECT SESSIONTIMEZONE FROM DUAL";
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let time_zone: String = conn.query_scalar(current, &[]).await.expect("Query failed");
+    assert_eq!(time_zone, "Europe/Oslo");
+    conn.execute("ALTER SESSION SET TIME_ZONE = '-05:00'", &[])
+        .await
+        .expect("Failed to alter session");
+    drop(conn);
+
+    // Recycling restores the time zone
+    let conn = pool.get().await.expect("Failed to get connection");
+    let time_zone: String = conn.query_scalar(current, &[]).await.expect("Query failed");
+    assert_eq!(time_zone, "Europe/Oslo");
+    assert_eq!(pool.stats().connections_created, 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_session_setting() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+    let current = "SELECT value FROM v$parameter WHERE name = 'statistics_level'";
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let before: String = conn.query_scalar(current, &[]).await.expect("Query failed");
+    conn.with_session_setting("statistics_level", "ALL")
+        .await
+        .expect("Failed to change setting");
+    let level: String = conn.query_scalar(current, &[]).await.expect("Query failed");
+    assert_eq!(level, "ALL");
+    drop(conn);
+
+    // Recycling sets it back
+    let conn = pool.get().await.expect("Failed to get connection");
+    let level: String = conn.query_scalar(current, &[]).await.expect("Query failed");
+    assert_eq!(level, before);
+    assert_eq!(pool.stats().connections_created, 1);
+
+    assert!(conn
+        .with_session_setting("no_such_parameter", "1")
+        .await
+        .is_err());
+
+    // NLS parameters are set back to the session's value, not the instance's
+    let date_format =
+        "SELECT value FROM nls_session_parameters WHERE parameter = 'NLS_DATE_FORMAT'";
+    let before: String = conn
+        .query_scalar(date_format, &[])
+        .await
+        .expect("Query failed");
+    conn.with_session_setting("nls_date_format", "YYYY-MM-DD HH24:MI")
+        .await
+        .expect("Failed to change setting");
+    drop(conn);
+    let conn = pool.get().await.expect("Failed to get connection");
+    let format: String = conn
+        .query_scalar(date_format, &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(format, before);
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_session_params() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .session_params([
+            ("optimizer_mode", "FIRST_ROWS_10"),
+            ("nls_sort", "BINARY_CI"),
+        ])
+        .build()
+        .expect("Failed to build pool");
+
+    // Creating the connection applies all of them
+    let conn = pool.get().await.expect("Failed to get connection");
+    let sort: String = conn
+        .query_scalar("SELECT SYS_CONTEXT('USERENV', 'NLS_SORT') FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(sort, "BINARY_CI");
+    drop(conn);
+
+    // An invalid name fails connection creation
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .session_params([("optimizer_mode = ALL_ROWS --", "x")])
+        .build()
+        .expect("Failed to build pool");
+    assert!(pool.get().await.is_err());
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_any_pool() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+    let pool: Arc<dyn AnyPool> = Arc::new(pool);
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let rows = conn
+        .query(
+            "SELECT :1 AS n, :2 AS s FROM DUAL",
+            &[AnyValue::Int(42), AnyValue::Text("hello".to_string())],
+        )
+        .await
+        .expect("Query failed");
+    assert_eq!(rows.columns, ["N", "S"]);
+    assert_eq!(
+        rows.rows,
+        [vec![AnyValue::Int(42), AnyValue::Text("hello".to_string())]]
+    );
+    conn.rollback().await.expect("Rollback failed");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires Oracle database"]
+async fn test_interact() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let mut conn = pool.get().await.expect("Failed to get connection");
+    let total = conn
+        .interact(|conn| {
+            let handle = tokio::runtime::Handle::current();
+            let sql = "SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 100";
+            let result = handle.block_on(conn.query(sql, &[]))?;
+            let total: i64 = result.rows.iter().filter_map(|row| row.get_i64(0)).sum();
+            Ok::<_, oracle_rs::Error>(total)
+        })
+        .await
+        .expect("Query failed");
+    assert_eq!(total, 5050);
+
+    // The connection is usable again afterwards
+    conn.query("SELECT 1 FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_idle_timeout() {
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .idle_timeout(Some(Duration::from_secs(1)))
+        .build()
+        .expect("Failed to build pool");
+
+    drop(pool.get().await.expect("Failed to get connection"));
+    assert_eq!(pool.status().size, 1);
+
+    // Closed in the background
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    assert_eq!(pool.status().size, 0);
+    assert_eq!(pool.recycle_metrics().evicted_idle, 1);
+
+    drop(pool.get().await.expect("Failed to get connection"));
+    assert_eq!(pool.close_all_idle().await, 1);
+    assert_eq!(pool.status().size, 0);
+    assert_eq!(pool.recycle_metrics().evicted_idle, 2);
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_fault_injector() {
+    use deadpool_oracle::testing::FaultInjector;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let faults = FaultInjector::new().disconnects(1.0);
+    let pool = PoolBuilder::new(config.clone())
+        .max_size(1)
+        .fault_injector(faults.clone())
+        .build()
+        .expect("Failed to build pool");
+
+    // Disconnected as it is handed out
+    let conn = pool.get().await.expect("Failed to get connection");
+    assert!(conn.query("SELECT 1 FROM DUAL", &[]).await.is_err());
+    drop(conn);
+    assert_eq!(faults.disconnects_injected(), 1);
+
+    let faults = FaultInjector::new().recycle_failures(1.0);
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .fault_injector(faults.clone())
+        .build()
+        .expect("Failed to build pool");
+
+    // The health check of the idle connection fails, so it is replaced
+    drop(pool.get().await.expect("Failed to get connection"));
+    drop(pool.get().await.expect("Failed to get connection"));
+    assert_eq!(faults.recycles_failed(), 1);
+    assert_eq!(pool.recycle_metrics().evicted_ping_failed, 1);
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_chaos_idle_kills() {
+    use deadpool_oracle::testing::FaultInjector;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let faults = FaultInjector::new().idle_kills(1.0, Duration::from_millis(100));
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .fault_injector(faults.clone())
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let first_id = conn.id();
+    drop(conn);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(faults.idle_connections_killed() >= 1);
+
+    // The killed connection is replaced on checkout
+    let conn = pool.get().await.expect("Failed to get connection");
+    assert_ne!(conn.id(), first_id);
+    conn.query("SELECT 1 FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(pool.recycle_metrics().evicted_closed, 1);
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_pool_assert() {
+    use deadpool_oracle::testing::PoolAssert;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(2)
+        .commit_on_drop(true)
+        .build()
+        .expect("Failed to build pool");
+    let check = PoolAssert::new(&pool);
+
+    let first = pool.get().await.expect("Failed to get connection");
+    let second = pool.get().await.expect("Failed to get connection");
+    check.wait_for_size(2).await;
+
+    // Returned by the background commit
+    drop(first);
+    drop(second);
+    check.assert_no_leaked_connections().await;
+    check.assert_all_recycled_clean().await;
+}
+
+#[cfg(feature = "testcontainers")]
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_test_pool() {
+    use deadpool_oracle::testing::TestPool;
+
+    let db = TestPool::start_with(|builder| builder.max_size(1))
+        .await
+        .expect("Failed to start Oracle container");
+    assert_eq!(db.status().max_size, 1);
+
+    let conn = db.get().await.expect("Failed to get connection");
+    let result = conn
+        .query("SELECT 1 FROM DUAL", &[])
+        .await
+        .expect("Query failed");
+    assert_eq!(result.row_count(), 1);
+    drop(conn);
+
+    db.stop().await.expect("Failed to remove container");
+}
+
+#[tokio::test]
+#[ignore = "requires Oracle database"]
+async fn test_query_paged() {
+    use deadpool_oracle::PageRequest;
+
+    let config = get_test_config().expect("ORACLE_TEST_URL not set");
+
+    let pool = PoolBuilder::new(config)
+        .max_size(1)
+        .build()
+        .expect("Failed to build pool");
+
+    let conn = pool.get().await.expect("Failed to get connection");
+    let sql = "SELECT LEVEL AS ID FROM DUAL CONNECT BY LEVEL <= 25";
+
+    let page = conn
+        .query_paged(
+            &format!("{} ORDER BY ID", sql),
+            &[],
+            PageRequest::page(2, 10).with_total(),
+        )
+        .await
+        .expect("Query failed");
+    assert_eq!(page.items.len(), 5);
+    assert_eq!(page.total, Some(25));
+    assert!(!page.has_more());
+
+    let mut request = Some(PageRequest::keyset("ID", None, 10));
+    let mut seen = 0;
+    while let Some(next) = request {
+        let page = conn
+            .query_paged(sql, &[], next)
+            .await
+            .expect("Query failed");
+        seen += page.items.len();
+        request = page.next;
+    }
+    assert_eq!(seen, 25);
+}
+
 #[tokio::test]
 #[ignore = "requires Oracle database"]
 async fn test_pool_config_ext_trait() {